@@ -15,6 +15,14 @@ fn criterion_benchmark(c: &mut Criterion) {
         })
     });
 
+    c.bench_function("build_index [parallel]", |b| {
+        b.iter(|| {
+            let file = File::open("resources/fatty_lipsum_lf").unwrap();
+            let mut reader = EasyReader::new(file).unwrap();
+            reader.build_index_parallel(4).unwrap();
+        })
+    });
+
     let file = File::open("resources/fatty_lipsum_lf").unwrap();
     let mut reader = EasyReader::new(file).unwrap();
     reader.build_index().unwrap();
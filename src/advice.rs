@@ -0,0 +1,121 @@
+use crate::EasyReader;
+use std::{fs::File, io};
+
+/// How the file is about to be read, hinted to the kernel with `EasyReader::advise()`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AccessPattern {
+    /// No particular pattern, the default.
+    Normal,
+    /// Read from start to end (full scans, index builds): more read-ahead.
+    Sequential,
+    /// Read at random offsets (random lines): no read-ahead wasted.
+    Random,
+    /// Read soon: loaded into the page cache in the background.
+    WillNeed,
+    /// Not read again soon: may be evicted from the page cache.
+    DontNeed,
+}
+
+impl EasyReader<File> {
+    /// Hints the kernel about how the file is about to be read (with `posix_fadvise()`), to
+    /// make the most of the page cache: e.g. `Sequential` before scanning a huge file,
+    /// `Random` before sampling random lines of it and `DontNeed` once done with it.
+    ///
+    /// A no-op on the platforms without such hints (macOS, Windows...).
+    pub fn advise(&mut self, pattern: AccessPattern) -> io::Result<&mut Self> {
+        os::advise_file(&self.file, pattern)?;
+        Ok(self)
+    }
+}
+
+impl<T: AsRef<[u8]>> EasyReader<io::Cursor<T>> {
+    /// Like `advise()`, for a file mapped in memory (with `posix_madvise()`) and read through a
+    /// `Cursor` (e.g. over a `memmap2::Mmap`). Pointless for the data which isn't mapped.
+    pub fn advise(&mut self, pattern: AccessPattern) -> io::Result<&mut Self> {
+        os::advise_memory(self.file.get_ref().as_ref(), pattern)?;
+        Ok(self)
+    }
+}
+
+#[cfg(any(target_os = "linux", target_os = "android", target_os = "freebsd"))]
+mod os {
+    use super::AccessPattern;
+    use std::{fs::File, io, os::unix::io::AsRawFd};
+
+    pub fn advise_file(file: &File, pattern: AccessPattern) -> io::Result<()> {
+        let advice = match pattern {
+            AccessPattern::Normal => libc::POSIX_FADV_NORMAL,
+            AccessPattern::Sequential => libc::POSIX_FADV_SEQUENTIAL,
+            AccessPattern::Random => libc::POSIX_FADV_RANDOM,
+            AccessPattern::WillNeed => libc::POSIX_FADV_WILLNEED,
+            AccessPattern::DontNeed => libc::POSIX_FADV_DONTNEED,
+        };
+        // The whole file, whatever its size
+        match unsafe { libc::posix_fadvise(file.as_raw_fd(), 0, 0, advice) } {
+            0 => Ok(()),
+            err => Err(io::Error::from_raw_os_error(err)),
+        }
+    }
+
+    pub use super::memory::advise_memory;
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "android", target_os = "freebsd")))]
+mod os {
+    use super::AccessPattern;
+    use std::{fs::File, io};
+
+    pub fn advise_file(_file: &File, _pattern: AccessPattern) -> io::Result<()> {
+        Ok(())
+    }
+
+    pub use super::memory::advise_memory;
+}
+
+#[cfg(any(
+    target_os = "linux",
+    target_os = "macos",
+    target_os = "ios",
+    target_os = "freebsd"
+))]
+mod memory {
+    use super::AccessPattern;
+    use std::io;
+
+    pub fn advise_memory(bytes: &[u8], pattern: AccessPattern) -> io::Result<()> {
+        if bytes.is_empty() {
+            return Ok(());
+        }
+        let advice = match pattern {
+            AccessPattern::Normal => libc::POSIX_MADV_NORMAL,
+            AccessPattern::Sequential => libc::POSIX_MADV_SEQUENTIAL,
+            AccessPattern::Random => libc::POSIX_MADV_RANDOM,
+            AccessPattern::WillNeed => libc::POSIX_MADV_WILLNEED,
+            AccessPattern::DontNeed => libc::POSIX_MADV_DONTNEED,
+        };
+        // The advice applies to whole pages, starting at a page boundary
+        let page_size = unsafe { libc::sysconf(libc::_SC_PAGESIZE) }.max(1) as usize;
+        let start = bytes.as_ptr() as usize;
+        let aligned = start - start % page_size;
+        let len = bytes.len() + (start - aligned);
+        match unsafe { libc::posix_madvise(aligned as *mut libc::c_void, len, advice) } {
+            0 => Ok(()),
+            err => Err(io::Error::from_raw_os_error(err)),
+        }
+    }
+}
+
+#[cfg(not(any(
+    target_os = "linux",
+    target_os = "macos",
+    target_os = "ios",
+    target_os = "freebsd"
+)))]
+mod memory {
+    use super::AccessPattern;
+    use std::io;
+
+    pub fn advise_memory(_bytes: &[u8], _pattern: AccessPattern) -> io::Result<()> {
+        Ok(())
+    }
+}
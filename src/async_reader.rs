@@ -0,0 +1,332 @@
+use crate::{
+    decode_line,
+    delimiter::Delimiter,
+    scan::{byte_positions, BULK_BLOCK_SIZE},
+    Cancel, LineIndex, ReaderError, LF_BYTE,
+};
+#[cfg(feature = "stream")]
+use futures_util::{stream, Stream};
+#[cfg(feature = "rand")]
+use rand::Rng;
+use std::io::{self, SeekFrom};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncSeek, AsyncSeekExt};
+
+/// The async counterpart of `EasyReader`, for tokio services navigating large files without
+/// blocking the runtime.
+///
+/// Every method is cancellation-safe: the cursor (and the index) are only updated once all the
+/// reads of an operation have succeeded, so dropping a pending future (e.g. the losing branch
+/// of a `tokio::select!`) leaves the reader as it was before the call.
+pub struct AsyncEasyReader<R> {
+    file: R,
+    file_size: u64,
+    chunk_size: usize,
+    current_start_line_offset: u64,
+    current_end_line_offset: u64,
+    at_bof: bool,
+    delimiter: Delimiter,
+    line_starts: Option<LineIndex>,
+}
+
+impl<R: AsyncRead + AsyncSeek + Unpin> AsyncEasyReader<R> {
+    pub async fn new(mut file: R) -> io::Result<Self> {
+        let file_size = file.seek(SeekFrom::End(0)).await?;
+        if file_size == 0 {
+            return Err(ReaderError::EmptyFile.into());
+        }
+
+        Ok(AsyncEasyReader {
+            file,
+            file_size,
+            chunk_size: 200,
+            current_start_line_offset: 0,
+            current_end_line_offset: 0,
+            at_bof: true,
+            delimiter: Delimiter::default(),
+            line_starts: None,
+        })
+    }
+
+    /// How many bytes are read at once while looking for a line terminator (at least 1).
+    pub fn chunk_size(&mut self, size: usize) -> &mut Self {
+        self.chunk_size = size.max(1);
+        self
+    }
+
+    /// Like `EasyReader::delimiter()`, the lines end with `byte` instead of an LF (no CR is
+    /// dropped before it). The index, if any, is dropped.
+    pub fn delimiter(&mut self, byte: u8) -> &mut Self {
+        self.delimiter = Delimiter::new(vec![byte]);
+        self.line_starts = None;
+        self
+    }
+
+    pub fn bof(&mut self) -> &mut Self {
+        self.set_cursor(0, 0, true);
+        self
+    }
+
+    pub fn eof(&mut self) -> &mut Self {
+        self.set_cursor(self.file_size, self.file_size, false);
+        self
+    }
+
+    pub async fn build_index(&mut self) -> io::Result<&mut Self> {
+        self.index_lines(None).await?;
+        Ok(self)
+    }
+
+    /// Like `build_index()`, but gives up as soon as `cancel` is cancelled.
+    pub async fn build_index_cancellable(&mut self, cancel: &Cancel) -> io::Result<&mut Self> {
+        self.index_lines(Some(cancel)).await?;
+        Ok(self)
+    }
+
+    pub async fn prev_line(&mut self) -> io::Result<Option<String>> {
+        match self.prev_offsets().await? {
+            Some((start, end)) => self.move_to(start, end).await.map(Some),
+            None => Ok(None),
+        }
+    }
+
+    pub async fn current_line(&mut self) -> io::Result<Option<String>> {
+        if self.at_bof {
+            return self.next_line().await;
+        }
+        if self.current_start_line_offset == self.file_size {
+            return self.prev_line().await;
+        }
+        let (start, end) = (self.current_start_line_offset, self.current_end_line_offset);
+        self.read_line(start, end).await.map(Some)
+    }
+
+    pub async fn next_line(&mut self) -> io::Result<Option<String>> {
+        match self.next_offsets().await? {
+            Some((start, end)) => self.move_to(start, end).await.map(Some),
+            None => Ok(None),
+        }
+    }
+
+    #[cfg(feature = "rand")]
+    pub async fn random_line(&mut self) -> io::Result<Option<String>> {
+        let (start, end) = match self.line_starts.as_ref().map(LineIndex::len) {
+            Some(lines) => match self
+                .index_entry(rand::thread_rng().gen_range(0..lines))
+                .await?
+            {
+                Some(offsets) => offsets,
+                None => return Ok(None),
+            },
+            None => {
+                let offset = rand::thread_rng().gen_range(0..self.file_size);
+                let start = self.find_backward(offset).await?.map_or(0, |lf| lf + 1);
+                (start, self.line_end(start).await?)
+            }
+        };
+        self.move_to(start, end).await.map(Some)
+    }
+
+    /// Turns the reader into a stream of the lines following the cursor.
+    ///
+    /// Like the iterators of `EasyReader`, the stream ends after the first error.
+    #[cfg(feature = "stream")]
+    pub fn into_stream(self) -> impl Stream<Item = io::Result<String>> {
+        stream::unfold(Some(self), |reader| async move {
+            let mut reader = reader?;
+            match reader.next_line().await {
+                Ok(line) => line.map(|line| (Ok(line), Some(reader))),
+                Err(err) => Some((Err(err), None)),
+            }
+        })
+    }
+
+    /// Turns the reader into a stream of the lines from the last one to the first one.
+    #[cfg(feature = "stream")]
+    pub fn into_rev_stream(mut self) -> impl Stream<Item = io::Result<String>> {
+        self.eof();
+        stream::unfold(Some(self), |reader| async move {
+            let mut reader = reader?;
+            match reader.prev_line().await {
+                Ok(line) => line.map(|line| (Ok(line), Some(reader))),
+                Err(err) => Some((Err(err), None)),
+            }
+        })
+    }
+
+    // Builds the index like `EasyReader::build_index()` does with a single-byte delimiter
+    async fn index_lines(&mut self, cancel: Option<&Cancel>) -> io::Result<()> {
+        let delimiter = self.delimiter_byte();
+        let mut line_starts = LineIndex::default();
+        line_starts.push(0);
+        let mut offset = 0;
+        while offset < self.file_size {
+            Cancel::check_opt(cancel)?;
+            let len = (self.file_size - offset).min(BULK_BLOCK_SIZE as u64) as usize;
+            let block = self.read_bytes(offset, len).await?;
+            for i in byte_positions(&block, delimiter) {
+                let start = offset + i as u64 + 1;
+                // A trailing line terminator doesn't open a new line
+                if start < self.file_size {
+                    line_starts.push(start);
+                }
+            }
+            offset += len as u64;
+        }
+        self.line_starts = Some(line_starts);
+        Ok(())
+    }
+
+    // The (start, end) offsets of the `line`-th line of the index
+    async fn index_entry(&mut self, line: usize) -> io::Result<Option<(u64, u64)>> {
+        let (start, next) = match &self.line_starts {
+            Some(line_starts) => match line_starts.get(line) {
+                Some(start) => (start, line_starts.get(line + 1).unwrap_or(self.file_size)),
+                None => return Ok(None),
+            },
+            None => return Ok(None),
+        };
+        Ok(Some((start, self.terminator_start(start, next).await?)))
+    }
+
+    async fn next_offsets(&mut self) -> io::Result<Option<(u64, u64)>> {
+        if self.line_starts.is_some() {
+            let line = match self.index_position() {
+                _ if self.at_bof => 0,
+                Some(line) => line + 1,
+                None => return Ok(None),
+            };
+            return self.index_entry(line).await;
+        }
+
+        let start = if self.at_bof {
+            0
+        } else {
+            match self.find_forward(self.current_end_line_offset).await? {
+                Some(terminator) => terminator + 1,
+                None => return Ok(None),
+            }
+        };
+        // A trailing line terminator doesn't open a new line
+        if start >= self.file_size {
+            return Ok(None);
+        }
+        Ok(Some((start, self.line_end(start).await?)))
+    }
+
+    async fn prev_offsets(&mut self) -> io::Result<Option<(u64, u64)>> {
+        if self.at_bof || self.current_start_line_offset == 0 {
+            return Ok(None);
+        }
+        let at_eof = self.current_start_line_offset == self.file_size;
+
+        if let Some(line_starts) = &self.line_starts {
+            let line = match self.index_position() {
+                _ if at_eof => line_starts.len(),
+                Some(line) => line,
+                None => return Ok(None),
+            };
+            return match line.checked_sub(1) {
+                Some(line) => self.index_entry(line).await,
+                None => Ok(None),
+            };
+        }
+
+        // The line before ends where the current one starts, its terminator included (if it
+        // has one, at the EOF)
+        let next = self.current_start_line_offset;
+        let start = self
+            .find_backward(next - 1)
+            .await?
+            .map_or(0, |terminator| terminator + 1);
+        Ok(Some((start, self.terminator_start(start, next).await?)))
+    }
+
+    fn index_position(&self) -> Option<usize> {
+        self.line_starts
+            .as_ref()
+            .and_then(|line_starts| line_starts.position(self.current_start_line_offset))
+    }
+
+    // Reads the line before moving the cursor onto it, a failed read leaves the cursor untouched
+    async fn move_to(&mut self, start: u64, end: u64) -> io::Result<String> {
+        let line = self.read_line(start, end).await?;
+        self.set_cursor(start, end, false);
+        Ok(line)
+    }
+
+    fn set_cursor(&mut self, start: u64, end: u64, at_bof: bool) {
+        self.current_start_line_offset = start;
+        self.current_end_line_offset = end;
+        self.at_bof = at_bof;
+    }
+
+    async fn read_line(&mut self, start: u64, end: u64) -> io::Result<String> {
+        let buffer = self.read_bytes(start, (end - start) as usize).await?;
+        decode_line(buffer, start, end)
+    }
+
+    // End of the line starting at `start`, line terminator excluded
+    async fn line_end(&mut self, start: u64) -> io::Result<u64> {
+        match self.find_forward(start).await? {
+            Some(terminator) => self.terminator_start(start, terminator + 1).await,
+            None => Ok(self.file_size),
+        }
+    }
+
+    // Where the terminator of the line ending at `end` starts (the line starting at `start`),
+    // the CR of a CRLF included, as `EasyReader` finds it
+    async fn terminator_start(&mut self, start: u64, end: u64) -> io::Result<u64> {
+        // The delimiter and a CR before it, the only bytes the delimiter reads
+        let from = end.saturating_sub(2).max(start);
+        let window = self.read_bytes(from, (end - from) as usize).await?;
+        self.delimiter.terminator_start(start, end, |offset, len| {
+            let i = (offset - from) as usize;
+            window
+                .get(i..i + len)
+                .map(<[u8]>::to_vec)
+                .ok_or_else(|| io::Error::other("The bytes read don't hold the terminator"))
+        })
+    }
+
+    // First delimiter at or after `from`
+    async fn find_forward(&mut self, from: u64) -> io::Result<Option<u64>> {
+        let delimiter = self.delimiter_byte();
+        let mut offset = from;
+        while offset < self.file_size {
+            let len = (self.file_size - offset).min(self.chunk_size as u64) as usize;
+            let chunk = self.read_bytes(offset, len).await?;
+            if let Some(i) = byte_positions(&chunk, delimiter).next() {
+                return Ok(Some(offset + i as u64));
+            }
+            offset += len as u64;
+        }
+        Ok(None)
+    }
+
+    // Last delimiter before `before`
+    async fn find_backward(&mut self, before: u64) -> io::Result<Option<u64>> {
+        let delimiter = self.delimiter_byte();
+        let mut offset = before;
+        while offset > 0 {
+            let len = offset.min(self.chunk_size as u64) as usize;
+            offset -= len as u64;
+            let chunk = self.read_bytes(offset, len).await?;
+            if let Some(i) = chunk.iter().rposition(|byte| *byte == delimiter) {
+                return Ok(Some(offset + i as u64));
+            }
+        }
+        Ok(None)
+    }
+
+    // Only single-byte delimiters can be set
+    fn delimiter_byte(&self) -> u8 {
+        self.delimiter.byte().unwrap_or(LF_BYTE)
+    }
+
+    async fn read_bytes(&mut self, offset: u64, bytes: usize) -> io::Result<Vec<u8>> {
+        let mut buffer = vec![0; bytes];
+        self.file.seek(SeekFrom::Start(offset)).await?;
+        self.file.read_exact(&mut buffer).await?;
+        Ok(buffer)
+    }
+}
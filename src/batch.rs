@@ -0,0 +1,326 @@
+use crate::{decode_line, EasyReader, ReadMode, SCAN_BLOCK_SIZE};
+use std::io::{self, prelude::*};
+
+// The lines closer than this to each other are read at once by read_spans()...
+#[cfg(any(feature = "rand", feature = "index"))]
+const COALESCE_GAP: u64 = 64 * 1024;
+// ...in reads of this size at most (unless a single line is longer)
+#[cfg(any(feature = "rand", feature = "index"))]
+const MAX_COALESCED_READ: u64 = 1024 * 1024;
+
+impl<R: Read + Seek> EasyReader<R> {
+    /// Reads up to `n` lines forward, like as many calls to `next_line()`, but the lines are
+    /// split out of large blocks: a single read usually covers all of them. The cursor ends on
+    /// the last line returned (and doesn't move if there's none).
+    ///
+    /// The lines which `iter()` reads one by one (with `comment_prefix()`, `continuation()`...)
+    /// are read one by one here too, so the batches hold the same lines as `next_line()` reads.
+    pub fn next_lines(&mut self, n: usize) -> io::Result<Vec<String>> {
+        let (mut lines, mut offsets) = (Vec::new(), Vec::new());
+        self.read_ahead(n, &mut lines, &mut offsets)?;
+        if let Some(&(start, end)) = offsets.last() {
+            self.set_current_line(start, end);
+        }
+        Ok(lines)
+    }
+
+    // Reads up to `n` lines following the cursor like next_lines() into `lines`, and their
+    // (start, end) offsets into `offsets`, without moving the cursor. The lines read before an
+    // error are kept.
+    pub(crate) fn read_ahead(
+        &mut self,
+        n: usize,
+        lines: &mut Vec<String>,
+        offsets: &mut Vec<(u64, u64)>,
+    ) -> io::Result<()> {
+        if !self.can_read_ahead() {
+            return self.read_one_by_one(true, n, lines, offsets);
+        }
+        if self.pending_offset.is_some() {
+            self.resolve_offset(&ReadMode::Next)?;
+        }
+        if n == 0 || (!self.at_bof && self.current_end_line_offset == self.file_size) {
+            return Ok(());
+        }
+        // Out of the BOF the first terminator met is the one of the current line
+        let (pos, skip_terminator) = (self.current_end_line_offset, !self.at_bof);
+        self.lines_from(pos, skip_terminator, n, lines, offsets)
+    }
+
+    /// Reads up to `n` lines backward, like as many calls to `prev_line()` (so the closest
+    /// line comes first), with block reads like `next_lines()`. The cursor ends on the last
+    /// line returned (and doesn't move if there's none).
+    pub fn prev_lines(&mut self, n: usize) -> io::Result<Vec<String>> {
+        let (mut lines, mut offsets) = (Vec::new(), Vec::new());
+        self.read_behind(n, &mut lines, &mut offsets)?;
+        if let Some(&(start, end)) = offsets.last() {
+            self.set_current_line(start, end);
+        }
+        Ok(lines)
+    }
+
+    // Reads up to `n` lines preceding the cursor like prev_lines() into `lines`, and their
+    // (start, end) offsets into `offsets`, without moving the cursor
+    pub(crate) fn read_behind(
+        &mut self,
+        n: usize,
+        lines: &mut Vec<String>,
+        offsets: &mut Vec<(u64, u64)>,
+    ) -> io::Result<()> {
+        if !self.can_read_ahead() {
+            return self.read_one_by_one(false, n, lines, offsets);
+        }
+        if self.pending_offset.is_some() {
+            self.resolve_offset(&ReadMode::Prev)?;
+        }
+        if n == 0 || self.at_bof {
+            return Ok(());
+        }
+
+        let mut pos = self.current_start_line_offset;
+        let mut first_block = true;
+        // Only the last line of the file may have no terminator
+        let mut terminated = true;
+        let cr = self.delimiter.cr();
+        let delimiter_len = self.delimiter.len();
+        // The bytes from `pos` to the end of the line being read, not split into lines yet
+        let mut buffer = Vec::new();
+        while lines.len() < n && pos > 0 {
+            let len = pos.min(SCAN_BLOCK_SIZE as u64);
+            let from = pos - len;
+            let mut block = self.read_bytes(from, len as usize)?;
+            block.append(&mut buffer);
+            buffer = block;
+            pos = from;
+
+            // The terminator before the cursor (or the trailing one of the file at the EOF)
+            if first_block {
+                first_block = false;
+                if self.ends_delimiter(&buffer, pos, buffer.len() - 1)? {
+                    buffer.truncate(buffer.len() - delimiter_len);
+                } else {
+                    terminated = false;
+                }
+            }
+
+            // The bytes after the new block were already scanned
+            let mut i = (len as usize).min(buffer.len());
+            while lines.len() < n && i > 0 {
+                i -= 1;
+                if !self.ends_delimiter(&buffer, pos, i)? {
+                    continue;
+                }
+                let line = buffer.split_off(i + 1);
+                buffer.truncate(i + 1 - delimiter_len);
+                i = buffer.len();
+                let start = pos + (i + delimiter_len) as u64;
+                offsets.push(self.push_line(lines, line, start, cr.filter(|_| terminated))?);
+                terminated = true;
+            }
+            // The line still to read doesn't start in the bytes read so far
+            if lines.len() < n {
+                let cr_len = cr.map_or(0, <[u8]>::len);
+                self.check_line_length(pos, buffer.len().saturating_sub(cr_len) as u64)?;
+            }
+        }
+        // The first line of the file has no terminator before it
+        if lines.len() < n && pos == 0 && !first_block {
+            offsets.push(self.push_line(lines, buffer, 0, cr.filter(|_| terminated))?);
+        }
+        Ok(())
+    }
+
+    // Reads up to `n` lines like read_ahead() (or read_behind() if not `forward`) with as many
+    // calls to next_line() (or prev_line()), for the lines which can't be split out of blocks
+    // (see `iter()`). The offsets are those of the line the cursor is left on by each call.
+    fn read_one_by_one(
+        &mut self,
+        forward: bool,
+        n: usize,
+        lines: &mut Vec<String>,
+        offsets: &mut Vec<(u64, u64)>,
+    ) -> io::Result<()> {
+        let (cursor, pending_offset) = (self.save_cursor(), self.pending_offset);
+        let read = (|| {
+            while lines.len() < n {
+                let line = if forward {
+                    self.next_line()?
+                } else {
+                    self.prev_line()?
+                };
+                match line {
+                    Some(line) => lines.push(line),
+                    None => break,
+                }
+                offsets.push(self.current_offsets());
+            }
+            Ok(())
+        })();
+        self.restore_cursor(cursor);
+        self.pending_offset = pending_offset;
+        read
+    }
+
+    /// The first `n` lines of the file (or all of them if there are fewer), without moving the
+    /// cursor.
+    pub fn head(&mut self, n: usize) -> io::Result<Vec<String>> {
+        let mut lines = Vec::new();
+        if !self.can_read_ahead() {
+            let cursor = self.save_cursor();
+            self.bof();
+            let read = self.read_one_by_one(true, n, &mut lines, &mut Vec::new());
+            self.restore_cursor(cursor);
+            return read.map(|_| lines);
+        }
+        self.lines_from(0, false, n, &mut lines, &mut Vec::new())?;
+        Ok(lines)
+    }
+
+    /// The last `n` lines of the file (or all of them if there are fewer) in their order,
+    /// without moving the cursor. With an index the first of them is found right away,
+    /// otherwise by counting the line terminators backwards from the EOF, block by block: the
+    /// lines are only split and decoded once their start is known.
+    pub fn tail(&mut self, n: usize) -> io::Result<Vec<String>> {
+        let mut lines = Vec::new();
+        if n == 0 {
+            return Ok(lines);
+        }
+        if !self.can_read_ahead() {
+            let cursor = self.save_cursor();
+            self.eof();
+            let read = self.read_one_by_one(false, n, &mut lines, &mut Vec::new());
+            self.restore_cursor(cursor);
+            lines.reverse();
+            return read.map(|_| lines);
+        }
+
+        let start = if let Some(line_starts) = self.index() {
+            line_starts.get(line_starts.len().saturating_sub(n))
+        } else {
+            let cursor = self.save_cursor();
+            self.eof();
+            let start = self.skip_backward(n as u64);
+            self.restore_cursor(cursor);
+            start?
+        };
+        // Fewer lines than asked for
+        self.lines_from(start.unwrap_or(0), false, n, &mut lines, &mut Vec::new())?;
+        Ok(lines)
+    }
+
+    // Reads up to `n` lines from `pos` forward (after the first terminator met if
+    // `skip_terminator`) into `lines`, and their (start, end) offsets into `offsets`
+    pub(crate) fn lines_from(
+        &mut self,
+        mut pos: u64,
+        mut skip_terminator: bool,
+        n: usize,
+        lines: &mut Vec<String>,
+        offsets: &mut Vec<(u64, u64)>,
+    ) -> io::Result<()> {
+        let cr = self.delimiter.cr();
+        let delimiter_len = self.delimiter.len();
+        // The bytes from `buffer_start` to `pos`, not split into lines yet
+        let mut buffer = Vec::new();
+        let mut buffer_start = pos;
+        while lines.len() < n && pos < self.file_size {
+            let len = (self.file_size - pos).min(SCAN_BLOCK_SIZE as u64) as usize;
+            // The bytes before the new block were already scanned
+            let mut i = buffer.len();
+            buffer.extend_from_slice(&self.read_bytes(pos, len)?);
+            pos += len as u64;
+
+            let mut consumed = 0;
+            while lines.len() < n && i < buffer.len() {
+                if self.ends_delimiter(&buffer, buffer_start, i)? {
+                    if skip_terminator {
+                        skip_terminator = false;
+                    } else {
+                        let line = buffer[consumed..i + 1 - delimiter_len].to_vec();
+                        let start = buffer_start + consumed as u64;
+                        offsets.push(self.push_line(lines, line, start, cr)?);
+                    }
+                    consumed = i + 1;
+                }
+                i += 1;
+            }
+            buffer.drain(..consumed);
+            buffer_start += consumed as u64;
+            // The line still to read doesn't end in the bytes read so far (but a CR may be part
+            // of its terminator)
+            if lines.len() < n {
+                let cr_len = cr.map_or(0, <[u8]>::len);
+                self.check_line_length(buffer_start, buffer.len().saturating_sub(cr_len) as u64)?;
+            }
+        }
+        // The last line of the file may have no terminator
+        if lines.len() < n && !skip_terminator && buffer_start < self.file_size {
+            offsets.push(self.push_line(lines, buffer, buffer_start, None)?);
+        }
+        Ok(())
+    }
+
+    // Reads the lines at the (sorted) `spans`, including their terminator if `terminated`, the
+    // spans close to each other at once
+    #[cfg(any(feature = "rand", feature = "index"))]
+    pub(crate) fn read_spans(
+        &mut self,
+        spans: &[(u64, u64)],
+        terminated: bool,
+    ) -> io::Result<Vec<String>> {
+        let mut lines = Vec::with_capacity(spans.len());
+        let mut i = 0;
+        while i < spans.len() {
+            let (from, mut to) = spans[i];
+            let mut j = i + 1;
+            while j < spans.len()
+                && spans[j].0 <= to + COALESCE_GAP
+                && spans[j].1 - from <= MAX_COALESCED_READ
+            {
+                to = to.max(spans[j].1);
+                j += 1;
+            }
+            let block = self.read_bytes(from, (to - from) as usize)?;
+            for &(start, stop) in &spans[i..j] {
+                let end = if terminated {
+                    self.terminator_start_in(start, stop, &block, from)?
+                } else {
+                    stop
+                };
+                self.check_line_length(start, end - start)?;
+                let bytes = block[(start - from) as usize..(end - from) as usize].to_vec();
+                lines.push(self.decode(bytes, start, end)?);
+                self.stats.lines_decoded += 1;
+            }
+            i = j;
+        }
+        Ok(lines)
+    }
+
+    // Decodes the line starting at `start` into `lines`, dropping the `cr` of a CRLF terminator
+    // if the line ended with an LF (unless the terminators are kept), and returns its (start,
+    // end) offsets
+    fn push_line(
+        &mut self,
+        lines: &mut Vec<String>,
+        mut line: Vec<u8>,
+        start: u64,
+        cr: Option<&[u8]>,
+    ) -> io::Result<(u64, u64)> {
+        if let Some(cr) = cr.filter(|cr| line.ends_with(cr)) {
+            line.truncate(line.len() - cr.len());
+        }
+        let end = start + line.len() as u64;
+        self.check_line_length(start, end - start)?;
+        if self.keep_terminators {
+            // Transcoded with its terminator and not trimmed, like by read_line_bytes()
+            line.append(&mut self.terminator_after(end)?);
+            self.transcode(&mut line, 0, start, end)?;
+            lines.push(decode_line(line, start, end)?);
+        } else {
+            lines.push(self.decode(line, start, end)?);
+        }
+        self.stats.lines_decoded += 1;
+        Ok((start, end))
+    }
+}
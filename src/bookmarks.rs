@@ -0,0 +1,103 @@
+use crate::EasyReader;
+use std::{
+    fs,
+    io::{self, prelude::*, Error, ErrorKind},
+    path::Path,
+};
+
+const BOOKMARKS_HEADER: &str = "easy_reader bookmarks v1";
+
+impl<R: Read + Seek> EasyReader<R> {
+    /// Remembers the current line under `name`, replacing any bookmark with the same name.
+    pub fn bookmark(&mut self, name: &str) -> &mut Self {
+        self.bookmarks.insert(
+            name.to_string(),
+            (self.current_start_line_offset, self.current_end_line_offset),
+        );
+        self
+    }
+
+    /// Moves to the line bookmarked as `name` and returns it.
+    pub fn goto_bookmark(&mut self, name: &str) -> io::Result<Option<String>> {
+        match self.bookmarks.get(name) {
+            Some(&(start, end)) => {
+                self.set_current_line(start, end);
+                self.current_line()
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// Forgets the bookmark `name`, returning whether there was one.
+    pub fn remove_bookmark(&mut self, name: &str) -> bool {
+        self.bookmarks.remove(name).is_some()
+    }
+
+    /// The names of the bookmarks, in alphabetical order.
+    pub fn bookmarks(&self) -> impl Iterator<Item = &str> {
+        self.bookmarks.keys().map(String::as_str)
+    }
+
+    /// Writes the bookmarks to `path`, along with the size and a fingerprint of the file.
+    pub fn save_bookmarks<P: AsRef<Path>>(&mut self, path: P) -> io::Result<()> {
+        let fingerprint = self.fingerprint()?;
+        let mut content = format!(
+            "{}\n{} {:016x}\n",
+            BOOKMARKS_HEADER, self.file_size, fingerprint
+        );
+        for (name, (start, end)) in &self.bookmarks {
+            if name.contains('\n') {
+                return Err(Error::new(
+                    ErrorKind::InvalidInput,
+                    format!("The bookmark name {:?} contains a line terminator", name),
+                ));
+            }
+            content.push_str(&format!("{} {} {}\n", start, end, name));
+        }
+        fs::write(path, content)
+    }
+
+    /// Loads the bookmarks saved by `save_bookmarks`, merging them with the current ones.
+    ///
+    /// Fails with `ErrorKind::InvalidData` if the file has changed since the bookmarks were saved.
+    /// Returns the number of bookmarks loaded.
+    pub fn load_bookmarks<P: AsRef<Path>>(&mut self, path: P) -> io::Result<usize> {
+        let content = fs::read_to_string(path)?;
+        let invalid = || Error::new(ErrorKind::InvalidData, "Malformed bookmarks file");
+
+        let mut lines = content.lines();
+        if lines.next() != Some(BOOKMARKS_HEADER) {
+            return Err(invalid());
+        }
+
+        let fingerprint = self.fingerprint()?;
+        let expected = format!("{} {:016x}", self.file_size, fingerprint);
+        if lines.next() != Some(expected.as_str()) {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                "The bookmarks were saved for a different version of the file",
+            ));
+        }
+
+        let mut bookmarks = Vec::new();
+        for line in lines {
+            let mut fields = line.splitn(3, ' ');
+            let mut offset = || -> io::Result<u64> {
+                fields
+                    .next()
+                    .and_then(|f| f.parse().ok())
+                    .ok_or_else(invalid)
+            };
+            let (start, end) = (offset()?, offset()?);
+            let name = fields.next().ok_or_else(invalid)?;
+            if start > end || end > self.file_size {
+                return Err(invalid());
+            }
+            bookmarks.push((name.to_string(), (start, end)));
+        }
+
+        let loaded = bookmarks.len();
+        self.bookmarks.extend(bookmarks);
+        Ok(loaded)
+    }
+}
@@ -0,0 +1,53 @@
+use crate::EasyReader;
+use std::{
+    io::{self, prelude::*},
+    mem,
+};
+
+// The start offset of every line
+const INDEX_BYTES_PER_LINE: usize = mem::size_of::<u64>();
+const SPARSE_INDEX_BYTES_PER_ENTRY: usize = mem::size_of::<u64>();
+
+/// How the lines of a reader are currently located.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum IndexStrategy {
+    /// Every line is indexed.
+    Full,
+    /// One line every `every` lines is indexed.
+    Sparse { every: usize },
+    /// No index, line terminators are searched around the cursor.
+    None,
+}
+
+impl<R: Read + Seek> EasyReader<R> {
+    /// Builds the most complete index expected to fit within `bytes` of memory: a full one if
+    /// possible, a sparse one (see `build_index_every()`) otherwise.
+    ///
+    /// The decision is based on an estimate of the number of lines: if it turns out to be
+    /// wrong, the build is abandoned as soon as the budget is exceeded, falling back to the
+    /// next strategy. `index_strategy()` tells which strategy has been picked.
+    pub fn with_memory_budget(&mut self, bytes: usize) -> io::Result<&mut Self> {
+        self.drop_index();
+        let estimate = self.estimate_line_count()?;
+
+        let max_lines = bytes / INDEX_BYTES_PER_LINE;
+        if estimate.lines <= max_lines as u64 && self.index_lines(Some(max_lines), None)? {
+            return Ok(self);
+        }
+
+        let max_entries = bytes / SPARSE_INDEX_BYTES_PER_ENTRY;
+        if max_entries > 0 {
+            let every = estimate.high / max_entries as u64 + 1;
+            self.index_sparse(every as usize, Some(max_entries))?;
+        }
+        Ok(self)
+    }
+
+    pub fn index_strategy(&self) -> IndexStrategy {
+        match self.sparse_every() {
+            _ if self.indexed => IndexStrategy::Full,
+            Some(every) => IndexStrategy::Sparse { every },
+            None => IndexStrategy::None,
+        }
+    }
+}
@@ -0,0 +1,23 @@
+use crate::{EasyReader, ReadMode};
+use bytes::Bytes;
+use std::io::{self, prelude::*};
+
+// Shorthands for the *_line_as::<Bytes>() methods
+impl<R: Read + Seek> EasyReader<R> {
+    pub fn prev_line_bytes(&mut self) -> io::Result<Option<Bytes>> {
+        self.read_line_as(ReadMode::Prev)
+    }
+
+    pub fn current_line_bytes(&mut self) -> io::Result<Option<Bytes>> {
+        self.read_line_as(ReadMode::Current)
+    }
+
+    pub fn next_line_bytes(&mut self) -> io::Result<Option<Bytes>> {
+        self.read_line_as(ReadMode::Next)
+    }
+
+    #[cfg(feature = "rand")]
+    pub fn random_line_bytes(&mut self) -> io::Result<Option<Bytes>> {
+        self.read_line_as(ReadMode::Random)
+    }
+}
@@ -0,0 +1,117 @@
+use crate::{read_bytes_into, EasyReader};
+use std::{
+    collections::VecDeque,
+    io::{self, prelude::*},
+};
+
+// The file is cached in aligned blocks of this size
+const CACHE_BLOCK_SIZE: usize = 8 * 1024;
+// Larger reads (scans...) go straight to the file, not to evict the blocks being navigated
+const MAX_CACHED_READ: usize = 4 * CACHE_BLOCK_SIZE;
+
+// The blocks of the file read last, the most recently used first
+pub(crate) struct ChunkCache {
+    blocks: VecDeque<(u64, Vec<u8>)>,
+    capacity: usize,
+}
+
+impl ChunkCache {
+    fn block(&mut self, offset: u64) -> Option<&[u8]> {
+        let i = self.blocks.iter().position(|(start, _)| *start == offset)?;
+        let block = self.blocks.remove(i)?;
+        self.blocks.push_front(block);
+        self.blocks.front().map(|(_, bytes)| &bytes[..])
+    }
+
+    // A buffer for a new block: the one of the least recently used block once the cache is
+    // full
+    fn spare_block(&mut self) -> Vec<u8> {
+        if self.blocks.len() < self.capacity {
+            return Vec::with_capacity(CACHE_BLOCK_SIZE);
+        }
+        let mut bytes = self
+            .blocks
+            .pop_back()
+            .map(|(_, bytes)| bytes)
+            .unwrap_or_default();
+        bytes.clear();
+        bytes
+    }
+}
+
+impl<R: Read + Seek> EasyReader<R> {
+    /// Keeps the last `blocks` blocks (of 8 KiB) of the file read in memory: moving back and
+    /// forth around the same lines, locating them and reading them then hits the memory
+    /// instead of the storage. Disabled by default, `0` disables it again.
+    ///
+    /// The bytes of the file aren't expected to change in place: `refresh()` and `reopen()`
+    /// empty the cache.
+    pub fn chunk_cache(&mut self, blocks: usize) -> &mut Self {
+        self.chunk_cache = Some(ChunkCache {
+            blocks: VecDeque::with_capacity(blocks),
+            capacity: blocks,
+        })
+        .filter(|_| blocks > 0);
+        self
+    }
+
+    pub(crate) fn clear_chunk_cache(&mut self) {
+        if let Some(cache) = self.chunk_cache.as_mut() {
+            cache.blocks.clear();
+        }
+    }
+
+    pub(crate) fn chunk_cache_capacity(&self) -> usize {
+        self.chunk_cache.as_ref().map_or(0, |cache| cache.capacity)
+    }
+
+    // Appends `bytes` bytes read from `offset` to `buffer`, zeros past the EOF, through the
+    // cache if there's one
+    pub(crate) fn read_into(
+        &mut self,
+        offset: u64,
+        bytes: usize,
+        buffer: &mut Vec<u8>,
+    ) -> io::Result<()> {
+        let (file, stats) = (&mut self.file, &mut self.stats);
+        // Past the header, if any
+        let (base, file_size) = (self.header_size, self.header_size + self.file_size);
+        let cache = match self.chunk_cache.as_mut() {
+            Some(cache) if bytes <= MAX_CACHED_READ => cache,
+            _ => return read_bytes_into(file, base + offset, bytes, file_size, buffer, stats),
+        };
+
+        let end = offset + bytes as u64;
+        let mut pos = offset;
+        buffer.reserve(bytes);
+        while pos < end {
+            let block_start = pos - pos % CACHE_BLOCK_SIZE as u64;
+            let block = match cache.block(block_start) {
+                Some(block) => {
+                    stats.cache_hits += 1;
+                    block
+                }
+                None => {
+                    stats.cache_misses += 1;
+                    let mut block = cache.spare_block();
+                    read_bytes_into(
+                        file,
+                        base + block_start,
+                        CACHE_BLOCK_SIZE,
+                        file_size,
+                        &mut block,
+                        stats,
+                    )?;
+                    cache.blocks.push_front((block_start, block));
+                    &cache.blocks[0].1
+                }
+            };
+            let block_end = (block_start + CACHE_BLOCK_SIZE as u64).min(end);
+            buffer.extend_from_slice(
+                &block[(pos - block_start) as usize..(block_end - block_start) as usize],
+            );
+            pos = block_end;
+        }
+        Ok(())
+    }
+}
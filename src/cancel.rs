@@ -0,0 +1,45 @@
+use crate::ReaderError;
+use std::{
+    io,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+};
+
+/// A token to cancel a long operation (e.g. `EasyReader::build_index_cancellable()` or
+/// `EasyReader::next_line_matching_cancellable()`) from another thread or task.
+///
+/// Clones share the same state: cancelling any of them cancels them all. A cancelled
+/// operation fails with a `ReaderError::Cancelled` error without moving the cursor, so it can
+/// be run again with a fresh token.
+#[derive(Clone, Debug, Default)]
+pub struct Cancel {
+    cancelled: Arc<AtomicBool>,
+}
+
+impl Cancel {
+    pub fn new() -> Self {
+        Cancel::default()
+    }
+
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+
+    pub(crate) fn check(&self) -> io::Result<()> {
+        if self.is_cancelled() {
+            return Err(ReaderError::Cancelled.into());
+        }
+        Ok(())
+    }
+
+    // Checks `cancel`, if any
+    pub(crate) fn check_opt(cancel: Option<&Cancel>) -> io::Result<()> {
+        cancel.map_or(Ok(()), Cancel::check)
+    }
+}
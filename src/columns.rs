@@ -0,0 +1,58 @@
+use crate::EasyReader;
+use std::{
+    io::{self, prelude::*},
+    ops::{Bound, RangeBounds},
+};
+#[cfg(feature = "unicode-segmentation")]
+use unicode_segmentation::UnicodeSegmentation;
+
+impl<R: Read + Seek> EasyReader<R> {
+    /// Returns the chars of the current line within the given column range.
+    ///
+    /// Columns past the end of the line are ignored, so scrolling horizontally beyond it yields an empty string.
+    pub fn current_line_chars<B: RangeBounds<usize>>(
+        &mut self,
+        columns: B,
+    ) -> io::Result<Option<String>> {
+        Ok(self.current_line()?.map(|line| {
+            let boundaries = line.char_indices().map(|(i, _)| i);
+            slice_columns(&line, boundaries, columns).to_string()
+        }))
+    }
+
+    /// Returns the extended grapheme clusters of the current line within the given column range.
+    #[cfg(feature = "unicode-segmentation")]
+    pub fn current_line_graphemes<B: RangeBounds<usize>>(
+        &mut self,
+        columns: B,
+    ) -> io::Result<Option<String>> {
+        Ok(self.current_line()?.map(|line| {
+            let boundaries = line.grapheme_indices(true).map(|(i, _)| i);
+            slice_columns(&line, boundaries, columns).to_string()
+        }))
+    }
+}
+
+fn slice_columns<B: RangeBounds<usize>>(
+    line: &str,
+    boundaries: impl Iterator<Item = usize>,
+    columns: B,
+) -> &str {
+    let boundaries: Vec<usize> = boundaries.chain(Some(line.len())).collect();
+    let last = boundaries.len() - 1;
+
+    let from = match columns.start_bound() {
+        Bound::Included(&c) => c,
+        Bound::Excluded(&c) => c.saturating_add(1),
+        Bound::Unbounded => 0,
+    }
+    .min(last);
+    let to = match columns.end_bound() {
+        Bound::Included(&c) => c.saturating_add(1),
+        Bound::Excluded(&c) => c,
+        Bound::Unbounded => last,
+    }
+    .clamp(from, last);
+
+    &line[boundaries[from]..boundaries[to]]
+}
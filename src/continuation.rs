@@ -0,0 +1,149 @@
+use crate::{EasyReader, ReadMode};
+use std::io::{self, prelude::*};
+
+impl<R: Read + Seek> EasyReader<R> {
+    /// Joins the lines ending with `marker` (e.g. `"\\"` in shell scripts and config files) to
+    /// the following ones: the reads return logical lines, the markers dropped, in both
+    /// directions. The cursor stays on one of their lines. An empty `marker` turns the joining
+    /// off. `comment_prefix()` and `skip_blank_lines()` then ignore logical lines.
+    ///
+    /// Only the line reads join lines: offsets, the index and the line counts are about the
+    /// lines of the file.
+    pub fn continuation(&mut self, marker: &str) -> &mut Self {
+        self.continuation = Some(marker.as_bytes().to_vec()).filter(|marker| !marker.is_empty());
+        self
+    }
+
+    // Reads the logical line in `mode`, returning its bytes and the (start, end) offsets of
+    // its lines. The ignored lines (see `comment_prefix()`) are the logical lines ignored,
+    // moved past like navigate_unignored() moves past the lines.
+    pub(crate) fn read_logical_bytes(
+        &mut self,
+        mut mode: ReadMode,
+        marker: &[u8],
+    ) -> io::Result<Option<(Vec<u8>, u64, u64)>> {
+        if !self.ignores_lines() {
+            return self.read_logical_line(mode, marker);
+        }
+        let (cursor, pending_offset) = (self.save_cursor(), self.pending_offset);
+        // Forward then backward from a line read in no direction (the current one...)
+        let mut around = false;
+        loop {
+            match self.read_logical_line(mode.clone(), marker)? {
+                Some(line) if !self.is_ignored(&line.0) => return Ok(Some(line)),
+                Some(_) => (),
+                None if around && mode == ReadMode::Next => {
+                    mode = ReadMode::Prev;
+                    continue;
+                }
+                None => {
+                    self.restore_cursor(cursor);
+                    self.pending_offset = pending_offset;
+                    return Ok(None);
+                }
+            }
+            mode = match mode {
+                ReadMode::Prev => ReadMode::Prev,
+                ReadMode::Next => ReadMode::Next,
+                _ => {
+                    around = true;
+                    ReadMode::Next
+                }
+            };
+        }
+    }
+
+    // Reads the logical line in `mode`, whether it's ignored or not
+    fn read_logical_line(
+        &mut self,
+        mode: ReadMode,
+        marker: &[u8],
+    ) -> io::Result<Option<(Vec<u8>, u64, u64)>> {
+        // Out of a line, there's no logical line to leave
+        let on_line = !self.at_bof
+            && self.pending_offset.is_none()
+            && self.current_start_line_offset < self.file_size;
+
+        match mode {
+            ReadMode::Next => {
+                if on_line {
+                    while self.read_current_bytes()?.ends_with(marker) {
+                        if !self.move_cursor(ReadMode::Next)? {
+                            return Ok(None);
+                        }
+                    }
+                }
+                if !self.navigate_physical(mode)? {
+                    return Ok(None);
+                }
+                self.join_forward(marker).map(Some)
+            }
+            ReadMode::Prev => {
+                if on_line {
+                    self.rewind_continued(marker)?;
+                }
+                if !self.navigate_physical(mode)? {
+                    return Ok(None);
+                }
+                self.rewind_continued(marker)?;
+                let cursor = self.save_cursor();
+                let line = self.join_forward(marker);
+                self.restore_cursor(cursor);
+                line.map(Some)
+            }
+            _ => {
+                if !self.navigate_physical(mode)? {
+                    return Ok(None);
+                }
+                let cursor = self.save_cursor();
+                let line = self
+                    .rewind_continued(marker)
+                    .and_then(|_| self.join_forward(marker));
+                self.restore_cursor(cursor);
+                line.map(Some)
+            }
+        }
+    }
+
+    // Moves the cursor like navigate(), onto the ignored lines too: only the logical lines
+    // are ignored
+    fn navigate_physical(&mut self, mode: ReadMode) -> io::Result<bool> {
+        self.prepare_move(&mode)?;
+        self.step(mode)
+    }
+
+    // Reads from the current line to the last one it continues into, where the cursor ends.
+    // Each line continues into the next one if it ends with `marker` itself, as in
+    // rewind_continued().
+    fn join_forward(&mut self, marker: &[u8]) -> io::Result<(Vec<u8>, u64, u64)> {
+        let start = self.current_start_line_offset;
+        let mut bytes = Vec::new();
+        let mut line = self.read_current_bytes()?;
+        while line.ends_with(marker) {
+            line.truncate(line.len() - marker.len());
+            bytes.append(&mut line);
+            // The last line of the file has nothing to continue into
+            if !self.move_cursor(ReadMode::Next)? {
+                break;
+            }
+            line = self.read_current_bytes()?;
+            self.check_line_length(start, (bytes.len() + line.len()) as u64)?;
+        }
+        bytes.append(&mut line);
+        Ok((bytes, start, self.current_end_line_offset))
+    }
+
+    // Moves back to the first line of the logical line the cursor is on
+    fn rewind_continued(&mut self, marker: &[u8]) -> io::Result<()> {
+        loop {
+            let cursor = self.save_cursor();
+            if !self.move_cursor(ReadMode::Prev)? {
+                return Ok(());
+            }
+            if !self.read_current_bytes()?.ends_with(marker) {
+                self.restore_cursor(cursor);
+                return Ok(());
+            }
+        }
+    }
+}
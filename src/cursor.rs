@@ -0,0 +1,257 @@
+use crate::{EasyReader, Position};
+#[cfg(feature = "rand")]
+use rand::Rng;
+use std::io::{self, prelude::*};
+
+/// Bidirectional navigation through lines, independently of where they're stored.
+///
+/// [`Viewport`](crate::Viewport) and [`Pager`](crate::Pager) are built on it, and the searches
+/// (`next_line_matching()`...) and `sample()` are written against it: they work with any
+/// backend, an `EasyReader` over a file (or over a view of it, see `view_lines()`) or a
+/// user-provided line store (a database, an in-memory buffer...). `EasyReader` overrides them
+/// with its block reads. Like with `EasyReader`, the cursor starts before the first line.
+///
+/// Out of scope for now:
+/// - `AsyncEasyReader` doesn't implement it: the reads of the trait block, while its own are
+///   `async`. An async counterpart of the trait would be needed.
+/// - There's no reader chaining several files, so no implementation for one either. A
+///   user-provided one can implement the trait like any other line store.
+pub trait LineCursor {
+    /// Where the cursor is, as returned by `position()` and accepted by `goto()`.
+    type Position: Clone;
+
+    fn next_line(&mut self) -> io::Result<Option<String>>;
+
+    fn prev_line(&mut self) -> io::Result<Option<String>>;
+
+    fn current_line(&mut self) -> io::Result<Option<String>>;
+
+    /// Moves the cursor before the first line.
+    fn bof(&mut self);
+
+    /// Moves the cursor after the last line.
+    fn eof(&mut self);
+
+    /// Where the cursor is, on a line or before the first one or after the last one.
+    fn position(&self) -> Self::Position;
+
+    /// Moves the cursor back to a position returned by `position()`.
+    fn goto(&mut self, position: &Self::Position) -> io::Result<()>;
+
+    /// Reads up to `n` lines forward, like as many calls to `next_line()`: the cursor ends on
+    /// the last line returned.
+    fn next_lines(&mut self, n: usize) -> io::Result<Vec<String>> {
+        let mut lines = Vec::new();
+        while lines.len() < n {
+            match self.next_line()? {
+                Some(line) => lines.push(line),
+                None => break,
+            }
+        }
+        Ok(lines)
+    }
+
+    /// Reads up to `n` lines backward, like as many calls to `prev_line()` (so the closest
+    /// line comes first).
+    fn prev_lines(&mut self, n: usize) -> io::Result<Vec<String>> {
+        let mut lines = Vec::new();
+        while lines.len() < n {
+            match self.prev_line()? {
+                Some(line) => lines.push(line),
+                None => break,
+            }
+        }
+        Ok(lines)
+    }
+
+    /// Moves to the first line after the cursor for which `predicate` is true and returns it.
+    /// Returns `None`, without moving the cursor, if there's none.
+    fn next_line_matching(
+        &mut self,
+        predicate: &mut dyn FnMut(&str) -> bool,
+    ) -> io::Result<Option<String>> {
+        search(self, true, predicate)
+    }
+
+    /// Like `next_line_matching()`, but moving backward from the cursor.
+    fn prev_line_matching(
+        &mut self,
+        predicate: &mut dyn FnMut(&str) -> bool,
+    ) -> io::Result<Option<String>> {
+        search(self, false, predicate)
+    }
+
+    /// `k` distinct random lines (all the lines if there are fewer), in no particular order,
+    /// without moving the cursor. All the lines are read once, keeping a reservoir of `k`
+    /// lines.
+    #[cfg(feature = "rand")]
+    fn sample(&mut self, k: usize) -> io::Result<Vec<String>> {
+        let position = self.position();
+        self.bof();
+        let sample = reservoir(self, k);
+        self.goto(&position)?;
+        sample
+    }
+}
+
+fn search<C: LineCursor + ?Sized>(
+    cursor: &mut C,
+    forward: bool,
+    predicate: &mut dyn FnMut(&str) -> bool,
+) -> io::Result<Option<String>> {
+    let position = cursor.position();
+    loop {
+        let line = match forward {
+            true => cursor.next_line()?,
+            false => cursor.prev_line()?,
+        };
+        match line {
+            Some(line) if predicate(&line) => return Ok(Some(line)),
+            Some(_) => (),
+            None => break,
+        }
+    }
+    cursor.goto(&position)?;
+    Ok(None)
+}
+
+#[cfg(feature = "rand")]
+fn reservoir<C: LineCursor + ?Sized>(cursor: &mut C, k: usize) -> io::Result<Vec<String>> {
+    let mut sample = Vec::with_capacity(k);
+    if k == 0 {
+        return Ok(sample);
+    }
+    let mut rng = rand::thread_rng();
+    let mut seen = 0;
+    while let Some(line) = cursor.next_line()? {
+        if sample.len() < k {
+            sample.push(line);
+        } else {
+            let i = rng.gen_range(0..=seen);
+            if i < k {
+                sample[i] = line;
+            }
+        }
+        seen += 1;
+    }
+    Ok(sample)
+}
+
+/// Positions are those of `EasyReader::position()` and `EasyReader::seek_to()`.
+impl<R: Read + Seek> LineCursor for EasyReader<R> {
+    type Position = Position;
+
+    fn next_line(&mut self) -> io::Result<Option<String>> {
+        EasyReader::next_line(self)
+    }
+
+    fn prev_line(&mut self) -> io::Result<Option<String>> {
+        EasyReader::prev_line(self)
+    }
+
+    fn current_line(&mut self) -> io::Result<Option<String>> {
+        EasyReader::current_line(self)
+    }
+
+    fn bof(&mut self) {
+        EasyReader::bof(self);
+    }
+
+    fn eof(&mut self) {
+        EasyReader::eof(self);
+    }
+
+    fn position(&self) -> Position {
+        EasyReader::position(self)
+    }
+
+    fn goto(&mut self, position: &Position) -> io::Result<()> {
+        self.seek_to(*position).map(|_| ())
+    }
+
+    fn next_lines(&mut self, n: usize) -> io::Result<Vec<String>> {
+        EasyReader::next_lines(self, n)
+    }
+
+    fn prev_lines(&mut self, n: usize) -> io::Result<Vec<String>> {
+        EasyReader::prev_lines(self, n)
+    }
+
+    fn next_line_matching(
+        &mut self,
+        predicate: &mut dyn FnMut(&str) -> bool,
+    ) -> io::Result<Option<String>> {
+        EasyReader::next_line_matching(self, predicate)
+    }
+
+    fn prev_line_matching(
+        &mut self,
+        predicate: &mut dyn FnMut(&str) -> bool,
+    ) -> io::Result<Option<String>> {
+        EasyReader::prev_line_matching(self, predicate)
+    }
+
+    #[cfg(feature = "rand")]
+    fn sample(&mut self, k: usize) -> io::Result<Vec<String>> {
+        EasyReader::sample(self, k)
+    }
+}
+
+impl<C: LineCursor + ?Sized> LineCursor for &mut C {
+    type Position = C::Position;
+
+    fn next_line(&mut self) -> io::Result<Option<String>> {
+        (**self).next_line()
+    }
+
+    fn prev_line(&mut self) -> io::Result<Option<String>> {
+        (**self).prev_line()
+    }
+
+    fn current_line(&mut self) -> io::Result<Option<String>> {
+        (**self).current_line()
+    }
+
+    fn bof(&mut self) {
+        (**self).bof()
+    }
+
+    fn eof(&mut self) {
+        (**self).eof()
+    }
+
+    fn position(&self) -> Self::Position {
+        (**self).position()
+    }
+
+    fn goto(&mut self, position: &Self::Position) -> io::Result<()> {
+        (**self).goto(position)
+    }
+
+    fn next_lines(&mut self, n: usize) -> io::Result<Vec<String>> {
+        (**self).next_lines(n)
+    }
+
+    fn prev_lines(&mut self, n: usize) -> io::Result<Vec<String>> {
+        (**self).prev_lines(n)
+    }
+
+    fn next_line_matching(
+        &mut self,
+        predicate: &mut dyn FnMut(&str) -> bool,
+    ) -> io::Result<Option<String>> {
+        (**self).next_line_matching(predicate)
+    }
+
+    fn prev_line_matching(
+        &mut self,
+        predicate: &mut dyn FnMut(&str) -> bool,
+    ) -> io::Result<Option<String>> {
+        (**self).prev_line_matching(predicate)
+    }
+
+    #[cfg(feature = "rand")]
+    fn sample(&mut self, k: usize) -> io::Result<Vec<String>> {
+        (**self).sample(k)
+    }
+}
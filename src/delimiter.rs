@@ -0,0 +1,471 @@
+use crate::{clamp_len, read_bytes_from, EasyReader, CR_BYTE, LF_BYTE, SCAN_BLOCK_SIZE};
+#[cfg(feature = "index")]
+use crate::{fnv1a, FNV_OFFSET_BASIS};
+use std::{
+    io::{self, prelude::*, Error, ErrorKind},
+    sync::Arc,
+};
+
+/// Decides where the records (the lines) of an `EasyReader` end, for the framings a delimiter
+/// can't describe: records spanning several lines (pretty-printed JSON documents...),
+/// terminators depending on the bytes around them...
+///
+/// Each record ends with a terminator of `terminator_len()` bytes, left out of the record like
+/// a line terminator. While scanning, in both directions and when indexing, the reader asks
+/// `is_boundary()` about every offset a terminator could end at.
+pub trait Boundary: Send + Sync {
+    /// The length of the terminators, at least 1 byte.
+    fn terminator_len(&self) -> usize;
+
+    /// How many bytes `is_boundary()` gets before and after the offset checked (the
+    /// terminator only by default).
+    fn window(&self) -> (usize, usize) {
+        (self.terminator_len(), 0)
+    }
+
+    /// Whether a terminator ends between `before` and `after`, the bytes preceding and
+    /// following the offset checked (fewer near the ends of the file).
+    fn is_boundary(&self, before: &[u8], after: &[u8]) -> bool;
+}
+
+/// How a line ends, see `EasyReader::line_ending()`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LineEnding {
+    Lf,
+    CrLf,
+    /// A bare CR, as in classic Mac OS files (see `detect_line_endings()`).
+    Cr,
+    /// A delimiter set with `delimiter()`, `delimiter_bytes()` or `boundary()`.
+    Other,
+    /// The last line of the file may have no terminator.
+    Unterminated,
+}
+
+// What terminates the lines. Occurrences of a delimiter which can overlap itself (e.g. "\n\n"
+// in "\n\n\n") are matched from left to right, like `str::split()` does. With code units of
+// several bytes (UTF-16), a delimiter only ends at the end of a code unit.
+#[derive(Clone)]
+pub(crate) enum Delimiter {
+    Bytes {
+        bytes: Vec<u8>,
+        overlapping: bool,
+        unit: u64,
+    },
+    Boundary(Arc<dyn Boundary>),
+}
+
+// Boundaries are only equal to themselves
+impl PartialEq for Delimiter {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (
+                Delimiter::Bytes { bytes, unit, .. },
+                Delimiter::Bytes {
+                    bytes: other_bytes,
+                    unit: other_unit,
+                    ..
+                },
+            ) => bytes == other_bytes && unit == other_unit,
+            (Delimiter::Boundary(boundary), Delimiter::Boundary(other)) => {
+                Arc::ptr_eq(boundary, other)
+            }
+            _ => false,
+        }
+    }
+}
+
+impl Default for Delimiter {
+    fn default() -> Self {
+        Delimiter::new(vec![LF_BYTE])
+    }
+}
+
+impl Delimiter {
+    pub(crate) fn new(bytes: Vec<u8>) -> Self {
+        // A proper prefix which is also a suffix lets two occurrences overlap
+        let overlapping = (1..bytes.len()).any(|len| bytes[..len] == bytes[bytes.len() - len..]);
+        Delimiter::Bytes {
+            bytes,
+            overlapping,
+            unit: 1,
+        }
+    }
+
+    // The LF of UTF-16 text
+    #[cfg(feature = "encoding")]
+    pub(crate) fn utf16_lf(big_endian: bool) -> Self {
+        let bytes = if big_endian {
+            vec![0, LF_BYTE]
+        } else {
+            vec![LF_BYTE, 0]
+        };
+        Delimiter::Bytes {
+            bytes,
+            overlapping: false,
+            unit: 2,
+        }
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        match self {
+            Delimiter::Bytes { bytes, .. } => bytes.len(),
+            Delimiter::Boundary(boundary) => boundary.terminator_len(),
+        }
+    }
+
+    // The byte of a single byte delimiter, which the scans can look for on their own
+    pub(crate) fn byte(&self) -> Option<u8> {
+        match self {
+            Delimiter::Bytes { bytes, .. } if bytes.len() == 1 => Some(bytes[0]),
+            _ => None,
+        }
+    }
+
+    // A hash of the delimiter, to tell whether an index saved to a file was built with it. A
+    // boundary can't be told apart from another one.
+    #[cfg(feature = "index")]
+    pub(crate) fn hash(&self) -> Option<u64> {
+        match self {
+            Delimiter::Bytes { bytes, unit, .. } => {
+                Some(fnv1a(fnv1a(FNV_OFFSET_BASIS, bytes), &unit.to_le_bytes()))
+            }
+            Delimiter::Boundary(_) => None,
+        }
+    }
+
+    pub(crate) fn is_lf(&self) -> bool {
+        self.byte() == Some(LF_BYTE)
+    }
+
+    // The CR which can precede an LF delimiter (in its encoding), making a CRLF. Only LF
+    // delimited lines can end with a CRLF.
+    pub(crate) fn cr(&self) -> Option<&'static [u8]> {
+        match self {
+            Delimiter::Bytes { bytes, unit: 1, .. } if bytes[..] == [LF_BYTE] => Some(&[CR_BYTE]),
+            Delimiter::Bytes { bytes, unit: 2, .. } if bytes[..] == [LF_BYTE, 0] => {
+                Some(&[CR_BYTE, 0])
+            }
+            Delimiter::Bytes { bytes, unit: 2, .. } if bytes[..] == [0, LF_BYTE] => {
+                Some(&[0, CR_BYTE])
+            }
+            _ => None,
+        }
+    }
+
+    // Whether a delimiter ends right after `block[i]`, `block` being read from `block_start`.
+    // `read` (offset, length) returns fewer bytes past the EOF.
+    pub(crate) fn ends_after<F>(
+        &self,
+        block: &[u8],
+        block_start: u64,
+        i: usize,
+        read: F,
+    ) -> io::Result<bool>
+    where
+        F: FnMut(u64, usize) -> io::Result<Vec<u8>>,
+    {
+        if let Delimiter::Bytes { bytes, .. } = self {
+            if block[i] != bytes[bytes.len() - 1] {
+                return Ok(false);
+            }
+            if bytes.len() == 1 {
+                return Ok(true);
+            }
+        }
+        self.ends_at(block_start + i as u64 + 1, block, block_start, read)
+    }
+
+    // Whether a delimiter ends at the offset `end`. `window` holds the bytes from the offset
+    // `window_start`, the others are taken from `read` (offset, length).
+    pub(crate) fn ends_at<F>(
+        &self,
+        end: u64,
+        window: &[u8],
+        window_start: u64,
+        mut read: F,
+    ) -> io::Result<bool>
+    where
+        F: FnMut(u64, usize) -> io::Result<Vec<u8>>,
+    {
+        let (bytes, overlapping) = match self {
+            Delimiter::Bytes { unit, .. } if end % *unit != 0 => return Ok(false),
+            Delimiter::Bytes {
+                bytes, overlapping, ..
+            } => (bytes, *overlapping),
+            Delimiter::Boundary(boundary) => {
+                let (before, after) = boundary.window();
+                let from = end.saturating_sub(before as u64);
+                let to = end + after as u64;
+                // The bytes are only read when they aren't all in the window, around its edges
+                if let Some(bytes) = window_slice(window, window_start, from, to) {
+                    let (before, after) = bytes.split_at((end - from) as usize);
+                    return Ok(boundary.is_boundary(before, after));
+                }
+                let before = read(from, (end - from) as usize)?;
+                let after = read(end, after)?;
+                return Ok(boundary.is_boundary(&before, &after));
+            }
+        };
+        let len = bytes.len() as u64;
+        if end < len {
+            return Ok(false);
+        }
+        let start = end - len;
+        let matches = match window_slice(window, window_start, start, end) {
+            Some(window) => window == &bytes[..],
+            None => read(start, len as usize)? == *bytes,
+        };
+        if !matches || !overlapping {
+            return Ok(matches);
+        }
+
+        // The first occurrence of a chain of overlapping ones is a delimiter, the following
+        // ones are found from there
+        let mut first = start;
+        while let Some(previous) = overlapping_before(bytes, first, &mut read)? {
+            first = previous;
+        }
+        let run = read(first, (end - first) as usize)?;
+        let mut i = 0;
+        while i + bytes.len() <= run.len() {
+            if run[i..].starts_with(bytes) {
+                i += bytes.len();
+            } else {
+                i += 1;
+            }
+        }
+        Ok(i == run.len())
+    }
+
+    // Where the terminator of the line ending at `end` starts (the line starting at `start`),
+    // the CR of a CRLF included. `end` if the line has no terminator.
+    pub(crate) fn terminator_start<F>(&self, start: u64, end: u64, mut read: F) -> io::Result<u64>
+    where
+        F: FnMut(u64, usize) -> io::Result<Vec<u8>>,
+    {
+        let len = self.len() as u64;
+        if end - start < len {
+            return Ok(end);
+        }
+        // Room for a CR before an LF
+        let cr = self.cr().unwrap_or(&[]);
+        let window_len = (end - start).min(len + cr.len() as u64);
+        let window = read(end - window_len, window_len as usize)?;
+        if !self.ends_at(end, &window, end - window_len, &mut read)? {
+            return Ok(end);
+        }
+        if !cr.is_empty() && window_len > len && window.starts_with(cr) {
+            return Ok(end - window_len);
+        }
+        Ok(end - len)
+    }
+}
+
+// The bytes from `start` to `end` if `window` (read from `window_start`) holds them all
+fn window_slice(window: &[u8], window_start: u64, start: u64, end: u64) -> Option<&[u8]> {
+    let window_end = window_start + window.len() as u64;
+    if start < window_start || end > window_end {
+        return None;
+    }
+    Some(&window[(start - window_start) as usize..(end - window_start) as usize])
+}
+
+// The start of the earliest occurrence of `delimiter` overlapping the beginning of the one at
+// `start`
+fn overlapping_before<F>(delimiter: &[u8], start: u64, read: &mut F) -> io::Result<Option<u64>>
+where
+    F: FnMut(u64, usize) -> io::Result<Vec<u8>>,
+{
+    let len = delimiter.len() as u64;
+    let from = start.saturating_sub(len - 1);
+    let bytes = read(from, (start - from) as usize + delimiter.len() - 1)?;
+    Ok((0..start - from)
+        .find(|&i| bytes[i as usize..].starts_with(delimiter))
+        .map(|i| from + i))
+}
+
+impl<R: Read + Seek> EasyReader<R> {
+    /// Like `delimiter()`, with a terminator of several bytes: e.g. `b"---\n"` or `b"\r\n\r\n"`
+    /// for records separated by sentinels. No CR is dropped before it.
+    ///
+    /// Fails with `ErrorKind::InvalidInput` if `delimiter` is empty or longer than 64 KiB.
+    pub fn delimiter_bytes(&mut self, delimiter: &[u8]) -> io::Result<&mut Self> {
+        if delimiter.is_empty() || delimiter.len() > SCAN_BLOCK_SIZE {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "The delimiter must be 1 byte to 64 KiB long",
+            ));
+        }
+        Ok(self.set_delimiter(Delimiter::new(delimiter.to_vec())))
+    }
+
+    /// Ends the lines where `boundary` decides, instead of after a delimiter: the lines become
+    /// any kind of records, read in both directions and indexed like lines. No CR is dropped
+    /// before the terminators.
+    ///
+    /// Fails with `ErrorKind::InvalidInput` if the terminators are empty, or if the terminators
+    /// or the window are longer than 64 KiB.
+    pub fn boundary<B: Boundary + 'static>(&mut self, boundary: B) -> io::Result<&mut Self> {
+        let (before, after) = boundary.window();
+        let len = boundary.terminator_len();
+        if len == 0 || len > SCAN_BLOCK_SIZE || before.max(after) > SCAN_BLOCK_SIZE {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "The terminators must be 1 byte to 64 KiB long, the window at most 64 KiB",
+            ));
+        }
+        Ok(self.set_delimiter(Delimiter::Boundary(Arc::new(boundary))))
+    }
+
+    pub(crate) fn set_delimiter(&mut self, delimiter: Delimiter) -> &mut Self {
+        self.delimiter = delimiter;
+        #[cfg(feature = "index")]
+        {
+            self.drop_index();
+            self.partial_index = None;
+            if let Some(lines) = self.lazy_index.as_mut() {
+                lines.clear();
+            }
+        }
+        self.line_count_estimate = None;
+        self.pending_offset = None;
+        self.bof()
+    }
+
+    /// Makes the line reads (`next_line()`, `prev_line()`... and the iterators) return the
+    /// lines with their terminators (`\n`, `\r\n` or the delimiter), for byte-exact copies.
+    /// The last line may have none.
+    pub fn keep_terminators(&mut self, keep: bool) -> &mut Self {
+        self.keep_terminators = keep;
+        self
+    }
+
+    /// Looks at the line endings of the beginning of the file (its first 64 KiB) and terminates
+    /// the lines with a bare CR if that's what the file uses (classic Mac OS files), with LF
+    /// otherwise (which handles CRLF too). Returns the line ending found, `Unterminated` if
+    /// there's none.
+    ///
+    /// Like `delimiter()`, the cursor goes back to the BOF and the index is dropped.
+    pub fn detect_line_endings(&mut self) -> io::Result<LineEnding> {
+        let len = self.file_size.min(SCAN_BLOCK_SIZE as u64) as usize;
+        let block = self.read_bytes(0, len)?;
+        let ending = match block.iter().position(|b| *b == LF_BYTE || *b == CR_BYTE) {
+            Some(i) if block[i] == LF_BYTE => LineEnding::Lf,
+            Some(i) if block.get(i + 1) == Some(&LF_BYTE) => LineEnding::CrLf,
+            // A CR at the end of the block may be followed by an LF
+            Some(i) if i + 1 < len || len as u64 == self.file_size => LineEnding::Cr,
+            Some(_) => LineEnding::CrLf,
+            None => LineEnding::Unterminated,
+        };
+        self.delimiter(if ending == LineEnding::Cr {
+            CR_BYTE
+        } else {
+            LF_BYTE
+        });
+        Ok(ending)
+    }
+
+    /// How the current line ends, to write the lines back with their own line endings when a
+    /// file mixes them. `None` out of a line (at the BOF or the EOF).
+    pub fn line_ending(&mut self) -> io::Result<Option<LineEnding>> {
+        if self.at_bof
+            || self.pending_offset.is_some()
+            || self.current_start_line_offset == self.file_size
+        {
+            return Ok(None);
+        }
+        self.ending_after(self.current_end_line_offset).map(Some)
+    }
+
+    // How the line ending at `end` ends
+    pub(crate) fn ending_after(&mut self, end: u64) -> io::Result<LineEnding> {
+        let terminator = self.terminator_after(end)?;
+        Ok(if terminator.is_empty() {
+            LineEnding::Unterminated
+        } else if self.delimiter.byte() == Some(CR_BYTE) {
+            LineEnding::Cr
+        } else if self.delimiter.cr().is_none() {
+            LineEnding::Other
+        } else if terminator.len() > self.delimiter.len() {
+            LineEnding::CrLf
+        } else {
+            LineEnding::Lf
+        })
+    }
+
+    // The terminator of the line ending at `end`, nothing at the EOF
+    pub(crate) fn terminator_after(&mut self, end: u64) -> io::Result<Vec<u8>> {
+        let cr = self.delimiter.cr().unwrap_or(&[]);
+        // Room for a CR before an LF
+        let len = (self.delimiter.len() + cr.len()) as u64;
+        let mut bytes = self.read_bytes(end, (self.file_size - end).min(len) as usize)?;
+        if !bytes.starts_with(cr) {
+            bytes.truncate(self.delimiter.len());
+        }
+        Ok(bytes)
+    }
+
+    // Whether a delimiter ends right after `block[i]`, `block` being read from `block_start`
+    pub(crate) fn ends_delimiter(
+        &mut self,
+        block: &[u8],
+        block_start: u64,
+        i: usize,
+    ) -> io::Result<bool> {
+        let (file, file_size, stats) = (&mut self.file, self.file_size, &mut self.stats);
+        let base = self.header_size;
+        // The chunks are padded with zeros past the EOF, where no delimiter ends
+        let block = &block[..clamp_len(block_start, block.len(), file_size)];
+        if i >= block.len() {
+            return Ok(false);
+        }
+        self.delimiter
+            .ends_after(block, block_start, i, |offset, len| {
+                read_bytes_from(
+                    file,
+                    base + offset,
+                    clamp_len(offset, len, file_size),
+                    base + file_size,
+                    stats,
+                )
+            })
+    }
+
+    // Where the terminator of the line from `start` to `end` starts, `end` if it has none
+    pub(crate) fn terminator_start(&mut self, start: u64, end: u64) -> io::Result<u64> {
+        let (file, file_size, stats) = (&mut self.file, self.file_size, &mut self.stats);
+        let base = self.header_size;
+        self.delimiter.terminator_start(start, end, |offset, len| {
+            read_bytes_from(
+                file,
+                base + offset,
+                clamp_len(offset, len, file_size),
+                base + file_size,
+                stats,
+            )
+        })
+    }
+
+    // Like terminator_start(), taking the bytes from `block` (read from `block_start`) when it
+    // holds them
+    #[cfg(any(feature = "rand", feature = "index"))]
+    pub(crate) fn terminator_start_in(
+        &mut self,
+        start: u64,
+        end: u64,
+        block: &[u8],
+        block_start: u64,
+    ) -> io::Result<u64> {
+        let (file, file_size, stats) = (&mut self.file, self.file_size, &mut self.stats);
+        let base = self.header_size;
+        let block_end = block_start + block.len() as u64;
+        self.delimiter.terminator_start(start, end, |offset, len| {
+            let len = clamp_len(offset, len, file_size);
+            if offset >= block_start && offset + len as u64 <= block_end {
+                let from = (offset - block_start) as usize;
+                return Ok(block[from..from + len].to_vec());
+            }
+            read_bytes_from(file, base + offset, len, base + file_size, stats)
+        })
+    }
+}
@@ -0,0 +1,66 @@
+use crate::{delimiter::Delimiter, EasyReader, ReaderError};
+use encoding_rs::{Encoding, UTF_16BE, UTF_16LE, UTF_8};
+use std::io::{self, prelude::*, Error, ErrorKind};
+
+impl<R: Read + Seek> EasyReader<R> {
+    /// Decodes the lines from `encoding` instead of UTF-8, to navigate legacy data without
+    /// converting it first: Latin-1 (`WINDOWS_1252`), Shift-JIS, GBK... or UTF-16LE and
+    /// UTF-16BE (e.g. Windows exports), the lines of which are split on the 2-byte LF (a CRLF
+    /// is handled too). A byte order mark at the start of the file is left out of the first
+    /// line.
+    ///
+    /// Like `delimiter()`, it sets the line terminator (the LF of `encoding`): the cursor goes
+    /// back to the BOF and the index is dropped. Fails with `ErrorKind::InvalidInput` for the
+    /// stateful encodings (ISO-2022-JP), whose lines can't be decoded on their own.
+    pub fn encoding(&mut self, encoding: &'static Encoding) -> io::Result<&mut Self> {
+        let delimiter = if encoding == UTF_16LE || encoding == UTF_16BE {
+            Delimiter::utf16_lf(encoding == UTF_16BE)
+        } else if encoding.is_ascii_compatible() {
+            Delimiter::default()
+        } else {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!("Unsupported encoding: {}", encoding.name()),
+            ));
+        };
+        self.encoding = Some(encoding).filter(|&encoding| encoding != UTF_8);
+        Ok(self.set_delimiter(delimiter))
+    }
+
+    // Transcodes the bytes of the line at `start..end`, from `buffer[from]` on, to UTF-8
+    pub(crate) fn transcode(
+        &self,
+        buffer: &mut Vec<u8>,
+        from: usize,
+        start: u64,
+        end: u64,
+    ) -> io::Result<()> {
+        let encoding = match self.encoding {
+            Some(encoding) => encoding,
+            None => return Ok(()),
+        };
+        let mut text = &buffer[from..];
+        if start == 0 {
+            if let Some((bom_encoding, bom_len)) = Encoding::for_bom(text) {
+                if bom_encoding == encoding {
+                    text = &text[bom_len..];
+                }
+            }
+        }
+        match encoding.decode_without_bom_handling_and_without_replacement(text) {
+            Some(line) => {
+                let line = line.into_owned();
+                buffer.truncate(from);
+                buffer.extend_from_slice(line.as_bytes());
+                Ok(())
+            }
+            None => Err(ReaderError::InvalidEncoding {
+                offset: start,
+                len: end - start,
+                encoding: encoding.name(),
+                bytes: buffer.split_off(from),
+            }
+            .into()),
+        }
+    }
+}
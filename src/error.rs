@@ -0,0 +1,195 @@
+use std::{error, fmt, io, str::Utf8Error};
+
+/// The errors of the reader other than the I/O ones and the invalid arguments, to handle them
+/// without matching their messages.
+///
+/// They're wrapped in an `io::Error` (of the kind given by `kind()`), from which
+/// `ReaderError::of()` gets them back.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ReaderError {
+    /// The file is empty, see `EasyReader::new()`.
+    EmptyFile,
+    /// The line at `offset`, `len` bytes long, isn't valid UTF-8. `bytes` are the bytes of the
+    /// line, e.g. to log them or decode them lossily.
+    InvalidUtf8 {
+        offset: u64,
+        len: u64,
+        error: Utf8Error,
+        bytes: Vec<u8>,
+    },
+    /// The line at `offset`, `len` bytes long, isn't valid in the encoding set with
+    /// `EasyReader::encoding()`. `bytes` are the bytes of the line, not transcoded.
+    InvalidEncoding {
+        offset: u64,
+        len: u64,
+        encoding: &'static str,
+        bytes: Vec<u8>,
+    },
+    /// The line at `offset` is longer than `EasyReader::max_line_length()` allows.
+    RecordTooLong { offset: u64, max_length: usize },
+    /// The index doesn't match the file anymore, see `StaleIndexPolicy::Error` and
+    /// `EasyReader::load_index()`.
+    IndexStale,
+    /// The file has been truncated from `previous_size` to `size` bytes, see
+    /// `TruncationPolicy::Error` and `IndexedWatcher::update()`.
+    Truncated { previous_size: u64, size: u64 },
+    /// The operation was cancelled with a `Cancel` token, see `Cancel::cancel()`.
+    Cancelled,
+    /// The record at `offset` of a `FramedReader` ends past the EOF.
+    TruncatedRecord { offset: u64 },
+    /// The length prefix of the record at `offset` of a `FramedReader` ends past the EOF.
+    TruncatedPrefix { offset: u64 },
+    /// The varint length prefix of the record at `offset` of a `FramedReader` doesn't fit in
+    /// 64 bits.
+    PrefixOverflow { offset: u64 },
+}
+
+impl ReaderError {
+    /// The error wrapped in `err`, if any.
+    pub fn of(err: &io::Error) -> Option<&ReaderError> {
+        err.get_ref()?.downcast_ref()
+    }
+
+    /// The byte offset of what failed: the start of the line or record (0 for an empty file),
+    /// `None` if the error isn't about a part of the file.
+    pub fn offset(&self) -> Option<u64> {
+        match self {
+            ReaderError::EmptyFile => Some(0),
+            ReaderError::InvalidUtf8 { offset, .. }
+            | ReaderError::InvalidEncoding { offset, .. }
+            | ReaderError::RecordTooLong { offset, .. }
+            | ReaderError::TruncatedRecord { offset }
+            | ReaderError::TruncatedPrefix { offset }
+            | ReaderError::PrefixOverflow { offset } => Some(*offset),
+            ReaderError::IndexStale | ReaderError::Truncated { .. } | ReaderError::Cancelled => {
+                None
+            }
+        }
+    }
+
+    /// The bytes of the line which couldn't be decoded (its terminator included if kept, see
+    /// `EasyReader::keep_terminators()`).
+    ///
+    /// ```no_run
+    /// use easy_reader::{EasyReader, ReaderError};
+    /// use std::{fs::File, io};
+    ///
+    /// fn next_line_lossy(reader: &mut EasyReader<File>) -> io::Result<Option<String>> {
+    ///     match reader.next_line() {
+    ///         Err(err) => match ReaderError::of(&err).and_then(ReaderError::bytes) {
+    ///             Some(bytes) => Ok(Some(String::from_utf8_lossy(bytes).into_owned())),
+    ///             None => Err(err),
+    ///         },
+    ///         line => line,
+    ///     }
+    /// }
+    /// ```
+    pub fn bytes(&self) -> Option<&[u8]> {
+        match self {
+            ReaderError::InvalidUtf8 { bytes, .. } | ReaderError::InvalidEncoding { bytes, .. } => {
+                Some(bytes)
+            }
+            _ => None,
+        }
+    }
+
+    /// The kind of the `io::Error` the error is wrapped in.
+    pub fn kind(&self) -> io::ErrorKind {
+        match self {
+            ReaderError::EmptyFile
+            | ReaderError::TruncatedRecord { .. }
+            | ReaderError::TruncatedPrefix { .. } => io::ErrorKind::UnexpectedEof,
+            ReaderError::InvalidUtf8 { .. }
+            | ReaderError::InvalidEncoding { .. }
+            | ReaderError::Cancelled => io::ErrorKind::Other,
+            ReaderError::RecordTooLong { .. }
+            | ReaderError::IndexStale
+            | ReaderError::Truncated { .. }
+            | ReaderError::PrefixOverflow { .. } => io::ErrorKind::InvalidData,
+        }
+    }
+
+    // A line found but which can't be returned: unlike after the other errors, the cursor
+    // moved to it and the following lines can still be read
+    pub(crate) fn is_invalid_line(&self) -> bool {
+        matches!(
+            self,
+            ReaderError::InvalidUtf8 { .. }
+                | ReaderError::InvalidEncoding { .. }
+                | ReaderError::RecordTooLong { .. }
+        )
+    }
+}
+
+impl fmt::Display for ReaderError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ReaderError::EmptyFile => write!(f, "Empty file"),
+            ReaderError::InvalidUtf8 {
+                offset, len, error, ..
+            } => write!(
+                f,
+                "The line starting at byte: {} and ending at byte: {} is not valid UTF-8. Conversion error: {}",
+                offset,
+                offset + len,
+                error
+            ),
+            ReaderError::InvalidEncoding {
+                offset,
+                len,
+                encoding,
+                ..
+            } => write!(
+                f,
+                "The line starting at byte: {} and ending at byte: {} is not valid {}",
+                offset,
+                offset + len,
+                encoding
+            ),
+            ReaderError::RecordTooLong { offset, max_length } => write!(
+                f,
+                "The line starting at byte: {} is longer than the maximum of {} bytes",
+                offset, max_length
+            ),
+            ReaderError::IndexStale => write!(f, "The index doesn't match the file anymore"),
+            ReaderError::Truncated {
+                previous_size,
+                size,
+            } => write!(
+                f,
+                "The file has been truncated from {} to {} bytes",
+                previous_size, size
+            ),
+            ReaderError::Cancelled => write!(f, "The operation has been cancelled"),
+            ReaderError::TruncatedRecord { offset } => {
+                write!(f, "The record starting at byte: {} is truncated", offset)
+            }
+            ReaderError::TruncatedPrefix { offset } => write!(
+                f,
+                "The prefix of the record starting at byte: {} is truncated",
+                offset
+            ),
+            ReaderError::PrefixOverflow { offset } => write!(
+                f,
+                "The prefix of the record starting at byte: {} overflows",
+                offset
+            ),
+        }
+    }
+}
+
+impl error::Error for ReaderError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            ReaderError::InvalidUtf8 { error, .. } => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<ReaderError> for io::Error {
+    fn from(err: ReaderError) -> Self {
+        io::Error::new(err.kind(), err)
+    }
+}
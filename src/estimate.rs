@@ -0,0 +1,251 @@
+use crate::{
+    scan::{byte_positions, BULK_BLOCK_SIZE},
+    EasyReader, SCAN_BLOCK_SIZE,
+};
+use std::io::{self, prelude::*};
+
+const ESTIMATE_SAMPLES: u64 = 16;
+const ESTIMATE_SAMPLE_SIZE: usize = 64 * 1024;
+// Two-sided 95% confidence
+const Z_95: f64 = 1.96;
+// How far from the BOF goto_line_approx() counts the lines to land exactly
+const EXACT_GOTO_WINDOW: u64 = 1024 * 1024;
+
+/// An estimate of the number of lines of a file, with its 95% confidence interval.
+///
+/// When the file is small enough to be read entirely, `low`, `lines` and `high` are all equal
+/// to the exact count and `is_exact()` returns true.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LineCountEstimate {
+    pub lines: u64,
+    pub low: u64,
+    pub high: u64,
+    exact: bool,
+}
+
+impl LineCountEstimate {
+    pub fn is_exact(&self) -> bool {
+        self.exact
+    }
+}
+
+/// A line reached by `EasyReader::goto_line_approx()`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ApproxLine {
+    pub line: String,
+    exact: bool,
+}
+
+impl ApproxLine {
+    /// Whether the line is exactly the one requested, not only one near it.
+    pub fn is_exact(&self) -> bool {
+        self.exact
+    }
+}
+
+impl<R: Read + Seek> EasyReader<R> {
+    /// Estimates the number of lines from the newline density of a few regions of the file,
+    /// without scanning it entirely.
+    ///
+    /// The file is split in equal strata and a region is sampled in each of them (at a random
+    /// position with the `rand` feature, at its beginning otherwise).
+    pub fn estimate_line_count(&mut self) -> io::Result<LineCountEstimate> {
+        let indexed_lines = self.index().map(|index| index.len() as u64);
+        if let Some(lines) = indexed_lines.or_else(|| self.sparse_lines()) {
+            return Ok(LineCountEstimate {
+                lines,
+                low: lines,
+                high: lines,
+                exact: true,
+            });
+        }
+
+        let sample_size = ESTIMATE_SAMPLE_SIZE as u64;
+        let last_line = self.unterminated_last_line()?;
+
+        if self.file_size <= ESTIMATE_SAMPLES * sample_size {
+            let mut newlines = 0;
+            let mut offset = 0;
+            while offset < self.file_size {
+                let len = (self.file_size - offset).min(sample_size) as usize;
+                let block = self.read_bytes(offset, len)?;
+                newlines += self.count_terminators(&block, offset)?;
+                offset += len as u64;
+            }
+            let lines = newlines + last_line;
+            return Ok(LineCountEstimate {
+                lines,
+                low: lines,
+                high: lines,
+                exact: true,
+            });
+        }
+
+        let stratum = self.file_size / ESTIMATE_SAMPLES;
+        let mut densities = Vec::with_capacity(ESTIMATE_SAMPLES as usize);
+        for i in 0..ESTIMATE_SAMPLES {
+            #[cfg(feature = "rand")]
+            let jitter = self.random_below(stratum.saturating_sub(sample_size) + 1);
+            #[cfg(not(feature = "rand"))]
+            let jitter = 0;
+
+            let offset = i * stratum + jitter;
+            let len = (self.file_size - offset).min(sample_size) as usize;
+            let block = self.read_bytes(offset, len)?;
+            let newlines = self.count_terminators(&block, offset)?;
+            densities.push(newlines as f64 / len as f64);
+        }
+
+        let n = densities.len() as f64;
+        let mean = densities.iter().sum::<f64>() / n;
+        let variance = densities.iter().map(|d| (d - mean).powi(2)).sum::<f64>() / (n - 1.0);
+        // Finite population correction, the samples cover a good part of mid-sized files
+        let sampled_fraction = n * sample_size as f64 / self.file_size as f64;
+        let std_error = (variance / n * (1.0 - sampled_fraction).max(0.0)).sqrt();
+
+        let size = self.file_size as f64;
+        let lines = (mean * size).round() as u64 + last_line;
+        let margin = (Z_95 * std_error * size).ceil() as u64;
+        Ok(LineCountEstimate {
+            lines,
+            low: lines.saturating_sub(margin).max(1),
+            high: lines + margin,
+            exact: false,
+        })
+    }
+
+    /// Counts the lines of the file, without moving the cursor.
+    ///
+    /// Immediate if the reader is indexed (even sparsely), the file is scanned for line
+    /// terminators in large blocks otherwise (with SIMD instructions with the `simd` feature).
+    pub fn line_count(&mut self) -> io::Result<u64> {
+        match self.line_count_estimate {
+            Some(estimate) if estimate.is_exact() => return Ok(estimate.lines),
+            _ => (),
+        }
+        let indexed_lines = self.index().map(|index| index.len() as u64);
+        if let Some(lines) = indexed_lines.or_else(|| self.sparse_lines()) {
+            return Ok(lines);
+        }
+
+        let block_size = match self.delimiter.byte() {
+            Some(_) => BULK_BLOCK_SIZE,
+            None => SCAN_BLOCK_SIZE,
+        };
+        let mut newlines = 0;
+        let mut offset = 0;
+        while offset < self.file_size {
+            let len = (self.file_size - offset).min(block_size as u64) as usize;
+            let block = self.read_bytes(offset, len)?;
+            newlines += self.count_terminators(&block, offset)?;
+            offset += len as u64;
+        }
+        // A trailing line terminator doesn't open a new line
+        Ok(newlines + self.unterminated_last_line()?)
+    }
+
+    /// Moves to a line near the `line`-th one (zero-based) and returns it, with whether it's
+    /// exactly the requested one (see `ApproxLine::is_exact()`).
+    ///
+    /// The move is exact if the reader is indexed, even sparsely (the lines are counted from
+    /// the closest line of the sparse index), or if the file is small enough to count its
+    /// lines (up to 1 MiB). Otherwise the offset of the line is extrapolated from the average
+    /// line length (sampled once, see `estimate_line_count()`). If it's in the first MiB of the
+    /// file, the lines up to it are counted to land exactly on the requested line. Past it, the
+    /// cursor lands on the start of the line containing the offset, only approximately the
+    /// requested one: no line number is known closer than the BOF to count from.
+    pub fn goto_line_approx(&mut self, line: u64) -> io::Result<Option<ApproxLine>> {
+        if self.index().is_some() {
+            return match self.index_entry(line)? {
+                Some((start, end)) => {
+                    self.set_current_line(start, end);
+                    self.approx_line(true)
+                }
+                None => Ok(None),
+            };
+        }
+
+        let estimate = match self.line_count_estimate {
+            Some(estimate) => estimate,
+            None => {
+                let estimate = self.estimate_line_count()?;
+                self.line_count_estimate = Some(estimate);
+                estimate
+            }
+        };
+
+        let (offset, exact) = if estimate.is_exact() {
+            match self.find_line_start(line)? {
+                Some(offset) => (offset, true),
+                None => return Ok(None),
+            }
+        } else {
+            let average_line_length = self.file_size as f64 / estimate.lines as f64;
+            let offset = (line as f64 * average_line_length) as u64;
+            match offset < EXACT_GOTO_WINDOW {
+                true => self.line_start_within(line, EXACT_GOTO_WINDOW)?,
+                false => None,
+            }
+            .map_or((offset, false), |offset| (offset, true))
+        };
+        if offset >= self.file_size {
+            return Ok(None);
+        }
+
+        self.align_to_line(offset)?;
+        self.approx_line(exact)
+    }
+}
+
+impl<R: Read + Seek> EasyReader<R> {
+    fn approx_line(&mut self, exact: bool) -> io::Result<Option<ApproxLine>> {
+        Ok(self.current_line()?.map(|line| ApproxLine { line, exact }))
+    }
+
+    // The line terminators ending in `block`, read from `block_start`
+    fn count_terminators(&mut self, block: &[u8], block_start: u64) -> io::Result<u64> {
+        if let Some(delimiter) = self.delimiter.byte() {
+            return Ok(byte_positions(block, delimiter).count() as u64);
+        }
+        let mut terminators = 0;
+        for i in 0..block.len() {
+            if self.ends_delimiter(block, block_start, i)? {
+                terminators += 1;
+            }
+        }
+        Ok(terminators)
+    }
+
+    // The start of the `line`-th line if it starts within the first `window` bytes, counting
+    // the lines up to it
+    fn line_start_within(&mut self, line: u64, window: u64) -> io::Result<Option<u64>> {
+        if line == 0 {
+            return Ok(Some(0));
+        }
+        let (mut current_line, mut pos) = (0, 0);
+        let end = window.min(self.file_size);
+        while pos < end {
+            let len = (end - pos).min(SCAN_BLOCK_SIZE as u64) as usize;
+            let block = self.read_bytes(pos, len)?;
+            for i in 0..len {
+                if self.ends_delimiter(&block, pos, i)? {
+                    current_line += 1;
+                    if current_line == line {
+                        return Ok(Some(pos + i as u64 + 1));
+                    }
+                }
+            }
+            pos += len as u64;
+        }
+        Ok(None)
+    }
+
+    // 1 if the last line has no terminator, which the count of terminators misses
+    fn unterminated_last_line(&mut self) -> io::Result<u64> {
+        let end = self.file_size;
+        if end == 0 {
+            return Ok(0);
+        }
+        Ok(u64::from(self.terminator_start(0, end)? == end))
+    }
+}
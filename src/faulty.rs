@@ -0,0 +1,132 @@
+use std::{
+    collections::BTreeMap,
+    io::{self, prelude::*, Error, ErrorKind, SeekFrom},
+    thread,
+    time::Duration,
+};
+
+/// A misbehavior injected by a [`FaultyReader`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Fault {
+    /// The read returns at most this many bytes.
+    ShortRead(usize),
+    /// The read fails with `ErrorKind::Interrupted`, without reading anything.
+    Interrupted,
+    /// The read is delayed.
+    Latency(Duration),
+    /// The file shrinks to this size before the read (no-op if it's already smaller).
+    Truncate(u64),
+    /// These bytes are appended to the file before the read.
+    Append(Vec<u8>),
+}
+
+/// A storage backend wrapping another one to inject I/O faults on a fixed schedule, so code
+/// reading through it can be tested deterministically against short reads, interruptions,
+/// latency and files changing size mid-read.
+///
+/// Faults are scheduled on the number of `read()` calls, counting from zero.
+pub struct FaultyReader<R> {
+    inner: R,
+    inner_size: u64,
+    appended: Vec<u8>,
+    position: u64,
+    calls: usize,
+    faults: BTreeMap<usize, Vec<Fault>>,
+}
+
+impl<R: Read + Seek> FaultyReader<R> {
+    pub fn new(mut inner: R) -> io::Result<Self> {
+        let inner_size = inner.seek(SeekFrom::End(0))?;
+        Ok(FaultyReader {
+            inner,
+            inner_size,
+            appended: Vec::new(),
+            position: 0,
+            calls: 0,
+            faults: BTreeMap::new(),
+        })
+    }
+
+    /// Schedules a fault for the `call`-th read, faults scheduled on the same call are applied
+    /// in order.
+    pub fn fault_at(&mut self, call: usize, fault: Fault) -> &mut Self {
+        self.faults.entry(call).or_default().push(fault);
+        self
+    }
+
+    /// Number of `read()` calls so far.
+    pub fn calls(&self) -> usize {
+        self.calls
+    }
+
+    pub fn size(&self) -> u64 {
+        self.inner_size + self.appended.len() as u64
+    }
+
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+
+    fn apply(&mut self, fault: Fault, max_len: &mut usize) -> io::Result<()> {
+        match fault {
+            Fault::ShortRead(len) => *max_len = (*max_len).min(len),
+            Fault::Interrupted => {
+                return Err(Error::new(ErrorKind::Interrupted, "Injected interruption"));
+            }
+            Fault::Latency(delay) => thread::sleep(delay),
+            Fault::Truncate(size) if size <= self.inner_size => {
+                self.inner_size = size;
+                self.appended.clear();
+            }
+            Fault::Truncate(size) => self.appended.truncate((size - self.inner_size) as usize),
+            Fault::Append(bytes) => self.appended.extend(bytes),
+        }
+        Ok(())
+    }
+}
+
+impl<R: Read + Seek> Read for FaultyReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let call = self.calls;
+        self.calls += 1;
+
+        let mut max_len = buf.len();
+        for fault in self.faults.remove(&call).unwrap_or_default() {
+            self.apply(fault, &mut max_len)?;
+        }
+
+        let available = self.size().saturating_sub(self.position);
+        let len = (max_len as u64).min(available) as usize;
+        let read = if self.position < self.inner_size {
+            let len = len.min((self.inner_size - self.position) as usize);
+            self.inner.seek(SeekFrom::Start(self.position))?;
+            self.inner.read(&mut buf[..len])?
+        } else {
+            let start = (self.position - self.inner_size) as usize;
+            buf[..len].copy_from_slice(&self.appended[start..start + len]);
+            len
+        };
+        self.position += read as u64;
+        Ok(read)
+    }
+}
+
+impl<R: Read + Seek> Seek for FaultyReader<R> {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        let position = match pos {
+            SeekFrom::Start(offset) => Some(offset),
+            SeekFrom::End(offset) => self.size().checked_add_signed(offset),
+            SeekFrom::Current(offset) => self.position.checked_add_signed(offset),
+        };
+        match position {
+            Some(position) => {
+                self.position = position;
+                Ok(position)
+            }
+            None => Err(Error::new(
+                ErrorKind::InvalidInput,
+                "Invalid seek to a negative or overflowing position",
+            )),
+        }
+    }
+}
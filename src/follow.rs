@@ -0,0 +1,52 @@
+use crate::EasyReader;
+use std::{
+    io::{self, prelude::*},
+    thread,
+    time::Duration,
+};
+
+/// A never-ending iterator over the lines of a growing file, see `EasyReader::follow()`.
+pub struct Follow<'a, R> {
+    reader: &'a mut EasyReader<R>,
+    poll_interval: Duration,
+}
+
+impl<R: Read + Seek> Iterator for Follow<'_, R> {
+    type Item = io::Result<String>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let cursor = self.reader.save_cursor();
+            match self.reader.next_line() {
+                Ok(Some(line)) if self.reader.current_end_line_offset < self.reader.file_size => {
+                    return Some(Ok(line));
+                }
+                // The last line is still being written
+                Ok(Some(_)) => self.reader.restore_cursor(cursor),
+                Ok(None) => (),
+                Err(err) => return Some(Err(err)),
+            }
+
+            match self.reader.refresh() {
+                Ok(true) => (),
+                Ok(false) => thread::sleep(self.poll_interval),
+                Err(err) => return Some(Err(err)),
+            }
+        }
+    }
+}
+
+impl<R: Read + Seek> EasyReader<R> {
+    /// Iterates over the lines following the cursor and then, like `tail -f`, waits for new
+    /// lines to be appended to the file, checking its size every `poll_interval`.
+    ///
+    /// Only complete lines (followed by a line terminator) are yielded: a line still being
+    /// written is yielded once terminated. Call `eof()` before to only get the new lines.
+    /// The iteration never ends, errors are yielded as they occur.
+    pub fn follow(&mut self, poll_interval: Duration) -> Follow<'_, R> {
+        Follow {
+            reader: self,
+            poll_interval,
+        }
+    }
+}
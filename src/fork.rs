@@ -0,0 +1,87 @@
+use crate::{rotation::file_id, EasyReader};
+use std::{
+    fs::File,
+    io::{self, prelude::*, Error, ErrorKind},
+};
+
+impl<R: Read + Seek> EasyReader<R> {
+    /// Creates a reader over `file`, another handle on the same file, sharing the index (full
+    /// or sparse) instead of building it again. The fork starts where this reader is, with the
+    /// same settings and bookmarks, and then moves on its own.
+    ///
+    /// Fails with `ErrorKind::InvalidInput` if `file` is shorter than the file of this reader.
+    pub fn fork_with<S: Read + Seek>(&self, file: S) -> io::Result<EasyReader<S>> {
+        let mut fork = EasyReader::new(file)?;
+        if fork.file_size < self.header_size + self.file_size {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "The file of the fork is shorter than the original one",
+            ));
+        }
+        // The data appended since is picked up with refresh(), as for this reader
+        fork.header_size = self.header_size;
+        fork.view_end = self.view_end;
+        fork.file_size = self.file_size;
+        fork.chunk_size = self.chunk_size;
+        fork.chunk_cache(self.chunk_cache_capacity());
+        fork.delimiter = self.delimiter.clone();
+        #[cfg(feature = "index")]
+        {
+            fork.share_index(self);
+            fork.compressed_index = self.compressed_index;
+            fork.stale_index_policy = self.stale_index_policy;
+            fork.lazy_index(self.lazy_index.is_some());
+        }
+        fork.section_anchors = self.section_anchors.clone();
+        fork.bookmarks = self.bookmarks.clone();
+        fork.line_count_estimate = self.line_count_estimate;
+        fork.auto_refresh = self.auto_refresh;
+        fork.truncation_policy = self.truncation_policy;
+        fork.continuation = self.continuation.clone();
+        fork.comment_prefix = self.comment_prefix.clone();
+        fork.skip_blank_lines = self.skip_blank_lines;
+        fork.keep_terminators = self.keep_terminators;
+        fork.trim = self.trim;
+        fork.max_line_length = self.max_line_length;
+        #[cfg(feature = "rand")]
+        {
+            fork.unbiased_random = self.unbiased_random;
+        }
+        #[cfg(feature = "encoding")]
+        {
+            fork.encoding = self.encoding;
+        }
+        fork.restore_cursor(self.save_cursor());
+        fork.pending_offset = self.pending_offset;
+        Ok(fork)
+    }
+}
+
+impl EasyReader<File> {
+    /// Forks the reader over a new handle on its file (see `fork_with()`), e.g. to read it
+    /// from another thread without rebuilding the index.
+    ///
+    /// The file is opened again by its path, so only readers created with `EasyReader::open()`
+    /// can be forked: the others fail with `ErrorKind::Unsupported`. Fails with
+    /// `ErrorKind::NotFound` if the file at the path has been replaced since (e.g. rotated).
+    pub fn fork(&self) -> io::Result<Self> {
+        let path = self.path.clone().ok_or_else(|| {
+            Error::new(
+                ErrorKind::Unsupported,
+                "Only readers created with EasyReader::open() can be forked",
+            )
+        })?;
+        let file = File::open(&path)?;
+        let id = file_id(&file.metadata()?);
+        if id != file_id(&self.file.metadata()?) {
+            return Err(Error::new(
+                ErrorKind::NotFound,
+                "The file has been replaced since it was opened",
+            ));
+        }
+
+        let mut fork = self.fork_with(file)?;
+        fork.track_path(path, id);
+        Ok(fork)
+    }
+}
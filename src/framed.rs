@@ -0,0 +1,184 @@
+use crate::ReaderError;
+use std::io::{self, prelude::*, SeekFrom};
+
+// An LEB128 u64 takes up to 10 bytes
+const MAX_VARINT_LEN: usize = 10;
+
+/// How the length of the payload is encoded before each record of a [`FramedReader`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LengthPrefix {
+    /// 4 bytes, little-endian.
+    U32Le,
+    /// 4 bytes, big-endian (network order).
+    U32Be,
+    /// An unsigned LEB128 varint, as in Protocol Buffers.
+    Varint,
+}
+
+/// Navigates through binary records each preceded by the length of its payload, forward and
+/// backward like the lines of an `EasyReader`, returning the payloads.
+///
+/// The records can't be told apart from the end: the start of each record is kept once
+/// passed, which makes moving backward immediate. `eof()` and `record()` read the prefixes
+/// (only) as far as needed, `build_index()` reads all of them.
+pub struct FramedReader<R> {
+    file: R,
+    file_size: u64,
+    prefix: LengthPrefix,
+    // The starts of the records from the first one, followed by the end of the last one once
+    // reached
+    starts: Vec<u64>,
+    // The number of the current record, None at the BOF (the record count at the EOF)
+    current: Option<usize>,
+}
+
+impl<R: Read + Seek> FramedReader<R> {
+    pub fn new(mut file: R, prefix: LengthPrefix) -> io::Result<Self> {
+        let file_size = file.seek(SeekFrom::End(0))?;
+        Ok(FramedReader {
+            file,
+            file_size,
+            prefix,
+            starts: vec![0],
+            current: None,
+        })
+    }
+
+    pub fn bof(&mut self) -> &mut Self {
+        self.current = None;
+        self
+    }
+
+    /// Moves after the last record, reading the prefixes of the records not passed yet.
+    pub fn eof(&mut self) -> io::Result<&mut Self> {
+        self.build_index()?;
+        self.current = Some(self.starts.len() - 1);
+        Ok(self)
+    }
+
+    /// Reads the prefixes of all the records: `record()` and `records_count()` are immediate
+    /// afterwards.
+    pub fn build_index(&mut self) -> io::Result<&mut Self> {
+        self.scan_to(usize::MAX)?;
+        Ok(self)
+    }
+
+    /// The number of records, read from their prefixes.
+    pub fn records_count(&mut self) -> io::Result<usize> {
+        self.build_index()?;
+        Ok(self.starts.len() - 1)
+    }
+
+    pub fn next_record(&mut self) -> io::Result<Option<Vec<u8>>> {
+        let next = self.current.map_or(0, |current| current + 1);
+        self.read_record(next)
+    }
+
+    pub fn prev_record(&mut self) -> io::Result<Option<Vec<u8>>> {
+        match self.current {
+            Some(current) if current > 0 => self.read_record(current - 1),
+            _ => Ok(None),
+        }
+    }
+
+    pub fn current_record(&mut self) -> io::Result<Option<Vec<u8>>> {
+        match self.current {
+            Some(current) => self.read_record(current),
+            None => Ok(None),
+        }
+    }
+
+    /// Moves to the `n`-th (zero-based) record and returns its payload, `None` (without
+    /// moving) if there aren't that many records.
+    pub fn record(&mut self, n: usize) -> io::Result<Option<Vec<u8>>> {
+        self.read_record(n)
+    }
+
+    /// The (start, end) byte offsets of the current record, its prefix included.
+    pub fn current_offsets(&self) -> (u64, u64) {
+        match self.current {
+            Some(current) if current + 1 < self.starts.len() => {
+                (self.starts[current], self.starts[current + 1])
+            }
+            Some(_) => (self.file_size, self.file_size),
+            None => (0, 0),
+        }
+    }
+
+    pub fn get_ref(&self) -> &R {
+        &self.file
+    }
+
+    pub fn into_inner(self) -> R {
+        self.file
+    }
+
+    fn read_record(&mut self, n: usize) -> io::Result<Option<Vec<u8>>> {
+        self.scan_to(n.saturating_add(1))?;
+        if n >= self.starts.len() - 1 {
+            return Ok(None);
+        }
+
+        let (start, end) = (self.starts[n], self.starts[n + 1]);
+        let (prefix_len, _) = self.read_prefix(start)?;
+        let mut payload = vec![0; (end - start) as usize - prefix_len];
+        self.file.seek(SeekFrom::Start(start + prefix_len as u64))?;
+        self.file.read_exact(&mut payload)?;
+        self.current = Some(n);
+        Ok(Some(payload))
+    }
+
+    // Reads the prefixes until the start of the `n`-th record is known (or the EOF)
+    fn scan_to(&mut self, n: usize) -> io::Result<()> {
+        while self.starts.len() <= n {
+            let start = self.starts[self.starts.len() - 1];
+            if start == self.file_size {
+                break;
+            }
+            let (prefix_len, payload_len) = self.read_prefix(start)?;
+            let end = (start + prefix_len as u64)
+                .checked_add(payload_len)
+                .filter(|&end| end <= self.file_size)
+                .ok_or(ReaderError::TruncatedRecord { offset: start })?;
+            self.starts.push(end);
+        }
+        Ok(())
+    }
+
+    // The length of the prefix at `start` and the payload length it holds
+    fn read_prefix(&mut self, start: u64) -> io::Result<(usize, u64)> {
+        let max_len = match self.prefix {
+            LengthPrefix::U32Le | LengthPrefix::U32Be => 4,
+            LengthPrefix::Varint => MAX_VARINT_LEN,
+        };
+        let len = (self.file_size - start).min(max_len as u64) as usize;
+        let mut bytes = [0; MAX_VARINT_LEN];
+        self.file.seek(SeekFrom::Start(start))?;
+        self.file.read_exact(&mut bytes[..len])?;
+
+        let truncated = || ReaderError::TruncatedPrefix { offset: start }.into();
+        let word = [bytes[0], bytes[1], bytes[2], bytes[3]];
+        match self.prefix {
+            LengthPrefix::U32Le | LengthPrefix::U32Be if len < 4 => Err(truncated()),
+            LengthPrefix::U32Le => Ok((4, u32::from_le_bytes(word) as u64)),
+            LengthPrefix::U32Be => Ok((4, u32::from_be_bytes(word) as u64)),
+            LengthPrefix::Varint => {
+                let mut value = 0u64;
+                for (i, byte) in bytes[..len].iter().enumerate() {
+                    let bits = (byte & 0x7f) as u64;
+                    if i == MAX_VARINT_LEN - 1 && bits > 1 {
+                        break;
+                    }
+                    value |= bits << (7 * i);
+                    if byte & 0x80 == 0 {
+                        return Ok((i + 1, value));
+                    }
+                }
+                if len < MAX_VARINT_LEN {
+                    return Err(truncated());
+                }
+                Err(ReaderError::PrefixOverflow { offset: start }.into())
+            }
+        }
+    }
+}
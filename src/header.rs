@@ -0,0 +1,49 @@
+use crate::{EasyReader, SCAN_BLOCK_SIZE};
+use std::io::{self, prelude::*};
+
+impl<R: Read + Seek> EasyReader<R> {
+    /// Skips the first `n` lines of the file (the header of a CSV/TSV file...): the reader then
+    /// sees the file as starting after them, so they're left out of the iteration, the random
+    /// lines, the line numbers and the index (rebuilt if there's one). Offsets are relative to
+    /// the end of the header too, like for the partitions of `split_into()`, see
+    /// `header_size()`. `0` shows the whole file again.
+    ///
+    /// Counted from the start of the file, whatever was skipped before. The cursor moves to
+    /// the BOF.
+    pub fn skip_header_lines(&mut self, n: usize) -> io::Result<&mut Self> {
+        let file_size = self.file_size + self.header_size;
+        self.header_size = 0;
+        self.file_size = file_size;
+        self.clear_chunk_cache();
+
+        let mut lines = 0;
+        let mut pos = 0;
+        let mut header_size = if n == 0 { 0 } else { file_size };
+        'scan: while lines < n && pos < file_size {
+            let len = (file_size - pos).min(SCAN_BLOCK_SIZE as u64) as usize;
+            let block = self.read_bytes(pos, len)?;
+            for i in 0..block.len() {
+                if self.ends_delimiter(&block, pos, i)? {
+                    lines += 1;
+                    if lines == n {
+                        header_size = pos + i as u64 + 1;
+                        break 'scan;
+                    }
+                }
+            }
+            pos += len as u64;
+        }
+
+        self.header_size = header_size;
+        self.pending_offset = None;
+        self.start_over(file_size - header_size)?;
+        Ok(self)
+    }
+
+    /// Where the reader starts in the file: after the header skipped by `skip_header_lines()`,
+    /// at the start of the view of `view_lines()`. To add to the offsets of the reader to get
+    /// the offsets in the file.
+    pub fn header_size(&self) -> u64 {
+        self.header_size
+    }
+}
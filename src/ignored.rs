@@ -0,0 +1,135 @@
+use crate::{EasyReader, ReadMode};
+use std::io::{self, prelude::*};
+
+// How many random lines are drawn before drawing among the lines kept (with an index) or
+// looking for a line around the last one (without), in case most lines are ignored
+#[cfg(feature = "rand")]
+const MAX_IGNORED_DRAWS: u32 = 64;
+
+impl<R: Read + Seek> EasyReader<R> {
+    /// Skips the lines starting with `prefix` (e.g. `"#"` or `"//"`): the line reads (in both
+    /// directions, the random lines and the iterators) move past them as if they weren't in
+    /// the file. An empty `prefix` turns the skipping off.
+    ///
+    /// A comment line which is the current one gives way to the closest line after it (before
+    /// it if there's none), one drawn at random is drawn again (see `skip_blank_lines()`).
+    /// Like for `continuation()`, offsets, the index
+    /// and the line counts are about all the lines of the file. `prefix` is compared to the
+    /// bytes of the lines as is, so it shouldn't be UTF-8 with another `encoding()`.
+    pub fn comment_prefix(&mut self, prefix: &str) -> &mut Self {
+        self.comment_prefix = Some(prefix.as_bytes().to_vec()).filter(|prefix| !prefix.is_empty());
+        self
+    }
+
+    /// Skips the empty lines, and those holding nothing but ASCII whitespace, like
+    /// `comment_prefix()` skips the comment lines.
+    ///
+    /// The random lines drawn with an index are drawn again until they aren't blank, up to 64
+    /// times, and then drawn among the lines kept (read from the whole file), so all the other
+    /// lines stay equally likely. `shuffled_lines()` and `batches()` leave them out of their
+    /// permutations too. Without an index, the 64th blank line drawn gives way to the closest
+    /// line after it instead: the lines following long runs of ignored lines are more likely.
+    pub fn skip_blank_lines(&mut self, enabled: bool) -> &mut Self {
+        self.skip_blank_lines = enabled;
+        self
+    }
+
+    pub(crate) fn ignores_lines(&self) -> bool {
+        self.comment_prefix.is_some() || self.skip_blank_lines
+    }
+
+    // Moves the cursor like navigate() until it's on a line which isn't ignored, and back to
+    // where it was if there's none
+    pub(crate) fn navigate_unignored(&mut self, mut mode: ReadMode) -> io::Result<bool> {
+        let cursor = self.save_cursor();
+        // Forward then backward from a line read in no direction (the current one...)
+        let mut around = false;
+        #[cfg(feature = "rand")]
+        let mut draws = 1;
+        loop {
+            if !self.step(mode.clone())? {
+                if around && mode == ReadMode::Next {
+                    mode = ReadMode::Prev;
+                    continue;
+                }
+                self.restore_cursor(cursor);
+                return Ok(false);
+            }
+            if !self.on_ignored_line()? {
+                return Ok(true);
+            }
+            mode = match mode {
+                ReadMode::Prev => ReadMode::Prev,
+                ReadMode::Next => ReadMode::Next,
+                #[cfg(feature = "rand")]
+                ReadMode::Random if draws < MAX_IGNORED_DRAWS => {
+                    draws += 1;
+                    ReadMode::Random
+                }
+                #[cfg(all(feature = "rand", feature = "index"))]
+                ReadMode::Random if self.index().is_some() => {
+                    let drawn = self.draw_kept_line()?;
+                    if !drawn {
+                        self.restore_cursor(cursor);
+                    }
+                    return Ok(drawn);
+                }
+                _ => {
+                    around = true;
+                    ReadMode::Next
+                }
+            };
+        }
+    }
+
+    // Whether the line the cursor is on is ignored, reading no more of it than needed
+    pub(crate) fn on_ignored_line(&mut self) -> io::Result<bool> {
+        let (start, end) = (self.current_start_line_offset, self.current_end_line_offset);
+        if self.skip_blank_lines {
+            let mut pos = start;
+            while pos < end {
+                let len = (end - pos).min(self.chunk_size.max(1) as u64) as usize;
+                let bytes = self.read_bytes(pos, len)?;
+                if !bytes.iter().all(u8::is_ascii_whitespace) {
+                    break;
+                }
+                pos += len as u64;
+            }
+            if pos >= end {
+                return Ok(true);
+            }
+        }
+        match self.comment_prefix.clone() {
+            Some(prefix) if end - start >= prefix.len() as u64 => {
+                Ok(self.read_bytes(start, prefix.len())? == prefix)
+            }
+            _ => Ok(false),
+        }
+    }
+
+    // Moves to a line drawn among those which aren't ignored, with an index
+    #[cfg(all(feature = "rand", feature = "index"))]
+    fn draw_kept_line(&mut self) -> io::Result<bool> {
+        let kept = self.kept_lines()?;
+        if kept.is_empty() {
+            return Ok(false);
+        }
+        let line = kept[self.random_below(kept.len() as u64) as usize];
+        match self.index_entry(line)? {
+            Some((start, end)) => {
+                self.random_draw = line;
+                self.set_current_line(start, end);
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+
+    pub(crate) fn is_ignored(&self, line: &[u8]) -> bool {
+        (self.skip_blank_lines && line.iter().all(u8::is_ascii_whitespace))
+            || self
+                .comment_prefix
+                .as_ref()
+                .is_some_and(|prefix| line.starts_with(prefix))
+    }
+}
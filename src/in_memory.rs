@@ -0,0 +1,68 @@
+use crate::{trim::trimmed, utf8_error, EasyReader, ReadMode, Trim};
+use std::{
+    io::{self, Error, ErrorKind},
+    str,
+};
+
+// Lines borrowed from the buffer (a Vec, a memory map...), instead of being read into a String.
+// The lines joined by continuation() or transcoded from another encoding() aren't in the buffer
+// as such, their reads fail with ErrorKind::Unsupported.
+impl<T: AsRef<[u8]>> EasyReader<io::Cursor<T>> {
+    /// Like `prev_line()`, borrowing the line from the buffer the reader is over instead of
+    /// copying it into a `String`.
+    ///
+    /// Fails with `ErrorKind::Unsupported` with `continuation()` or with an `encoding()` other
+    /// than UTF-8, as the lines returned aren't in the buffer as such.
+    pub fn prev_line_ref(&mut self) -> io::Result<Option<&str>> {
+        self.read_line_ref(ReadMode::Prev)
+    }
+
+    /// Like `current_line()`, borrowing the line like `prev_line_ref()`.
+    pub fn current_line_ref(&mut self) -> io::Result<Option<&str>> {
+        self.read_line_ref(ReadMode::Current)
+    }
+
+    /// Like `next_line()`, borrowing the line like `prev_line_ref()`.
+    pub fn next_line_ref(&mut self) -> io::Result<Option<&str>> {
+        self.read_line_ref(ReadMode::Next)
+    }
+
+    /// Like `random_line()`, borrowing the line like `prev_line_ref()`.
+    #[cfg(feature = "rand")]
+    pub fn random_line_ref(&mut self) -> io::Result<Option<&str>> {
+        self.read_line_ref(ReadMode::Random)
+    }
+
+    fn read_line_ref(&mut self, mode: ReadMode) -> io::Result<Option<&str>> {
+        if self.continuation.is_some() {
+            return Err(Error::new(
+                ErrorKind::Unsupported,
+                "The lines joined by continuation() can't be borrowed",
+            ));
+        }
+        #[cfg(feature = "encoding")]
+        if self.encoding.is_some() {
+            return Err(Error::new(
+                ErrorKind::Unsupported,
+                "The lines transcoded from another encoding can't be borrowed",
+            ));
+        }
+        if !self.navigate(mode)? {
+            return Ok(None);
+        }
+        let (start, end) = (self.current_start_line_offset, self.current_end_line_offset);
+        self.check_line_length(start, end - start)?;
+        // The terminator follows the line in the buffer, the line isn't trimmed with it
+        let (terminator_len, trim) = match self.keep_terminators {
+            true => (self.terminator_after(end)?.len(), Trim::None),
+            false => (0, self.trim),
+        };
+        let base = self.header_size as usize;
+        let bytes = &self.file.get_ref().as_ref()
+            [base + start as usize..base + end as usize + terminator_len];
+        let bytes = &bytes[trimmed(bytes, trim)];
+        str::from_utf8(bytes)
+            .map(Some)
+            .map_err(|err| utf8_error(start, end, err, bytes.to_vec()))
+    }
+}
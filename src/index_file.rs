@@ -0,0 +1,131 @@
+use crate::{fnv1a, EasyReader, LineIndex, ReaderError, FNV_OFFSET_BASIS};
+use std::{
+    convert::TryInto,
+    ffi::OsString,
+    fs::{self, File},
+    io::{self, prelude::*, BufWriter, Error, ErrorKind},
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+const INDEX_FILE_MAGIC: &[u8; 6] = b"ERIDX\0";
+const INDEX_FORMAT_VERSION: u16 = 3;
+
+// Format (little endian): the magic bytes and the format version, then the size and the
+// fingerprint of the file, a hash of the line delimiter, the number of lines and their start
+// offsets, all u64, and lastly a checksum of all of the above
+
+impl<R: Read + Seek> EasyReader<R> {
+    /// Saves the index to `path`, to be loaded back with `load_index()` instead of being
+    /// rebuilt. Fails with `ErrorKind::InvalidInput` if the reader isn't indexed, and with
+    /// `ErrorKind::Unsupported` if its records end at a `boundary()`, which the index file
+    /// can't tell apart from another one.
+    pub fn save_index<P: AsRef<Path>>(&mut self, path: P) -> io::Result<()> {
+        if self.index().is_none() {
+            return Err(Error::new(ErrorKind::InvalidInput, "No index to save"));
+        }
+        let delimiter = self.delimiter.hash().ok_or_else(|| {
+            Error::new(
+                ErrorKind::Unsupported,
+                "An index of records ending at a boundary can't be saved",
+            )
+        })?;
+
+        let line_starts = Arc::clone(&self.line_starts);
+        let fingerprint = self.fingerprint()?;
+
+        let mut file = BufWriter::new(File::create(path)?);
+        let mut checksum = FNV_OFFSET_BASIS;
+        let mut write = |bytes: &[u8]| {
+            checksum = fnv1a(checksum, bytes);
+            file.write_all(bytes)
+        };
+        write(INDEX_FILE_MAGIC)?;
+        write(&INDEX_FORMAT_VERSION.to_le_bytes())?;
+        write(&self.file_size.to_le_bytes())?;
+        write(&fingerprint.to_le_bytes())?;
+        write(&delimiter.to_le_bytes())?;
+        write(&(line_starts.len() as u64).to_le_bytes())?;
+        for start in line_starts.iter() {
+            write(&start.to_le_bytes())?;
+        }
+        file.write_all(&checksum.to_le_bytes())?;
+        file.flush()
+    }
+
+    /// Loads an index saved with `save_index()`.
+    ///
+    /// Fails with a `ReaderError::IndexStale` error if the index doesn't match the file (its
+    /// size or its first and last bytes changed), and with `ErrorKind::InvalidData` if it
+    /// doesn't match the line terminators (see `delimiter()`), is corrupted or comes from an
+    /// incompatible version, leaving the reader as it was.
+    pub fn load_index<P: AsRef<Path>>(&mut self, path: P) -> io::Result<&mut Self> {
+        let bytes = fs::read(path)?;
+        let invalid = |reason: &str| Error::new(ErrorKind::InvalidData, reason.to_string());
+
+        let header_len = INDEX_FILE_MAGIC.len() + 2;
+        if bytes.len() < header_len + 8 || !bytes.starts_with(INDEX_FILE_MAGIC) {
+            return Err(invalid("Not an index file"));
+        }
+        let version = u16::from_le_bytes([bytes[header_len - 2], bytes[header_len - 1]]);
+        if version != INDEX_FORMAT_VERSION {
+            return Err(invalid("Unsupported index format version"));
+        }
+        let (content, checksum) = bytes.split_at(bytes.len() - 8);
+        if fnv1a(FNV_OFFSET_BASIS, content).to_le_bytes() != checksum {
+            return Err(invalid("Corrupted index file"));
+        }
+
+        let mut words = content[header_len..].chunks(8).map(|word| {
+            word.try_into()
+                .map(u64::from_le_bytes)
+                .map_err(|_| invalid("Truncated index file"))
+        });
+        let mut next_word = || {
+            words
+                .next()
+                .unwrap_or_else(|| Err(invalid("Truncated index file")))
+        };
+
+        if next_word()? != self.file_size || next_word()? != self.fingerprint()? {
+            return Err(ReaderError::IndexStale.into());
+        }
+        if Some(next_word()?) != self.delimiter.hash() {
+            return Err(invalid("The index was built with other line terminators"));
+        }
+        let lines = next_word()?;
+        if lines > self.file_size + 1 {
+            return Err(invalid("Corrupted index file"));
+        }
+
+        // Every line but the first starts after the previous one (and its terminator)
+        let mut line_starts = LineIndex::new(self.compressed_index);
+        for _ in 0..lines {
+            let start = next_word()?;
+            let in_order = match line_starts.last() {
+                Some(previous) => start > previous,
+                None => start == 0,
+            };
+            if !in_order || start >= self.file_size {
+                return Err(invalid("Corrupted index file"));
+            }
+            line_starts.push(start);
+        }
+        if next_word().is_ok() {
+            return Err(invalid("Corrupted index file"));
+        }
+
+        self.set_index(line_starts)?;
+        Ok(self)
+    }
+}
+
+impl EasyReader<File> {
+    /// Path of the sidecar index of the file at `path` (`<path>.eridx`), loaded automatically
+    /// by `EasyReader::open()` when it's there and up to date.
+    pub fn sidecar_index_path<P: AsRef<Path>>(path: P) -> PathBuf {
+        let mut sidecar = OsString::from(path.as_ref().as_os_str());
+        sidecar.push(".eridx");
+        PathBuf::from(sidecar)
+    }
+}
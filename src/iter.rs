@@ -0,0 +1,248 @@
+use crate::{EasyReader, ReaderError};
+use std::{
+    collections::VecDeque,
+    io::{self, prelude::*},
+    iter::FusedIterator,
+};
+
+// How many lines are read at once when iterating forward
+const READ_AHEAD_LINES: usize = 1024;
+
+/// An iterator over the lines following the cursor, see `EasyReader::iter()`.
+///
+/// Each item moves the cursor like `next_line()` does. The iteration stops after the first
+/// error, the cursor being left on the last line read successfully.
+///
+/// The lines are split out of large blocks read ahead (like `next_lines()` does), unless the
+/// lines have to be read one by one: with `continuation()`, `comment_prefix()`,
+/// `skip_blank_lines()`, `keep_terminators()`, a trace being recorded, a truncation or stale
+/// index policy, `auto_refresh()` or a lazy index.
+pub struct EasyReaderIter<'a, R> {
+    reader: &'a mut EasyReader<R>,
+    failed: bool,
+    // The lines read ahead with their offsets, then the error which stopped the read, if any
+    ahead: VecDeque<(String, (u64, u64))>,
+    error: Option<io::Error>,
+}
+
+impl<R: Read + Seek> Iterator for EasyReaderIter<'_, R> {
+    type Item = io::Result<String>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.failed {
+            return None;
+        }
+        if self.ahead.is_empty() && self.error.is_none() && self.reader.can_read_ahead() {
+            let (mut lines, mut offsets) = (Vec::new(), Vec::new());
+            let read = self
+                .reader
+                .read_ahead(READ_AHEAD_LINES, &mut lines, &mut offsets);
+            self.ahead.extend(lines.into_iter().zip(offsets));
+            self.error = read.err();
+            if self.ahead.is_empty() && self.error.is_none() {
+                return None;
+            }
+        }
+
+        if let Some((line, (start, end))) = self.ahead.pop_front() {
+            self.reader.set_current_line(start, end);
+            return Some(Ok(line));
+        }
+        let line = match self.error.take() {
+            Some(err) => Some(Err(err)),
+            None => self.reader.next_line().transpose(),
+        };
+        self.failed = matches!(line, Some(Err(_)));
+        line
+    }
+}
+
+impl<R: Read + Seek> FusedIterator for EasyReaderIter<'_, R> {}
+
+/// An iterator over the lines from the last one to the first one, see `EasyReader::rev_lines()`.
+///
+/// Each item moves the cursor like `prev_line()` does. The iteration stops after the first
+/// error, the cursor being left on the last line read successfully.
+pub struct RevLines<'a, R> {
+    reader: &'a mut EasyReader<R>,
+    failed: bool,
+}
+
+impl<R: Read + Seek> Iterator for RevLines<'_, R> {
+    type Item = io::Result<String>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.failed {
+            return None;
+        }
+        let line = self.reader.prev_line().transpose();
+        self.failed = matches!(line, Some(Err(_)));
+        line
+    }
+}
+
+impl<R: Read + Seek> FusedIterator for RevLines<'_, R> {}
+
+/// Like [`RevLines`], but owning the reader, see `EasyReader::into_rev_lines()`.
+pub struct IntoRevLines<R> {
+    reader: EasyReader<R>,
+    failed: bool,
+}
+
+impl<R> IntoRevLines<R> {
+    /// Gives the reader back, its cursor on the last line read.
+    pub fn into_inner(self) -> EasyReader<R> {
+        self.reader
+    }
+}
+
+impl<R: Read + Seek> Iterator for IntoRevLines<R> {
+    type Item = io::Result<String>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.failed {
+            return None;
+        }
+        let line = self.reader.prev_line().transpose();
+        self.failed = matches!(line, Some(Err(_)));
+        line
+    }
+}
+
+impl<R: Read + Seek> FusedIterator for IntoRevLines<R> {}
+
+type SkipCallback<'a> = Box<dyn FnMut(&io::Error) + 'a>;
+
+/// An iterator over the lines following the cursor which skips the lines that can't be
+/// returned (not valid UTF-8, longer than `max_line_length()`...), see
+/// `EasyReader::lenient_iter()`.
+///
+/// Each item moves the cursor like `next_line()` does. The iteration stops after the first
+/// other error (I/O...).
+pub struct LenientLines<'a, R> {
+    reader: &'a mut EasyReader<R>,
+    failed: bool,
+    skipped: u64,
+    on_skip: Option<SkipCallback<'a>>,
+}
+
+impl<'a, R> LenientLines<'a, R> {
+    /// Calls `on_skip` with the error of each line skipped, e.g. to log it.
+    pub fn on_skip<F: FnMut(&io::Error) + 'a>(mut self, on_skip: F) -> Self {
+        self.on_skip = Some(Box::new(on_skip));
+        self
+    }
+
+    /// The number of lines skipped so far.
+    pub fn skipped(&self) -> u64 {
+        self.skipped
+    }
+}
+
+impl<R: Read + Seek> Iterator for LenientLines<'_, R> {
+    type Item = io::Result<String>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.failed {
+            return None;
+        }
+        loop {
+            match self.reader.next_line() {
+                Ok(line) => return line.map(Ok),
+                Err(err) if ReaderError::of(&err).is_some_and(ReaderError::is_invalid_line) => {
+                    self.skipped += 1;
+                    if let Some(on_skip) = &mut self.on_skip {
+                        on_skip(&err);
+                    }
+                }
+                Err(err) => {
+                    self.failed = true;
+                    return Some(Err(err));
+                }
+            }
+        }
+    }
+}
+
+impl<R: Read + Seek> FusedIterator for LenientLines<'_, R> {}
+
+impl<'a, R: Read + Seek> IntoIterator for &'a mut EasyReader<R> {
+    type Item = io::Result<String>;
+    type IntoIter = EasyReaderIter<'a, R>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<R: Read + Seek> EasyReader<R> {
+    /// Iterates over the lines following the cursor (the whole file after `bof()`).
+    pub fn iter(&mut self) -> EasyReaderIter<'_, R> {
+        EasyReaderIter {
+            reader: self,
+            failed: false,
+            ahead: VecDeque::new(),
+            error: None,
+        }
+    }
+
+    /// Like `iter()`, but skips the lines that can't be returned instead of stopping at them,
+    /// to go through dirty data to the end:
+    ///
+    /// ```no_run
+    /// use easy_reader::EasyReader;
+    /// use std::{fs::File, io};
+    ///
+    /// fn count_words() -> io::Result<usize> {
+    ///     let mut reader = EasyReader::new(File::open("resources/test-file-lf")?)?;
+    ///     let mut lines = reader.max_line_length(1 << 20).lenient_iter();
+    ///     let mut words = 0;
+    ///     for line in &mut lines {
+    ///         words += line?.split_whitespace().count();
+    ///     }
+    ///     eprintln!("{} lines skipped", lines.skipped());
+    ///     Ok(words)
+    /// }
+    /// ```
+    pub fn lenient_iter(&mut self) -> LenientLines<'_, R> {
+        LenientLines {
+            reader: self,
+            failed: false,
+            skipped: 0,
+            on_skip: None,
+        }
+    }
+
+    // Whether the lines following the cursor can be read ahead of next_line(), nothing having
+    // to be done for each of them
+    pub(crate) fn can_read_ahead(&self) -> bool {
+        #[cfg(feature = "index")]
+        if self.stale_index_policy.is_some() || self.lazy_index.is_some() {
+            return false;
+        }
+        self.continuation.is_none()
+            && !self.ignores_lines()
+            && !self.keep_terminators
+            && self.trace.is_none()
+            && self.truncation_policy.is_none()
+            && !self.auto_refresh
+    }
+
+    /// Iterates backwards over the whole file, starting from the EOF.
+    pub fn rev_lines(&mut self) -> RevLines<'_, R> {
+        self.eof();
+        RevLines {
+            reader: self,
+            failed: false,
+        }
+    }
+
+    /// Like `rev_lines()`, but consumes the reader, e.g. to return the iterator from a function.
+    pub fn into_rev_lines(mut self) -> IntoRevLines<R> {
+        self.eof();
+        IntoRevLines {
+            reader: self,
+            failed: false,
+        }
+    }
+}
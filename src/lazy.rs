@@ -0,0 +1,88 @@
+use crate::{EasyReader, ReadMode};
+use std::{collections::BTreeMap, io::prelude::*};
+
+// The lines met while navigating, by start offset: their end and the start of the following
+// line, once the reader has moved from one to the other
+pub(crate) type LazyIndex = BTreeMap<u64, (u64, Option<u64>)>;
+
+impl<R: Read + Seek> EasyReader<R> {
+    /// Remembers the lines met while navigating, without building an index upfront: moving
+    /// again through them (backwards too) doesn't scan the file anymore.
+    ///
+    /// Every line read takes a few dozen bytes of memory, which are freed by disabling it or
+    /// building a full index.
+    pub fn lazy_index(&mut self, enabled: bool) -> &mut Self {
+        self.lazy_index = if enabled {
+            self.lazy_index.take().or_else(|| Some(LazyIndex::new()))
+        } else {
+            None
+        };
+        self
+    }
+
+    // The line following the current one, if the reader already moved from one to the other
+    pub(crate) fn lazy_next(&self) -> Option<(u64, u64)> {
+        let lines = self.lazy_index.as_ref()?;
+        match lines.get(&self.current_start_line_offset) {
+            Some(&(end, Some(next))) if !self.at_bof && end == self.current_end_line_offset => {
+                lines.get(&next).map(|&(next_end, _)| (next, next_end))
+            }
+            _ => None,
+        }
+    }
+
+    // The line preceding the current one, if the reader already moved from one to the other
+    pub(crate) fn lazy_prev(&self) -> Option<(u64, u64)> {
+        let lines = self.lazy_index.as_ref()?;
+        let start = self.current_start_line_offset;
+        match lines.range(..start).next_back() {
+            Some((&prev, &(prev_end, Some(next)))) if next == start => Some((prev, prev_end)),
+            _ => None,
+        }
+    }
+
+    // The end of the line starting at `start`, if it has been met
+    pub(crate) fn lazy_end(&self, start: u64) -> Option<u64> {
+        self.lazy_index.as_ref()?.get(&start).map(|&(end, _)| end)
+    }
+
+    // Records the line the cursor moved to and, if it moved from one line to its neighbor,
+    // that they follow each other
+    pub(crate) fn remember_line(&mut self, from: (u64, u64, bool), mode: &ReadMode) {
+        let (start, end) = (self.current_start_line_offset, self.current_end_line_offset);
+        let lines = match self.lazy_index.as_mut() {
+            Some(lines) if !self.indexed => lines,
+            _ => return,
+        };
+        let next = lines.get(&start).and_then(|&(_, next)| next);
+        lines.insert(start, (end, next));
+
+        // Moving from the BOF or the EOF, or from a line already forgotten, links nothing
+        let (from_start, from_end, from_bof) = from;
+        if from_bof || lines.get(&from_start).map(|&(end, _)| end) != Some(from_end) {
+            return;
+        }
+        match mode {
+            ReadMode::Next if from_start < start => {
+                lines.insert(from_start, (from_end, Some(start)));
+            }
+            ReadMode::Prev if start < from_start => {
+                lines.insert(start, (end, Some(from_start)));
+            }
+            _ => (),
+        }
+    }
+
+    // Forgets the line ending at the EOF, which the data appended after it may extend
+    pub(crate) fn forget_last_line(&mut self) {
+        let file_size = self.file_size;
+        if let Some(lines) = self.lazy_index.as_mut() {
+            match lines.iter().next_back() {
+                Some((&start, &(end, _))) if end == file_size => {
+                    lines.remove(&start);
+                }
+                _ => (),
+            }
+        }
+    }
+}
@@ -17,6 +17,9 @@
 //! EasyReader by default does not generate an index, it just searches for line terminators from time to time, this allows it to be used with very large files without "startup" times and excessive RAM consumption.
 //! However, the lack of an index makes the reading slower and does not allow to take random lines with a perfect distribution, for these reasons there's a method to generate it; the start time will be slower, but all the following readings will use it and will therefore be faster (excluding the index build time, reading times are a bit longer but still comparable to those of a sequential forward reading through Lines) and in the random reading case the lines will be taken with a perfect distribution.
 //! By the way, it's not advisable to generate the index for very large files, as an excessive RAM consumption could occur.
+//! The index and the random lines are optional (`index` and `rand` features, enabled by default): with `default-features = false` the only dependency left is `libc` and the lines are located by scanning only.
+//!
+//! With the `simd` feature, the index builds and the line counts search the line terminators with SIMD instructions (through `memchr`), as does `find_next_literal()` with the text it looks for.
 //!
 //! ### Example: basic usage
 //!
@@ -35,6 +38,7 @@
 //!     let mut reader = EasyReader::new(file)?;
 //!
 //!     // Generate index (optional)
+//!     #[cfg(feature = "index")]
 //!     reader.build_index();
 //!
 //!     // Move through the lines
@@ -78,6 +82,7 @@
 //!     let mut reader = EasyReader::new(file)?;
 //!
 //!     // Generate index (optional)
+//!     #[cfg(feature = "index")]
 //!     reader.build_index();
 //!
 //!     #[cfg(feature = "rand")]
@@ -88,13 +93,142 @@
 //! }
 //! ```
 
-use fnv::FnvHashMap;
+#[cfg(feature = "index")]
+use std::sync::Arc;
+use std::{
+    collections::BTreeMap,
+    convert::TryFrom,
+    io::{self, prelude::*, Error, ErrorKind, SeekFrom},
+    mem,
+    path::PathBuf,
+    str::Utf8Error,
+};
+
+mod advice;
+#[cfg(feature = "tokio")]
+mod async_reader;
+mod batch;
+mod bookmarks;
+#[cfg(feature = "index")]
+mod budget;
+#[cfg(feature = "bytes")]
+mod bytes_lines;
+mod cache;
+mod cancel;
+mod columns;
+mod continuation;
+mod cursor;
+mod delimiter;
+#[cfg(feature = "encoding")]
+mod encoding;
+mod error;
+mod estimate;
+#[cfg(feature = "faulty")]
+mod faulty;
+mod follow;
+mod fork;
+mod framed;
+mod header;
+mod ignored;
+mod in_memory;
+#[cfg(feature = "index")]
+mod index_file;
+mod iter;
+#[cfg(feature = "index")]
+mod lazy;
+mod line;
+mod line_buf;
+mod line_index;
+mod pager;
+#[cfg(all(feature = "rayon", feature = "index"))]
+mod par_lines;
+#[cfg(feature = "index")]
+mod parallel;
+mod pool;
+mod position;
+#[cfg(feature = "index")]
+mod progress;
 #[cfg(feature = "rand")]
-use rand::Rng;
-use std::io::{self, prelude::*, Error, ErrorKind, SeekFrom};
+mod random;
+mod refresh;
+mod rotation;
+mod scan;
+mod search;
+mod sections;
+mod shared;
+#[cfg(feature = "index")]
+mod shuffle;
+mod skip;
+mod snapshot;
+#[cfg(feature = "index")]
+mod sparse;
+#[cfg(any(unix, windows))]
+mod split;
+mod spool;
+#[cfg(feature = "index")]
+mod stale;
+mod stats;
+mod trace;
+mod trim;
+mod truncation;
+mod view;
+mod viewport;
+#[cfg(feature = "index")]
+mod watcher;
+
+pub use advice::AccessPattern;
+#[cfg(feature = "tokio")]
+pub use async_reader::AsyncEasyReader;
+#[cfg(feature = "index")]
+pub use budget::IndexStrategy;
+pub use cancel::Cancel;
+pub use cursor::LineCursor;
+pub use delimiter::{Boundary, LineEnding};
+#[cfg(feature = "encoding")]
+pub use encoding_rs;
+pub use error::ReaderError;
+pub use estimate::{ApproxLine, LineCountEstimate};
+#[cfg(feature = "faulty")]
+pub use faulty::{Fault, FaultyReader};
+pub use follow::Follow;
+pub use framed::{FramedReader, LengthPrefix};
+pub use iter::{EasyReaderIter, IntoRevLines, LenientLines, RevLines};
+pub use line::Line;
+pub use line_buf::{LineBuf, LineSink};
+pub use pager::{Page, Pager};
+pub use pool::{PooledReader, ReaderPool};
+pub use position::Position;
+#[cfg(feature = "index")]
+pub use progress::PartialIndex;
+pub use search::LineContext;
+pub use shared::ReadAt;
+#[cfg(feature = "index")]
+pub use shuffle::{Batches, IntoBatches, ShuffledLines};
+#[cfg(any(unix, windows))]
+pub use split::Partition;
+pub use spool::Spooled;
+#[cfg(feature = "index")]
+pub use stale::StaleIndexPolicy;
+pub use stats::ReaderStats;
+pub use trace::Trace;
+pub use trim::Trim;
+pub use truncation::TruncationPolicy;
+pub use viewport::Viewport;
+#[cfg(feature = "index")]
+pub use watcher::IndexedWatcher;
+
+use delimiter::Delimiter;
+use line_index::LineIndex;
 
 const CR_BYTE: u8 = b'\r';
 const LF_BYTE: u8 = b'\n';
+const SCAN_BLOCK_SIZE: usize = 64 * 1024;
+const FINGERPRINT_SAMPLE_SIZE: usize = 4096;
+const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0100_0000_01b3;
+
+// The (start, end) offsets of a line
+type LineSpan = (u64, u64);
 
 #[derive(Clone, PartialEq)]
 enum ReadMode {
@@ -103,35 +237,135 @@ enum ReadMode {
     Next,
     #[cfg(feature = "rand")]
     Random,
+    // The line containing the offset the cursor starts at
+    Containing,
 }
 
 pub struct EasyReader<R> {
     file: R,
     file_size: u64,
+    // The bytes skipped at the start of the file, the offsets being relative to their end
+    header_size: u64,
+    // Where the file ends for the reader, once narrowed to a view
+    view_end: Option<u64>,
     chunk_size: usize,
+    chunk_cache: Option<cache::ChunkCache>,
+    delimiter: Delimiter,
     current_start_line_offset: u64,
     current_end_line_offset: u64,
+    at_bof: bool,
+    #[cfg(feature = "index")]
     indexed: bool,
-    offsets_index: Vec<(usize, usize)>,
-    newline_map: FnvHashMap<usize, usize>,
+    #[cfg(feature = "index")]
+    line_starts: Arc<LineIndex>,
+    #[cfg(feature = "index")]
+    compressed_index: bool,
+    #[cfg(feature = "index")]
+    sparse_index: Option<Arc<sparse::SparseIndex>>,
+    #[cfg(feature = "index")]
+    lazy_index: Option<lazy::LazyIndex>,
+    #[cfg(feature = "index")]
+    partial_index: Option<LineIndex>,
+    #[cfg(feature = "index")]
+    index_fingerprint: Option<u64>,
+    #[cfg(feature = "index")]
+    stale_index_policy: Option<StaleIndexPolicy>,
+    section_anchors: Vec<(u64, u64)>,
+    bookmarks: BTreeMap<String, (u64, u64)>,
+    line_count_estimate: Option<LineCountEstimate>,
+    trace: Option<Trace>,
+    auto_refresh: bool,
+    reopen: Option<rotation::Reopen<R>>,
+    path: Option<PathBuf>,
+    truncation_policy: Option<TruncationPolicy>,
+    pending_offset: Option<u64>,
+    continuation: Option<Vec<u8>>,
+    comment_prefix: Option<Vec<u8>>,
+    skip_blank_lines: bool,
+    keep_terminators: bool,
+    trim: Trim,
+    max_line_length: Option<usize>,
+    line_buffer: Vec<u8>,
+    chunk_buffer: Vec<u8>,
+    stats: ReaderStats,
+    #[cfg(feature = "encoding")]
+    encoding: Option<&'static encoding_rs::Encoding>,
+    #[cfg(feature = "rand")]
+    random_draw: u64,
+    #[cfg(feature = "rand")]
+    rng: Option<Box<dyn rand::RngCore + Send + Sync>>,
+    #[cfg(feature = "rand")]
+    call_rng: Option<rand::rngs::StdRng>,
+    #[cfg(feature = "rand")]
+    unbiased_random: bool,
 }
 
 impl<R: Read + Seek> EasyReader<R> {
     pub fn new(mut file: R) -> Result<Self, Error> {
         let file_size = file.seek(SeekFrom::End(0))?;
         if file_size == 0 {
-            return Err(Error::new(ErrorKind::UnexpectedEof, "Empty file"));
+            return Err(ReaderError::EmptyFile.into());
         }
 
         Ok(EasyReader {
             file,
             file_size,
+            header_size: 0,
+            view_end: None,
             chunk_size: 200,
+            chunk_cache: None,
+            delimiter: Delimiter::default(),
             current_start_line_offset: 0,
             current_end_line_offset: 0,
+            at_bof: true,
+            #[cfg(feature = "index")]
             indexed: false,
-            offsets_index: Vec::new(),
-            newline_map: FnvHashMap::default(),
+            #[cfg(feature = "index")]
+            line_starts: Arc::new(LineIndex::default()),
+            #[cfg(feature = "index")]
+            compressed_index: false,
+            #[cfg(feature = "index")]
+            sparse_index: None,
+            #[cfg(feature = "index")]
+            lazy_index: None,
+            #[cfg(feature = "index")]
+            partial_index: None,
+            #[cfg(feature = "index")]
+            index_fingerprint: None,
+            #[cfg(feature = "index")]
+            stale_index_policy: None,
+            section_anchors: Vec::new(),
+            bookmarks: BTreeMap::new(),
+            line_count_estimate: None,
+            trace: None,
+            auto_refresh: false,
+            reopen: None,
+            path: None,
+            truncation_policy: None,
+            pending_offset: None,
+            continuation: None,
+            comment_prefix: None,
+            skip_blank_lines: false,
+            keep_terminators: false,
+            trim: Trim::None,
+            max_line_length: None,
+            line_buffer: Vec::new(),
+            chunk_buffer: Vec::new(),
+            // The seek to get the size of the file
+            stats: ReaderStats {
+                seeks: 1,
+                ..ReaderStats::default()
+            },
+            #[cfg(feature = "encoding")]
+            encoding: None,
+            #[cfg(feature = "rand")]
+            random_draw: 0,
+            #[cfg(feature = "rand")]
+            rng: None,
+            #[cfg(feature = "rand")]
+            call_rng: None,
+            #[cfg(feature = "rand")]
+            unbiased_random: false,
         })
     }
 
@@ -140,38 +374,61 @@ impl<R: Read + Seek> EasyReader<R> {
         self
     }
 
+    /// Caps the length in bytes of the lines read (terminators excluded): a longer line (e.g.
+    /// in a file without any line terminator) fails with a `ReaderError::RecordTooLong` before
+    /// being loaded in memory, the cursor having moved to it. Unlimited by default.
+    pub fn max_line_length(&mut self, length: usize) -> &mut Self {
+        self.max_line_length = Some(length);
+        self
+    }
+
+    /// Sets the byte terminating the lines (the records), LF by default: e.g. `b'\0'` for
+    /// NUL-delimited data (`find -print0`...). A CR before the terminator is only dropped with
+    /// LF.
+    ///
+    /// The lines change: the cursor goes back to the BOF and the index, if any, is dropped
+    /// (`build_index()` rebuilds it for the new terminator).
+    pub fn delimiter(&mut self, byte: u8) -> &mut Self {
+        self.set_delimiter(Delimiter::new(vec![byte]))
+    }
+
     pub fn bof(&mut self) -> &mut Self {
         self.current_start_line_offset = 0;
         self.current_end_line_offset = 0;
+        self.at_bof = true;
         self
     }
 
     pub fn eof(&mut self) -> &mut Self {
         self.current_start_line_offset = self.file_size;
         self.current_end_line_offset = self.file_size;
+        self.at_bof = false;
         self
     }
 
-    pub fn build_index(&mut self) -> io::Result<&mut Self> {
-        if self.file_size > usize::max_value() as u64 {
-            // 32bit ¯\_(ツ)_/¯
-            return Err(Error::new(
-                ErrorKind::InvalidData,
-                "File too large to build an index",
-            ));
-        }
-
-        while let Ok(Some(_line)) = self.next_line() {
-            self.offsets_index.push((
-                self.current_start_line_offset as usize,
-                self.current_end_line_offset as usize,
-            ));
-            self.newline_map.insert(
-                self.current_start_line_offset as usize,
-                self.offsets_index.len() - 1,
-            );
+    /// Moves the cursor to the byte `offset`, like `bof()` and `eof()` move it to the ends of
+    /// the file: the reader aligns to the line containing it on the next read. `next_line()`
+    /// (and `current_line()`) then return that line, `prev_line()` the one before it.
+    pub fn set_offset(&mut self, offset: u64) -> &mut Self {
+        if offset >= self.file_size {
+            return self.eof();
         }
-        self.indexed = true;
+        self.restore_cursor((offset, offset, false));
+        self.pending_offset = Some(offset);
+        self
+    }
+
+    #[cfg(feature = "index")]
+    pub fn build_index(&mut self) -> io::Result<&mut Self> {
+        self.index_lines(None, None)?;
+        Ok(self)
+    }
+
+    /// Like `build_index()`, but gives up as soon as `cancel` is cancelled, leaving the reader
+    /// unindexed and its cursor untouched.
+    #[cfg(feature = "index")]
+    pub fn build_index_cancellable(&mut self, cancel: &Cancel) -> io::Result<&mut Self> {
+        self.index_lines(None, Some(cancel))?;
         Ok(self)
     }
 
@@ -192,98 +449,594 @@ impl<R: Read + Seek> EasyReader<R> {
         self.read_line(ReadMode::Random)
     }
 
+    /// The (start, end) byte offsets of the current line, its terminator excluded: the line is
+    /// `end - start` bytes long. Both are 0 at the BOF and the file size at the EOF.
+    pub fn current_offsets(&self) -> (u64, u64) {
+        (self.current_start_line_offset, self.current_end_line_offset)
+    }
+
+    /// Returns the previous, current and next lines in one go, without moving the cursor.
+    pub fn neighbors(&mut self) -> io::Result<(Option<String>, String, Option<String>)> {
+        let (cursor, pending_offset) = (self.save_cursor(), self.pending_offset);
+        let neighbors = self
+            .resolve_offset(&ReadMode::Current)
+            .and_then(|_| self.read_neighbors());
+        self.restore_cursor(cursor);
+        self.pending_offset = pending_offset;
+        neighbors
+    }
+
+    /// Maps a byte offset to its (line, column) position, both zero-based (the column is in bytes).
+    ///
+    /// The EOF is at the end of the last line, or at the start of the line after it (the
+    /// number of lines, 0) if the file ends with a line terminator, like the cursor of a text
+    /// editor.
+    pub fn position_of(&mut self, offset: u64) -> io::Result<(u64, u64)> {
+        if offset > self.file_size {
+            return Err(Error::new(ErrorKind::InvalidInput, "Offset out of bounds"));
+        }
+
+        if let Some(line_starts) = self.index() {
+            let lines = line_starts.len() as u64;
+            let line = line_starts.partition_point(offset).saturating_sub(1);
+            let line_start = line_starts.get(line).unwrap_or(0);
+            if offset == self.file_size
+                && lines > 0
+                && self.terminator_start(line_start, offset)? < offset
+            {
+                return Ok((lines, 0));
+            }
+            return Ok((line as u64, offset - line_start));
+        }
+
+        let (mut line, mut line_start) = self.offset_checkpoint(offset);
+        let mut pos = line_start;
+        while pos < offset {
+            let len = (offset - pos).min(SCAN_BLOCK_SIZE as u64) as usize;
+            let block = self.read_bytes(pos, len)?;
+            for i in 0..block.len() {
+                if self.ends_delimiter(&block, pos, i)? {
+                    line += 1;
+                    line_start = pos + i as u64 + 1;
+                }
+            }
+            pos += len as u64;
+        }
+        Ok((line, offset - line_start))
+    }
+
+    /// Maps a zero-based (line, column) position to its byte offset, the inverse of `position_of`.
+    pub fn offset_of(&mut self, line: u64, column: u64) -> io::Result<u64> {
+        let (line_start, line_end) = match self.line_offsets(line)? {
+            Some(offsets) => offsets,
+            // Past the terminator of the last line, the EOF
+            None if column == 0 && line > 0 => match self.line_offsets(line - 1)? {
+                Some((_, end)) if end < self.file_size => return Ok(self.file_size),
+                _ => return Err(Error::new(ErrorKind::InvalidInput, "Line out of bounds")),
+            },
+            None => return Err(Error::new(ErrorKind::InvalidInput, "Line out of bounds")),
+        };
+
+        if column > line_end - line_start {
+            return Err(Error::new(ErrorKind::InvalidInput, "Column out of bounds"));
+        }
+        Ok(line_start + column)
+    }
+
+    // The (start, end) offsets of the `line`-th line, without moving the cursor
+    fn line_offsets(&mut self, line: u64) -> io::Result<Option<LineSpan>> {
+        if self.index().is_some() {
+            return self.index_entry(line);
+        }
+        let line_start = match self.find_line_start(line)? {
+            Some(line_start) => line_start,
+            None => return Ok(None),
+        };
+
+        let cursor = self.save_cursor();
+        self.current_start_line_offset = line_start;
+        let line_end = self.find_end_line();
+        self.restore_cursor(cursor);
+        Ok(Some((line_start, line_end?)))
+    }
+
+    /// Moves to the line containing the byte `offset` and returns it, e.g. to resume from an
+    /// offset reported by another tool (`grep -b`...). A line terminator belongs to the line it
+    /// ends. Returns `None`, without moving the cursor, if the offset is past the last line.
+    pub fn line_at_offset(&mut self, offset: u64) -> io::Result<Option<String>> {
+        if offset >= self.file_size {
+            return Ok(None);
+        }
+
+        self.pending_offset = None;
+        if let Some(line_starts) = self.index() {
+            let line = line_starts.partition_point(offset).saturating_sub(1);
+            if let Some((start, end)) = self.index_entry(line as u64)? {
+                self.set_current_line(start, end);
+            }
+        } else {
+            self.align_to_line(offset)?;
+        }
+        self.current_line()
+    }
+
+    // Builds the index, giving up (and leaving the reader unindexed) past `max_lines` lines
+    #[cfg(feature = "index")]
+    fn index_lines(
+        &mut self,
+        max_lines: Option<usize>,
+        cancel: Option<&Cancel>,
+    ) -> io::Result<bool> {
+        if let Some(delimiter) = self.delimiter.byte() {
+            self.drop_index();
+            return match self.bulk_line_starts(delimiter, max_lines, cancel)? {
+                Some(line_starts) => self.set_index(line_starts).map(|_| true),
+                None => Ok(false),
+            };
+        }
+
+        let cursor = self.save_cursor();
+        self.drop_index();
+        self.bof();
+
+        let mut line_starts = LineIndex::new(self.compressed_index);
+        let scan = (|| -> io::Result<bool> {
+            while self.move_cursor(ReadMode::Next)? {
+                Cancel::check_opt(cancel)?;
+                if Some(line_starts.len()) == max_lines {
+                    return Ok(false);
+                }
+                line_starts.push(self.current_start_line_offset);
+            }
+            Ok(true)
+        })();
+        self.restore_cursor(cursor);
+
+        let complete = scan?;
+        if complete {
+            self.set_index(line_starts)?;
+        }
+        Ok(complete)
+    }
+
+    #[cfg(feature = "index")]
+    fn set_index(&mut self, line_starts: LineIndex) -> io::Result<()> {
+        self.line_starts = Arc::new(line_starts);
+        self.indexed = true;
+        self.sparse_index = None;
+        self.partial_index = None;
+        if let Some(lines) = self.lazy_index.as_mut() {
+            lines.clear();
+        }
+        self.fingerprint_index()
+    }
+
+    #[cfg(feature = "index")]
+    fn drop_index(&mut self) {
+        self.indexed = false;
+        self.line_starts = Arc::new(LineIndex::new(self.compressed_index));
+        self.sparse_index = None;
+        self.index_fingerprint = None;
+    }
+
+    // The index is immutable once built, readers over the same file can share it
+    #[cfg(feature = "index")]
+    fn share_index<S>(&mut self, other: &EasyReader<S>) {
+        self.line_starts = Arc::clone(&other.line_starts);
+        self.indexed = other.indexed;
+        self.sparse_index = other.sparse_index.clone();
+        self.index_fingerprint = other.index_fingerprint;
+    }
+
+    // The (sorted) start offsets of every line, if the reader is indexed
+    #[cfg(feature = "index")]
+    fn index(&self) -> Option<&LineIndex> {
+        Some(&*self.line_starts).filter(|_| self.indexed)
+    }
+
+    #[cfg(not(feature = "index"))]
+    fn index(&self) -> Option<&LineIndex> {
+        None
+    }
+
+    // The (start, end) offsets of the given line, if the reader is indexed and the line exists.
+    // Only the starts are indexed, the end is found before the start of the following line.
+    fn index_entry(&mut self, line: u64) -> io::Result<Option<(u64, u64)>> {
+        let (start, next) = match (self.index(), usize::try_from(line)) {
+            (Some(line_starts), Ok(line)) => match line_starts.get(line) {
+                Some(start) => (start, line_starts.get(line + 1).unwrap_or(self.file_size)),
+                None => return Ok(None),
+            },
+            _ => return Ok(None),
+        };
+        Ok(Some((start, self.line_end_before(start, next)?)))
+    }
+
+    // End of the line starting at `start`, given the start of the next one (or the EOF)
+    #[cfg_attr(not(feature = "index"), allow(dead_code))]
+    fn line_end_before(&mut self, start: u64, next: u64) -> io::Result<u64> {
+        self.terminator_start(start, next)
+    }
+
+    #[cfg(not(feature = "index"))]
+    fn line_checkpoint(&self, _line: u64) -> (u64, u64) {
+        (0, 0)
+    }
+
+    #[cfg(not(feature = "index"))]
+    fn offset_checkpoint(&self, _offset: u64) -> (u64, u64) {
+        (0, 0)
+    }
+
+    #[cfg(not(feature = "index"))]
+    fn sparse_lines(&self) -> Option<u64> {
+        None
+    }
+
+    #[cfg(not(feature = "index"))]
+    fn lazy_next(&self) -> Option<(u64, u64)> {
+        None
+    }
+
+    #[cfg(not(feature = "index"))]
+    fn lazy_prev(&self) -> Option<(u64, u64)> {
+        None
+    }
+
+    #[cfg(not(feature = "index"))]
+    fn lazy_end(&self, _start: u64) -> Option<u64> {
+        None
+    }
+
+    // Decodes the bytes of the line at `start..end`
+    fn decode(&self, mut bytes: Vec<u8>, start: u64, end: u64) -> io::Result<String> {
+        self.transcode(&mut bytes, 0, start, end)?;
+        self.trim_line(&mut bytes, 0);
+        decode_line(bytes, start, end)
+    }
+
+    #[cfg(not(feature = "encoding"))]
+    fn transcode(
+        &self,
+        _buffer: &mut Vec<u8>,
+        _from: usize,
+        _start: u64,
+        _end: u64,
+    ) -> io::Result<()> {
+        Ok(())
+    }
+
+    // Position in the index of the line the cursor is on
+    #[cfg(feature = "index")]
+    fn index_line(&self) -> Option<usize> {
+        if self.at_bof {
+            return None;
+        }
+        self.index()?.position(self.current_start_line_offset)
+    }
+
+    #[cfg(not(feature = "index"))]
+    fn index_line(&self) -> Option<usize> {
+        None
+    }
+
+    // Aligns the cursor left by `set_offset()` (unless it moved since) for `mode` to reach the
+    // line containing the offset, or the one before it moving backwards
+    fn resolve_offset(&mut self, mode: &ReadMode) -> io::Result<()> {
+        let offset = match self.pending_offset.take() {
+            Some(offset) if self.save_cursor() == (offset, offset, false) => offset,
+            _ => return Ok(()),
+        };
+        self.align_to_line(offset)?;
+        match mode {
+            ReadMode::Current => (),
+            // Nothing but the BOF before the first line
+            _ if self.current_start_line_offset == 0 => {
+                self.bof();
+            }
+            ReadMode::Next => {
+                self.move_cursor(ReadMode::Prev)?;
+            }
+            _ => (),
+        }
+        Ok(())
+    }
+
+    // Moves the cursor to the line containing the given offset
+    fn align_to_line(&mut self, offset: u64) -> io::Result<()> {
+        self.current_start_line_offset = offset;
+        self.move_cursor(ReadMode::Containing)?;
+        Ok(())
+    }
+
+    fn save_cursor(&self) -> (u64, u64, bool) {
+        (
+            self.current_start_line_offset,
+            self.current_end_line_offset,
+            self.at_bof,
+        )
+    }
+
+    fn restore_cursor(&mut self, (start, end, at_bof): (u64, u64, bool)) {
+        self.current_start_line_offset = start;
+        self.current_end_line_offset = end;
+        self.at_bof = at_bof;
+    }
+
+    fn set_current_line(&mut self, start: u64, end: u64) {
+        self.restore_cursor((start, end, false));
+    }
+
     fn read_line(&mut self, mode: ReadMode) -> io::Result<Option<String>> {
+        self.read_line_as(mode)
+    }
+
+    // Moves the cursor like move_cursor() (past the ignored lines), recording the steps if a
+    // trace is being recorded
+    fn navigate(&mut self, mode: ReadMode) -> io::Result<bool> {
+        self.prepare_move(&mode)?;
+        if self.ignores_lines() {
+            return self.navigate_unignored(mode);
+        }
+        self.step(mode)
+    }
+
+    // Checks the file and the index as configured, and aligns the cursor left by
+    // `set_offset()`, before moving in `mode`
+    fn prepare_move(&mut self, mode: &ReadMode) -> io::Result<()> {
+        if self.truncation_policy.is_some() {
+            self.check_truncation()?;
+        }
+        #[cfg(feature = "index")]
+        if self.stale_index_policy.is_some() {
+            self.check_index()?;
+        }
+        if self.pending_offset.is_some() {
+            self.resolve_offset(mode)?;
+        }
+        Ok(())
+    }
+
+    fn step(&mut self, mode: ReadMode) -> io::Result<bool> {
+        let from = self.save_cursor();
+        let mut moved = self.move_cursor(mode.clone())?;
+        if !moved && self.auto_refresh && mode == ReadMode::Next && self.refresh()? {
+            moved = self.move_cursor(mode.clone())?;
+        }
+        #[cfg(feature = "index")]
+        if moved {
+            self.remember_line(from, &mode);
+        }
+        if self.trace.is_some() {
+            self.record_step(&mode, from, moved);
+        }
+        Ok(moved)
+    }
+
+    // Moves the cursor without reading the line, returns false if there's no line in that direction
+    fn move_cursor(&mut self, mode: ReadMode) -> io::Result<bool> {
         match mode {
             ReadMode::Prev => {
                 if self.current_start_line_offset == 0 {
-                    return Ok(None);
+                    return Ok(false);
                 }
 
-                if self.indexed && self.current_start_line_offset < self.file_size {
-                    let current_line = *self
-                        .newline_map
-                        .get(&(self.current_start_line_offset as usize))
-                        .unwrap();
-                    self.current_start_line_offset = self.offsets_index[current_line - 1].0 as u64;
-                    self.current_end_line_offset = self.offsets_index[current_line - 1].1 as u64;
-                    return self.read_line(ReadMode::Current);
-                } else {
-                    self.current_end_line_offset = self.current_start_line_offset;
+                if let Some(current_line) = self.index_line() {
+                    return match self.index_entry(current_line as u64 - 1)? {
+                        Some((start, end)) => {
+                            self.set_current_line(start, end);
+                            self.move_cursor(ReadMode::Current)
+                        }
+                        None => Ok(false),
+                    };
+                }
+                if let Some((start, end)) = self.lazy_prev() {
+                    self.set_current_line(start, end);
+                    return self.move_cursor(ReadMode::Current);
                 }
+                self.current_end_line_offset = self.current_start_line_offset;
             }
             ReadMode::Current => {
+                // An empty view (or file) holds no line
+                if self.file_size == 0 {
+                    return Ok(false);
+                }
                 if self.current_start_line_offset == self.current_end_line_offset {
                     if self.current_start_line_offset == self.file_size {
-                        self.current_start_line_offset =
-                            self.find_start_line(ReadMode::Prev)? as u64;
+                        self.current_start_line_offset = self.find_start_line(ReadMode::Prev)?;
                     }
                     if self.current_end_line_offset == 0 {
-                        self.current_end_line_offset = self.find_end_line()? as u64;
+                        self.current_end_line_offset = self.find_end_line()?;
                     }
                 }
             }
             ReadMode::Next => {
-                if self.current_end_line_offset == self.file_size {
-                    return Ok(None);
+                if self.current_end_line_offset == self.file_size || self.only_terminator_left()? {
+                    return Ok(false);
                 }
 
-                if self.indexed && self.current_start_line_offset > 0 {
-                    let current_line = *self
-                        .newline_map
-                        .get(&(self.current_start_line_offset as usize))
-                        .unwrap();
-                    self.current_start_line_offset = self.offsets_index[current_line + 1].0 as u64;
-                    self.current_end_line_offset = self.offsets_index[current_line + 1].1 as u64;
-                    return self.read_line(ReadMode::Current);
-                } else {
-                    self.current_start_line_offset = self.current_end_line_offset;
+                if let Some(current_line) = self.index_line() {
+                    return match self.index_entry(current_line as u64 + 1)? {
+                        Some((start, end)) => {
+                            self.set_current_line(start, end);
+                            self.move_cursor(ReadMode::Current)
+                        }
+                        None => Ok(false),
+                    };
+                }
+                if let Some((start, end)) = self.lazy_next() {
+                    self.set_current_line(start, end);
+                    return self.move_cursor(ReadMode::Current);
                 }
+                self.current_start_line_offset = self.current_end_line_offset;
             }
             #[cfg(feature = "rand")]
             ReadMode::Random => {
-                if self.indexed {
-                    let rnd_idx = rand::thread_rng().gen_range(0..self.offsets_index.len() - 1);
-                    self.current_start_line_offset = self.offsets_index[rnd_idx].0 as u64;
-                    self.current_end_line_offset = self.offsets_index[rnd_idx].1 as u64;
-                    return self.read_line(ReadMode::Current);
+                if self.file_size == 0 {
+                    // Emptied by a rotation
+                    return Ok(false);
+                }
+                if let Some(lines) = self.index().map(|line_starts| line_starts.len() as u64) {
+                    let rnd_idx = self.random_below(lines);
+                    if let Some((start, end)) = self.index_entry(rnd_idx)? {
+                        self.random_draw = rnd_idx;
+                        self.set_current_line(start, end);
+                        return self.move_cursor(ReadMode::Current);
+                    }
+                } else if let Some(lines) = self.sparse_lines() {
+                    let line = self.random_below(lines);
+                    // The start of the line, the draw being an offset like without index
+                    self.random_draw = self.find_line_start(line)?.unwrap_or(0);
+                    self.current_start_line_offset = self.random_draw;
                 } else {
-                    self.current_start_line_offset =
-                        rand::thread_rng().gen_range(0..self.file_size);
+                    self.random_draw = self.random_offset(0..self.file_size)?;
+                    self.current_start_line_offset = self.random_draw;
                 }
             }
+            ReadMode::Containing => (),
         }
 
         if mode != ReadMode::Current {
             self.current_start_line_offset = self.find_start_line(mode)?;
-            self.current_end_line_offset = self.find_end_line()?;
+            self.current_end_line_offset = match self.lazy_end(self.current_start_line_offset) {
+                Some(end) => end,
+                None => self.find_end_line()?,
+            };
+        }
+        self.at_bof = false;
+        Ok(true)
+    }
+
+    // A line terminator at the very end of the file doesn't open a new (empty) line
+    fn only_terminator_left(&mut self) -> io::Result<bool> {
+        let left = self.file_size - self.current_end_line_offset;
+        // Room for a CR before an LF
+        let cr_len = self.delimiter.cr().map_or(0, <[u8]>::len);
+        if self.at_bof || left > (self.delimiter.len() + cr_len) as u64 {
+            return Ok(false);
+        }
+        let end = self.current_end_line_offset;
+        Ok(self.terminator_start(end, self.file_size)? == end)
+    }
+
+    fn read_neighbors(&mut self) -> io::Result<(Option<String>, String, Option<String>)> {
+        // The moves aren't steps of a trace being recorded
+        let trace = self.trace.take();
+        let moves = self.neighbor_offsets();
+        self.trace = trace;
+        let (prev, current, next) = moves?;
+
+        for (start, end) in prev.iter().chain(Some(&current)).chain(next.iter()) {
+            self.check_line_length(*start, end - start)?;
+        }
+        // The three lines are close (contiguous, unless lines are ignored), a single read covers
+        // all of them
+        let from = prev.unwrap_or(current).0;
+        let to = next.unwrap_or(current).1;
+        let buffer = self.read_bytes(from, (to - from) as usize)?;
+        self.stats.lines_decoded += 1 + prev.is_some() as u64 + next.is_some() as u64;
+        let mut decode = |(start, end): (u64, u64)| {
+            let bytes = buffer[(start - from) as usize..(end - from) as usize].to_vec();
+            self.decode(bytes, start, end)
+        };
+
+        Ok((
+            prev.map(&mut decode).transpose()?,
+            decode(current)?,
+            next.map(&mut decode).transpose()?,
+        ))
+    }
+
+    // The (start, end) offsets of the current line and of the lines before and after it, past
+    // the ignored lines
+    fn neighbor_offsets(&mut self) -> io::Result<(Option<LineSpan>, LineSpan, Option<LineSpan>)> {
+        let move_cursor = |reader: &mut Self, mode| match reader.ignores_lines() {
+            true => reader.navigate_unignored(mode),
+            false => reader.move_cursor(mode),
+        };
+        if !move_cursor(self, ReadMode::Current)? {
+            let message = match self.file_size {
+                0 => "There are no lines",
+                _ => "All the lines are ignored",
+            };
+            return Err(Error::new(ErrorKind::NotFound, message));
         }
+        let current = (self.current_start_line_offset, self.current_end_line_offset);
 
+        let prev = if move_cursor(self, ReadMode::Prev)? {
+            Some((self.current_start_line_offset, self.current_end_line_offset))
+        } else {
+            None
+        };
+        self.set_current_line(current.0, current.1);
+        let next = if move_cursor(self, ReadMode::Next)? {
+            Some((self.current_start_line_offset, self.current_end_line_offset))
+        } else {
+            None
+        };
+        Ok((prev, current, next))
+    }
+
+    fn read_current_into(&mut self, buffer: &mut Vec<u8>) -> io::Result<()> {
         let offset = self.current_start_line_offset;
         let line_length = self.current_end_line_offset - self.current_start_line_offset;
-        let buffer = self.read_bytes(offset, line_length as usize)?;
+        self.check_line_length(offset, line_length)?;
+        self.read_into(offset, line_length as usize, buffer)
+    }
 
-        let line = String::from_utf8(buffer)
-            .map_err(|err| {
-                Error::new(
-                    ErrorKind::Other,
-                    format!(
-                        "The line starting at byte: {} and ending at byte: {} is not valid UTF-8. Conversion error: {}",
-                        self.current_start_line_offset,
-                        self.current_end_line_offset,
-                        err
-                    )
-                )
-            })?;
+    fn read_current_bytes(&mut self) -> io::Result<Vec<u8>> {
+        let offset = self.current_start_line_offset;
+        let line_length = self.current_end_line_offset - self.current_start_line_offset;
+        self.check_line_length(offset, line_length)?;
+        self.read_bytes(offset, line_length as usize)
+    }
 
-        Ok(Some(line))
+    fn read_current_line(&mut self) -> io::Result<String> {
+        let buffer = self.read_current_bytes()?;
+        self.stats.lines_decoded += 1;
+        self.decode(
+            buffer,
+            self.current_start_line_offset,
+            self.current_end_line_offset,
+        )
+    }
+
+    // Fails if the line starting at `start` is known to be `length` bytes long (at least) and
+    // that's over `max_line_length()`
+    fn check_line_length(&self, start: u64, length: u64) -> io::Result<()> {
+        match self.max_line_length {
+            Some(max) if length > max as u64 => Err(ReaderError::RecordTooLong {
+                offset: start,
+                max_length: max,
+            }
+            .into()),
+            _ => Ok(()),
+        }
     }
 
     fn find_start_line(&mut self, mode: ReadMode) -> io::Result<u64> {
+        let mut chunk = mem::take(&mut self.chunk_buffer);
+        let start = self.find_start_line_in(mode, &mut chunk);
+        self.chunk_buffer = chunk;
+        start
+    }
+
+    // Like find_start_line(), the chunks being read into `chunk`
+    fn find_start_line_in(&mut self, mode: ReadMode, chunk: &mut Vec<u8>) -> io::Result<u64> {
         let mut new_start_line_offset = self.current_start_line_offset;
+        // Moving backwards, the terminator before the cursor (if any at the EOF) is skipped
+        let mut skip_to = new_start_line_offset;
+        if mode == ReadMode::Prev && new_start_line_offset > 0 {
+            let len = self.delimiter.len() as u64;
+            let end = new_start_line_offset;
+            if len == 1 || self.terminator_start(0, end)? < end {
+                skip_to -= len.min(end);
+            }
+        }
 
-        let mut n_chunks = 0;
         loop {
-            if new_start_line_offset == 0 {
+            // Moving forward from an empty first line still has to skip its terminator
+            if new_start_line_offset == 0 && (mode != ReadMode::Next || self.at_bof) {
                 break;
             }
 
@@ -291,10 +1044,11 @@ impl<R: Read + Seek> EasyReader<R> {
             match mode {
                 ReadMode::Current => (),
                 ReadMode::Next => {
-                    let chunk = self.read_chunk(new_start_line_offset)?;
+                    let chunk_start = new_start_line_offset;
+                    self.read_chunk(chunk_start, chunk)?;
 
-                    for chunk_el in chunk.iter().take(self.chunk_size) {
-                        if *chunk_el == LF_BYTE {
+                    for i in 0..chunk.len().min(self.chunk_size) {
+                        if self.ends_delimiter(chunk, chunk_start, i)? {
                             found = true;
                         }
 
@@ -315,37 +1069,24 @@ impl<R: Read + Seek> EasyReader<R> {
                         }
                     };
 
-                    let mut chunk = self.read_chunk(from)?;
-                    chunk.reverse();
+                    self.read_chunk(from, chunk)?;
 
-                    for (i, chunk_el) in chunk.iter().enumerate().take(self.chunk_size) {
-                        if i < margin {
+                    // Backwards from the byte before `new_start_line_offset`
+                    for i in (0..chunk.len().min(self.chunk_size)).rev() {
+                        if i >= chunk.len() - margin {
                             continue;
                         }
                         if new_start_line_offset == 0 {
                             found = true;
                             break;
                         } else {
-                            if n_chunks == 0
-                                && self.current_start_line_offset == new_start_line_offset
-                            {
-                                #[cfg(feature = "rand")]
-                                {
-                                    if mode != ReadMode::Random {
-                                        // Not moved yet
-                                        new_start_line_offset -= 1;
-                                        continue;
-                                    }
-                                }
-                                #[cfg(not(feature = "rand"))]
-                                {
-                                    // Not moved yet
-                                    new_start_line_offset -= 1;
-                                    continue;
-                                }
+                            if new_start_line_offset > skip_to {
+                                // Not moved yet
+                                new_start_line_offset -= 1;
+                                continue;
                             }
 
-                            if *chunk_el == LF_BYTE {
+                            if self.ends_delimiter(chunk, from, i)? {
                                 found = true;
                             }
                         }
@@ -361,13 +1102,20 @@ impl<R: Read + Seek> EasyReader<R> {
             if found {
                 break;
             }
-            n_chunks += 1;
         }
 
         Ok(new_start_line_offset)
     }
 
     fn find_end_line(&mut self) -> io::Result<u64> {
+        let mut chunk = mem::take(&mut self.chunk_buffer);
+        let end = self.find_end_line_in(&mut chunk);
+        self.chunk_buffer = chunk;
+        end
+    }
+
+    // Like find_end_line(), the chunks being read into `chunk`
+    fn find_end_line_in(&mut self, chunk: &mut Vec<u8>) -> io::Result<u64> {
         let mut new_end_line_offset = self.current_start_line_offset;
 
         loop {
@@ -375,25 +1123,25 @@ impl<R: Read + Seek> EasyReader<R> {
                 break;
             }
 
-            let chunk = self.read_chunk(new_end_line_offset)?;
+            let chunk_start = new_end_line_offset;
+            self.read_chunk(chunk_start, chunk)?;
 
             let mut found = false;
             for i in 0..self.chunk_size {
                 if new_end_line_offset == self.file_size {
                     found = true;
                     break;
-                } else if chunk[i] == LF_BYTE {
-                    // Handle CRLF files
-                    if i > 0 {
-                        if chunk[i - 1] == CR_BYTE {
-                            new_end_line_offset -= 1;
-                        }
-                    } else if new_end_line_offset < self.file_size && new_end_line_offset > 0 {
-                        let next_byte = self.read_bytes(new_end_line_offset - 1, 1)?[0];
-                        if next_byte == CR_BYTE {
-                            new_end_line_offset -= 1;
-                        }
-                    }
+                } else if self.ends_delimiter(chunk, chunk_start, i)? {
+                    // The line ends where its terminator starts
+                    let next = new_end_line_offset + 1;
+                    new_end_line_offset = if self.delimiter.cr().is_none() {
+                        next - self.delimiter.len() as u64
+                    } else if self.delimiter.is_lf() && i > 0 {
+                        // Handle CRLF files
+                        new_end_line_offset - u64::from(chunk[i - 1] == CR_BYTE)
+                    } else {
+                        self.terminator_start(self.current_start_line_offset, next)?
+                    };
                     found = true;
                     break;
                 } else {
@@ -408,18 +1156,135 @@ impl<R: Read + Seek> EasyReader<R> {
         Ok(new_end_line_offset)
     }
 
-    fn read_chunk(&mut self, offset: u64) -> io::Result<Vec<u8>> {
-        let chunk_size = self.chunk_size;
-        self.read_bytes(offset, chunk_size)
+    fn find_line_start(&mut self, line: u64) -> io::Result<Option<u64>> {
+        let (mut current_line, mut pos) = self.line_checkpoint(line);
+        if current_line == line {
+            // An empty file has no first line
+            return Ok(Some(pos).filter(|&s| s < self.file_size));
+        }
+
+        while pos < self.file_size {
+            let len = (self.file_size - pos).min(SCAN_BLOCK_SIZE as u64) as usize;
+            let block = self.read_bytes(pos, len)?;
+            for i in 0..block.len() {
+                if self.ends_delimiter(&block, pos, i)? {
+                    current_line += 1;
+                    if current_line == line {
+                        let line_start = pos + i as u64 + 1;
+                        // A trailing line terminator doesn't open a new line
+                        return Ok(Some(line_start).filter(|&s| s < self.file_size));
+                    }
+                }
+            }
+            pos += len as u64;
+        }
+        Ok(None)
+    }
+
+    // Reads the chunk at `offset` into `chunk`, replacing its content
+    fn read_chunk(&mut self, offset: u64, chunk: &mut Vec<u8>) -> io::Result<()> {
+        chunk.clear();
+        self.read_into(offset, self.chunk_size, chunk)
+    }
+
+    // Cheap hash of the first and last bytes of the file, used to tell whether
+    // persisted data still matches it
+    fn fingerprint(&mut self) -> io::Result<u64> {
+        let head_size = self.file_size.min(FINGERPRINT_SAMPLE_SIZE as u64);
+        let tail_offset = self.file_size - head_size;
+
+        let mut hash = fnv1a(FNV_OFFSET_BASIS, &self.file_size.to_ne_bytes());
+        hash = fnv1a(hash, &self.read_bytes(0, head_size as usize)?);
+        hash = fnv1a(hash, &self.read_bytes(tail_offset, head_size as usize)?);
+        Ok(hash)
     }
 
     fn read_bytes(&mut self, offset: u64, bytes: usize) -> io::Result<Vec<u8>> {
-        let mut buffer = vec![0; bytes];
-        self.file.seek(SeekFrom::Start(offset as u64))?;
-        let _ = self.file.read(&mut buffer)?;
+        let mut buffer = Vec::new();
+        self.read_into(offset, bytes, &mut buffer)?;
         Ok(buffer)
     }
 }
 
+// How many of the `len` bytes from `offset` are in a file of `file_size` bytes
+pub(crate) fn clamp_len(offset: u64, len: usize, file_size: u64) -> usize {
+    file_size.saturating_sub(offset).min(len as u64) as usize
+}
+
+pub(crate) fn read_bytes_from<R: Read + Seek>(
+    file: &mut R,
+    offset: u64,
+    bytes: usize,
+    file_size: u64,
+    stats: &mut ReaderStats,
+) -> io::Result<Vec<u8>> {
+    let mut buffer = Vec::with_capacity(bytes);
+    read_bytes_into(file, offset, bytes, file_size, &mut buffer, stats)?;
+    Ok(buffer)
+}
+
+// Appends `bytes` bytes read from `offset` to `buffer`, zeros past the EOF. A reader may return
+// fewer bytes than asked for (or be interrupted), the reads go on until all the bytes in the
+// file of `file_size` bytes are read: reaching its EOF before means it shrank.
+pub(crate) fn read_bytes_into<R: Read + Seek>(
+    file: &mut R,
+    offset: u64,
+    bytes: usize,
+    file_size: u64,
+    buffer: &mut Vec<u8>,
+    stats: &mut ReaderStats,
+) -> io::Result<()> {
+    let from = buffer.len();
+    buffer.resize(from + bytes, 0);
+    stats.seeks += 1;
+    file.seek(io::SeekFrom::Start(offset))?;
+    let expected = from + clamp_len(offset, bytes, file_size);
+    let mut filled = from;
+    while filled < expected {
+        stats.reads += 1;
+        match file.read(&mut buffer[filled..expected]) {
+            Ok(0) => {
+                return Err(Error::new(
+                    ErrorKind::UnexpectedEof,
+                    "The file is shorter than expected",
+                ))
+            }
+            Ok(read) => {
+                filled += read;
+                stats.bytes_read += read as u64;
+            }
+            Err(err) if err.kind() == ErrorKind::Interrupted => continue,
+            Err(err) => return Err(err),
+        }
+    }
+    Ok(())
+}
+
+// 64-bit FNV-1a
+fn fnv1a(mut hash: u64, bytes: &[u8]) -> u64 {
+    for byte in bytes {
+        hash ^= u64::from(*byte);
+        hash = hash.wrapping_mul(FNV_PRIME);
+    }
+    hash
+}
+
+fn decode_line(buffer: Vec<u8>, start: u64, end: u64) -> io::Result<String> {
+    String::from_utf8(buffer).map_err(|err| {
+        let error = err.utf8_error();
+        utf8_error(start, end, error, err.into_bytes())
+    })
+}
+
+fn utf8_error(start: u64, end: u64, error: Utf8Error, bytes: Vec<u8>) -> Error {
+    ReaderError::InvalidUtf8 {
+        offset: start,
+        len: end - start,
+        error,
+        bytes,
+    }
+    .into()
+}
+
 #[cfg(test)]
 mod tests;
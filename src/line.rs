@@ -0,0 +1,59 @@
+use crate::{decode_line, EasyReader, LineEnding, ReadMode};
+use std::io::{self, prelude::*};
+
+/// A line along with where it is in the file, as returned by `EasyReader::next_line_info()` and
+/// its siblings.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Line {
+    pub text: String,
+    /// The number (zero-based) of the line, known when the reader is indexed.
+    pub number: Option<u64>,
+    /// The byte offsets of the line, its terminator excluded.
+    pub start: u64,
+    pub end: u64,
+    pub ending: LineEnding,
+}
+
+impl<R: Read + Seek> EasyReader<R> {
+    /// Like `prev_line()`, but returns the line with its number, offsets and line ending.
+    pub fn prev_line_info(&mut self) -> io::Result<Option<Line>> {
+        self.read_line_info(ReadMode::Prev)
+    }
+
+    /// Like `current_line()`, but returns the line with its number, offsets and line ending.
+    pub fn current_line_info(&mut self) -> io::Result<Option<Line>> {
+        self.read_line_info(ReadMode::Current)
+    }
+
+    /// Like `next_line()`, but returns the line with its number, offsets and line ending.
+    pub fn next_line_info(&mut self) -> io::Result<Option<Line>> {
+        self.read_line_info(ReadMode::Next)
+    }
+
+    /// Like `random_line()`, but returns the line with its number, offsets and line ending.
+    #[cfg(feature = "rand")]
+    pub fn random_line_info(&mut self) -> io::Result<Option<Line>> {
+        self.read_line_info(ReadMode::Random)
+    }
+
+    fn read_line_info(&mut self, mode: ReadMode) -> io::Result<Option<Line>> {
+        let mut bytes = Vec::new();
+        let (start, end) = match self.read_line_bytes(mode, &mut bytes)? {
+            Some(offsets) => offsets,
+            None => return Ok(None),
+        };
+        // A logical line (see `continuation()`) ends like its last line
+        let ending = self.ending_after(end)?;
+        let number = self
+            .index()
+            .and_then(|line_starts| line_starts.position(start))
+            .map(|line| line as u64);
+        Ok(Some(Line {
+            text: decode_line(bytes, start, end)?,
+            number,
+            start,
+            end,
+            ending,
+        }))
+    }
+}
@@ -0,0 +1,228 @@
+use crate::{decode_line, utf8_error, EasyReader, ReadMode};
+#[cfg(feature = "bytes")]
+use bytes::Bytes;
+use std::{
+    io::{self, prelude::*},
+    rc::Rc,
+    sync::Arc,
+};
+
+/// A type the lines can be returned as, see `EasyReader::next_line_as()` and its siblings.
+///
+/// Besides the built-in implementations, it can be implemented to build any line
+/// representation straight from the bytes read from the file.
+pub trait LineBuf: Sized {
+    /// Builds a line from its raw bytes (without the line terminator unless it's kept, see
+    /// `EasyReader::keep_terminators()`), read at `start..end`. With an encoding other than
+    /// UTF-8 (see `EasyReader::encoding()`), the bytes are the line transcoded to UTF-8.
+    fn from_line_bytes(bytes: Vec<u8>, start: u64, end: u64) -> io::Result<Self>;
+}
+
+impl LineBuf for String {
+    fn from_line_bytes(bytes: Vec<u8>, start: u64, end: u64) -> io::Result<Self> {
+        decode_line(bytes, start, end)
+    }
+}
+
+/// The bytes as they are in the file, without any UTF-8 validation.
+impl LineBuf for Vec<u8> {
+    fn from_line_bytes(bytes: Vec<u8>, _start: u64, _end: u64) -> io::Result<Self> {
+        Ok(bytes)
+    }
+}
+
+/// The bytes as they are in the file, without any UTF-8 validation (nor copy).
+#[cfg(feature = "bytes")]
+impl LineBuf for Bytes {
+    fn from_line_bytes(bytes: Vec<u8>, _start: u64, _end: u64) -> io::Result<Self> {
+        Ok(Bytes::from(bytes))
+    }
+}
+
+impl LineBuf for Box<str> {
+    fn from_line_bytes(bytes: Vec<u8>, start: u64, end: u64) -> io::Result<Self> {
+        decode_line(bytes, start, end).map(String::into_boxed_str)
+    }
+}
+
+impl LineBuf for Rc<str> {
+    fn from_line_bytes(bytes: Vec<u8>, start: u64, end: u64) -> io::Result<Self> {
+        decode_line(bytes, start, end).map(Rc::from)
+    }
+}
+
+impl LineBuf for Arc<str> {
+    fn from_line_bytes(bytes: Vec<u8>, start: u64, end: u64) -> io::Result<Self> {
+        decode_line(bytes, start, end).map(Arc::from)
+    }
+}
+
+/// A buffer the lines can be appended to, see `EasyReader::next_line_into()` and its
+/// siblings: the same buffer can take line after line without any allocation.
+pub trait LineSink {
+    /// Appends a line from its bytes, read at `start..end` (see `LineBuf::from_line_bytes()`).
+    fn append_line(&mut self, bytes: &[u8], start: u64, end: u64) -> io::Result<()>;
+}
+
+impl LineSink for String {
+    fn append_line(&mut self, bytes: &[u8], start: u64, end: u64) -> io::Result<()> {
+        let line = std::str::from_utf8(bytes)
+            .map_err(|err| utf8_error(start, end, err, bytes.to_vec()))?;
+        self.push_str(line);
+        Ok(())
+    }
+}
+
+impl LineSink for Vec<u8> {
+    fn append_line(&mut self, bytes: &[u8], _start: u64, _end: u64) -> io::Result<()> {
+        self.extend_from_slice(bytes);
+        Ok(())
+    }
+}
+
+impl<R: Read + Seek> EasyReader<R> {
+    pub fn prev_line_as<T: LineBuf>(&mut self) -> io::Result<Option<T>> {
+        self.read_line_as(ReadMode::Prev)
+    }
+
+    pub fn current_line_as<T: LineBuf>(&mut self) -> io::Result<Option<T>> {
+        self.read_line_as(ReadMode::Current)
+    }
+
+    pub fn next_line_as<T: LineBuf>(&mut self) -> io::Result<Option<T>> {
+        self.read_line_as(ReadMode::Next)
+    }
+
+    #[cfg(feature = "rand")]
+    pub fn random_line_as<T: LineBuf>(&mut self) -> io::Result<Option<T>> {
+        self.read_line_as(ReadMode::Random)
+    }
+
+    /// Like `prev_line()`, but appends the line to `buffer` and returns its length in bytes.
+    pub fn prev_line_into<B: LineSink>(&mut self, buffer: &mut B) -> io::Result<Option<usize>> {
+        self.read_line_into(ReadMode::Prev, buffer)
+    }
+
+    /// Like `current_line()`, but appends the line to `buffer` and returns its length in bytes.
+    pub fn current_line_into<B: LineSink>(&mut self, buffer: &mut B) -> io::Result<Option<usize>> {
+        self.read_line_into(ReadMode::Current, buffer)
+    }
+
+    /// Like `next_line()`, but appends the line to `buffer` and returns its length in bytes:
+    ///
+    /// ```no_run
+    /// use easy_reader::EasyReader;
+    /// use std::{fs::File, io};
+    ///
+    /// fn longest_line_length() -> io::Result<usize> {
+    ///     let mut reader = EasyReader::new(File::open("resources/test-file-lf")?)?;
+    ///     let (mut line, mut longest) = (String::new(), 0);
+    ///     while let Some(length) = reader.next_line_into(&mut line)? {
+    ///         longest = longest.max(length);
+    ///         line.clear();
+    ///     }
+    ///     Ok(longest)
+    /// }
+    /// ```
+    pub fn next_line_into<B: LineSink>(&mut self, buffer: &mut B) -> io::Result<Option<usize>> {
+        self.read_line_into(ReadMode::Next, buffer)
+    }
+
+    /// Like `random_line()`, but appends the line to `buffer` and returns its length in bytes.
+    #[cfg(feature = "rand")]
+    pub fn random_line_into<B: LineSink>(&mut self, buffer: &mut B) -> io::Result<Option<usize>> {
+        self.read_line_into(ReadMode::Random, buffer)
+    }
+
+    /// Like `prev_line()`, but decodes the line into a buffer of the reader, reused from a
+    /// line to the next, and lends it: no allocation once the buffer is large enough.
+    pub fn prev_line_str(&mut self) -> io::Result<Option<&str>> {
+        self.read_line_str(ReadMode::Prev)
+    }
+
+    /// Like `current_line()`, lending the line like `prev_line_str()`.
+    pub fn current_line_str(&mut self) -> io::Result<Option<&str>> {
+        self.read_line_str(ReadMode::Current)
+    }
+
+    /// Like `next_line()`, lending the line like `prev_line_str()`.
+    pub fn next_line_str(&mut self) -> io::Result<Option<&str>> {
+        self.read_line_str(ReadMode::Next)
+    }
+
+    pub(crate) fn read_line_as<T: LineBuf>(&mut self, mode: ReadMode) -> io::Result<Option<T>> {
+        let mut bytes = Vec::new();
+        match self.read_line_bytes(mode, &mut bytes)? {
+            Some((start, end)) => T::from_line_bytes(bytes, start, end).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    fn read_line_into<B: LineSink>(
+        &mut self,
+        mode: ReadMode,
+        buffer: &mut B,
+    ) -> io::Result<Option<usize>> {
+        // The bytes go through a buffer of the reader, which keeps its capacity from a line to
+        // the next
+        let mut bytes = std::mem::take(&mut self.line_buffer);
+        bytes.clear();
+        let line = self
+            .read_line_bytes(mode, &mut bytes)
+            .and_then(|line| match line {
+                Some((start, end)) => buffer
+                    .append_line(&bytes, start, end)
+                    .map(|_| Some(bytes.len())),
+                None => Ok(None),
+            });
+        self.line_buffer = bytes;
+        line
+    }
+
+    fn read_line_str(&mut self, mode: ReadMode) -> io::Result<Option<&str>> {
+        let mut bytes = std::mem::take(&mut self.line_buffer);
+        bytes.clear();
+        let line = self.read_line_bytes(mode, &mut bytes);
+        self.line_buffer = bytes;
+        match line? {
+            Some((start, end)) => std::str::from_utf8(&self.line_buffer)
+                .map(Some)
+                .map_err(|err| utf8_error(start, end, err, self.line_buffer.clone())),
+            None => Ok(None),
+        }
+    }
+
+    // Appends the bytes of the line read in `mode` to `buffer` (its terminator included if
+    // kept, transcoded to UTF-8 with another encoding) and returns its (start, end) offsets
+    pub(crate) fn read_line_bytes(
+        &mut self,
+        mode: ReadMode,
+        buffer: &mut Vec<u8>,
+    ) -> io::Result<Option<(u64, u64)>> {
+        let from = buffer.len();
+        let (start, end) = if let Some(marker) = self.continuation.clone() {
+            match self.read_logical_bytes(mode, &marker)? {
+                Some((mut bytes, start, end)) => {
+                    buffer.append(&mut bytes);
+                    (start, end)
+                }
+                None => return Ok(None),
+            }
+        } else {
+            if !self.navigate(mode)? {
+                return Ok(None);
+            }
+            self.read_current_into(buffer)?;
+            (self.current_start_line_offset, self.current_end_line_offset)
+        };
+        if self.keep_terminators {
+            buffer.append(&mut self.terminator_after(end)?);
+        }
+        self.transcode(buffer, from, start, end)?;
+        if !self.keep_terminators {
+            self.trim_line(buffer, from);
+        }
+        self.stats.lines_decoded += 1;
+        Ok(Some((start, end)))
+    }
+}
@@ -0,0 +1,231 @@
+#![cfg_attr(not(feature = "index"), allow(dead_code))]
+
+#[cfg(feature = "index")]
+use crate::EasyReader;
+#[cfg(feature = "index")]
+use std::{io::prelude::*, sync::Arc};
+
+// Lines per block of a compressed index, the first start of each block is stored as is so that
+// an access never decodes more than a block
+const BLOCK_LEN: usize = 64;
+
+// The sorted start offsets of the lines of a file, either as they are or delta encoded as
+// varints (one or two bytes per line for most text files, instead of eight)
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) enum LineIndex {
+    Plain(Vec<u64>),
+    Compressed {
+        // The first start of every block and the position of the following deltas
+        blocks: Vec<(u64, usize)>,
+        deltas: Vec<u8>,
+        len: usize,
+        last: u64,
+    },
+}
+
+impl Default for LineIndex {
+    fn default() -> Self {
+        LineIndex::Plain(Vec::new())
+    }
+}
+
+impl LineIndex {
+    pub(crate) fn new(compressed: bool) -> Self {
+        if compressed {
+            LineIndex::Compressed {
+                blocks: Vec::new(),
+                deltas: Vec::new(),
+                len: 0,
+                last: 0,
+            }
+        } else {
+            LineIndex::Plain(Vec::new())
+        }
+    }
+
+    pub(crate) fn is_compressed(&self) -> bool {
+        matches!(self, LineIndex::Compressed { .. })
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        match self {
+            LineIndex::Plain(starts) => starts.len(),
+            LineIndex::Compressed { len, .. } => *len,
+        }
+    }
+
+    pub(crate) fn get(&self, line: usize) -> Option<u64> {
+        match self {
+            LineIndex::Plain(starts) => starts.get(line).copied(),
+            LineIndex::Compressed { len, .. } if line >= *len => None,
+            LineIndex::Compressed { .. } => self.block(line / BLOCK_LEN).nth(line % BLOCK_LEN),
+        }
+    }
+
+    pub(crate) fn last(&self) -> Option<u64> {
+        match self {
+            LineIndex::Plain(starts) => starts.last().copied(),
+            LineIndex::Compressed { len: 0, .. } => None,
+            LineIndex::Compressed { last, .. } => Some(*last),
+        }
+    }
+
+    pub(crate) fn push(&mut self, start: u64) {
+        match self {
+            LineIndex::Plain(starts) => starts.push(start),
+            LineIndex::Compressed {
+                blocks,
+                deltas,
+                len,
+                last,
+            } => {
+                if *len % BLOCK_LEN == 0 {
+                    blocks.push((start, deltas.len()));
+                } else {
+                    write_varint(deltas, start - *last);
+                }
+                *len += 1;
+                *last = start;
+            }
+        }
+    }
+
+    pub(crate) fn pop(&mut self) -> Option<u64> {
+        if let LineIndex::Plain(starts) = self {
+            return starts.pop();
+        }
+
+        let line = self.len().checked_sub(1)?;
+        let popped = self.last();
+        // The delta of the popped line follows the previous line of its block, if any
+        let previous = match line % BLOCK_LEN {
+            0 => None,
+            in_block => self.block_entries(line / BLOCK_LEN).nth(in_block - 1),
+        };
+        let previous_start = match previous {
+            Some((start, _)) => Some(start),
+            None => line.checked_sub(1).and_then(|line| self.get(line)),
+        };
+        if let LineIndex::Compressed {
+            blocks,
+            deltas,
+            len,
+            last,
+        } = self
+        {
+            match previous {
+                Some((_, position)) => deltas.truncate(position),
+                None => {
+                    blocks.pop();
+                }
+            }
+            *len = line;
+            *last = previous_start.unwrap_or(0);
+        }
+        popped
+    }
+
+    // Number of lines starting at or before `offset`
+    pub(crate) fn partition_point(&self, offset: u64) -> usize {
+        match self {
+            LineIndex::Plain(starts) => starts.partition_point(|&start| start <= offset),
+            LineIndex::Compressed { blocks, .. } => {
+                match blocks.partition_point(|&(start, _)| start <= offset) {
+                    0 => 0,
+                    block => {
+                        let block = block - 1;
+                        let in_block = self.block(block).take_while(|&start| start <= offset);
+                        block * BLOCK_LEN + in_block.count()
+                    }
+                }
+            }
+        }
+    }
+
+    // The line starting exactly at `offset`, if any
+    pub(crate) fn position(&self, offset: u64) -> Option<usize> {
+        let line = self.partition_point(offset).checked_sub(1)?;
+        Some(line).filter(|&line| self.get(line) == Some(offset))
+    }
+
+    pub(crate) fn iter(&self) -> Box<dyn Iterator<Item = u64> + '_> {
+        match self {
+            LineIndex::Plain(starts) => Box::new(starts.iter().copied()),
+            LineIndex::Compressed { blocks, .. } => {
+                Box::new((0..blocks.len()).flat_map(move |block| self.block(block)))
+            }
+        }
+    }
+
+    // The same lines, in the given representation
+    pub(crate) fn with_compression(&self, compressed: bool) -> Self {
+        let mut line_index = LineIndex::new(compressed);
+        for start in self.iter() {
+            line_index.push(start);
+        }
+        line_index
+    }
+
+    fn block(&self, block: usize) -> impl Iterator<Item = u64> + '_ {
+        self.block_entries(block).map(|(start, _)| start)
+    }
+
+    // The starts of a block of a compressed index, each one with the position following it
+    fn block_entries(&self, block: usize) -> impl Iterator<Item = (u64, usize)> + '_ {
+        let (blocks, deltas, len) = match self {
+            LineIndex::Compressed {
+                blocks,
+                deltas,
+                len,
+                ..
+            } => (&blocks[..], &deltas[..], *len),
+            LineIndex::Plain(_) => (&[][..], &[][..], 0),
+        };
+        let first = blocks.get(block).copied();
+        let count = len.saturating_sub(block * BLOCK_LEN).min(BLOCK_LEN);
+        let mut position = first.map_or(0, |(_, position)| position);
+        let mut start = first.map_or(0, |(start, _)| start);
+        (0..count).map(move |i| {
+            if i > 0 {
+                start += read_varint(deltas, &mut position);
+            }
+            (start, position)
+        })
+    }
+}
+
+fn write_varint(bytes: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        bytes.push(value as u8 | 0x80);
+        value >>= 7;
+    }
+    bytes.push(value as u8);
+}
+
+fn read_varint(bytes: &[u8], position: &mut usize) -> u64 {
+    let mut value = 0;
+    let mut shift = 0;
+    while let Some(&byte) = bytes.get(*position) {
+        *position += 1;
+        value |= u64::from(byte & 0x7F) << shift;
+        if byte & 0x80 == 0 {
+            break;
+        }
+        shift += 7;
+    }
+    value
+}
+
+#[cfg(feature = "index")]
+impl<R: Read + Seek> EasyReader<R> {
+    /// Keeps the index delta encoded in memory, for a fraction of the memory of a plain one
+    /// (one or two bytes per line instead of eight for most text files) at the cost of some
+    /// decoding on every access. Applies to the current index, if any, and the following ones.
+    pub fn compress_index(&mut self, enabled: bool) -> &mut Self {
+        self.compressed_index = enabled;
+        if self.line_starts.is_compressed() != enabled {
+            self.line_starts = Arc::new(self.line_starts.with_compression(enabled));
+        }
+        self
+    }
+}
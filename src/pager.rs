@@ -0,0 +1,101 @@
+use crate::LineCursor;
+use std::io::{self, Error, ErrorKind};
+
+/// A page of lines, as returned by a [`Pager`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Page {
+    /// The number (zero-based) of the first line of the page.
+    pub first_line: u64,
+    pub lines: Vec<String>,
+}
+
+/// Moves through the lines of a [`LineCursor`] (e.g. an `EasyReader`) by whole pages,
+/// keeping track of the line numbers, to build pagers (`less`-like). Each page is read with
+/// `next_lines()` or `prev_lines()`, in large blocks with an `EasyReader`.
+///
+/// The pager starts before the first page: the first `page_down()` returns it.
+pub struct Pager<C: LineCursor> {
+    reader: C,
+    rows: usize,
+    // The first and last lines of the current page, the number of the first one and how many
+    // lines it has
+    page: Option<(C::Position, C::Position, u64, usize)>,
+}
+
+impl<C: LineCursor> Pager<C> {
+    /// Fails with `ErrorKind::InvalidInput` if `rows` is 0.
+    pub fn new(mut reader: C, rows: usize) -> io::Result<Self> {
+        if rows == 0 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "A page needs at least one row",
+            ));
+        }
+        reader.bof();
+        Ok(Pager {
+            reader,
+            rows,
+            page: None,
+        })
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    /// The page following the current one, `None` (staying on the current page) if there are
+    /// no lines left. The last page can be shorter than the others.
+    pub fn page_down(&mut self) -> io::Result<Option<Page>> {
+        let first_line = match &self.page {
+            Some((_, last, first_line, len)) => {
+                self.reader.goto(last)?;
+                first_line + *len as u64
+            }
+            None => {
+                self.reader.bof();
+                0
+            }
+        };
+
+        let first_line_of_page = match self.reader.next_line()? {
+            Some(line) => line,
+            None => return Ok(None),
+        };
+        let first = self.reader.position();
+        let mut lines = vec![first_line_of_page];
+        lines.append(&mut self.reader.next_lines(self.rows - 1)?);
+        self.page = Some((first, self.reader.position(), first_line, lines.len()));
+        Ok(Some(Page { first_line, lines }))
+    }
+
+    /// The page preceding the current one, `None` (staying on the current page) on the first
+    /// page.
+    pub fn page_up(&mut self) -> io::Result<Option<Page>> {
+        let (first, first_line) = match &self.page {
+            Some((first, _, first_line, _)) if *first_line > 0 => (first.clone(), *first_line),
+            _ => return Ok(None),
+        };
+
+        self.reader.goto(&first)?;
+        let last_line_of_page = match self.reader.prev_line()? {
+            Some(line) => line,
+            None => return Ok(None),
+        };
+        let last = self.reader.position();
+        let mut lines = self.reader.prev_lines(self.rows - 1)?;
+        lines.reverse();
+        lines.push(last_line_of_page);
+
+        let first_line = first_line - lines.len() as u64;
+        self.page = Some((self.reader.position(), last, first_line, lines.len()));
+        Ok(Some(Page { first_line, lines }))
+    }
+
+    pub fn get_ref(&self) -> &C {
+        &self.reader
+    }
+
+    pub fn into_inner(self) -> C {
+        self.reader
+    }
+}
@@ -0,0 +1,32 @@
+use crate::{shared::ReadAt, EasyReader};
+use rayon::prelude::*;
+use std::io::{self, prelude::*, Error, ErrorKind};
+
+impl<R: Read + Seek + ReadAt> EasyReader<R>
+where
+    Self: Sync,
+{
+    /// Reads every line on the rayon thread pool, for map/filter/aggregate workloads over huge
+    /// files. The index is split across the threads, each reading its lines with positional
+    /// reads (see `get_line()`): the handle is shared and the cursor doesn't move.
+    ///
+    /// The iterator is indexed, so `collect()` keeps the lines in order. Fails with
+    /// `ErrorKind::InvalidInput` if the reader isn't indexed.
+    pub fn par_lines(
+        &self,
+    ) -> io::Result<impl IndexedParallelIterator<Item = io::Result<String>> + '_> {
+        let lines = match self.index() {
+            Some(line_starts) => line_starts.len(),
+            None => {
+                return Err(Error::new(
+                    ErrorKind::InvalidInput,
+                    "Parallel reads need an index",
+                ))
+            }
+        };
+        Ok((0..lines).into_par_iter().map(move |line| {
+            // Every indexed line exists
+            self.get_line(line as u64).map(Option::unwrap_or_default)
+        }))
+    }
+}
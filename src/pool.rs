@@ -0,0 +1,259 @@
+use crate::{delimiter::Delimiter, EasyReader, LineCountEstimate, Trim, TruncationPolicy};
+#[cfg(feature = "index")]
+use crate::{sparse::SparseIndex, LineIndex, StaleIndexPolicy};
+#[cfg(feature = "index")]
+use std::sync::Arc;
+use std::{
+    collections::BTreeMap,
+    fs::File,
+    io::{self, prelude::*, Error, ErrorKind},
+    ops::{Deref, DerefMut},
+    path::Path,
+    sync::{Condvar, Mutex, MutexGuard},
+};
+
+/// A set of readers over the same file, to serve concurrent requests without a `&mut` bottleneck.
+///
+/// Readers are borrowed with `checkout()` and automatically returned to the pool when the guard
+/// is dropped, rewound to the BOF and with the settings (`delimiter()`, `view_lines()`,
+/// `comment_prefix()`, the index...) they had when they were borrowed.
+pub struct ReaderPool<R> {
+    readers: Mutex<Vec<EasyReader<R>>>,
+    returned: Condvar,
+}
+
+impl ReaderPool<File> {
+    /// Opens `size` handles on the file at `path`, all sharing a single index (with the
+    /// `index` feature).
+    pub fn open<P: AsRef<Path>>(path: P, size: usize) -> io::Result<Self> {
+        if size == 0 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "A pool needs at least one reader",
+            ));
+        }
+
+        #[allow(unused_mut)]
+        let mut first = EasyReader::new(File::open(&path)?)?;
+        #[cfg(feature = "index")]
+        first.build_index()?;
+
+        let mut readers = Vec::with_capacity(size);
+        for _ in 1..size {
+            #[allow(unused_mut)]
+            let mut reader = EasyReader::new(File::open(&path)?)?;
+            #[cfg(feature = "index")]
+            reader.share_index(&first);
+            readers.push(reader);
+        }
+        readers.push(first);
+
+        Ok(ReaderPool::new(readers))
+    }
+}
+
+impl<R: Read + Seek> ReaderPool<R> {
+    pub fn new(readers: Vec<EasyReader<R>>) -> Self {
+        ReaderPool {
+            readers: Mutex::new(readers),
+            returned: Condvar::new(),
+        }
+    }
+
+    /// Number of readers currently available.
+    pub fn available(&self) -> usize {
+        self.lock().len()
+    }
+
+    /// Borrows a reader, waiting for one to be returned if they're all in use.
+    pub fn checkout(&self) -> PooledReader<'_, R> {
+        let mut readers = self.lock();
+        loop {
+            if let Some(reader) = readers.pop() {
+                return PooledReader::new(self, reader);
+            }
+            readers = self
+                .returned
+                .wait(readers)
+                .unwrap_or_else(|poisoned| poisoned.into_inner());
+        }
+    }
+
+    /// Borrows a reader if one is available right away.
+    pub fn try_checkout(&self) -> Option<PooledReader<'_, R>> {
+        let reader = self.lock().pop();
+        reader.map(|reader| PooledReader::new(self, reader))
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Vec<EasyReader<R>>> {
+        // A panic while holding the lock can't leave the Vec in an inconsistent state
+        self.readers
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+/// A reader borrowed from a [`ReaderPool`], returned to it on drop.
+pub struct PooledReader<'a, R: Read + Seek> {
+    pool: &'a ReaderPool<R>,
+    reader: Option<EasyReader<R>>,
+    settings: Settings,
+}
+
+impl<'a, R: Read + Seek> PooledReader<'a, R> {
+    fn new(pool: &'a ReaderPool<R>, reader: EasyReader<R>) -> Self {
+        PooledReader {
+            pool,
+            settings: Settings::of(&reader),
+            reader: Some(reader),
+        }
+    }
+}
+
+impl<R: Read + Seek> Deref for PooledReader<'_, R> {
+    type Target = EasyReader<R>;
+
+    fn deref(&self) -> &Self::Target {
+        self.reader.as_ref().unwrap()
+    }
+}
+
+impl<R: Read + Seek> DerefMut for PooledReader<'_, R> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.reader.as_mut().unwrap()
+    }
+}
+
+impl<R: Read + Seek> Drop for PooledReader<'_, R> {
+    fn drop(&mut self) {
+        if let Some(mut reader) = self.reader.take() {
+            self.settings.restore(&mut reader);
+            reader.bof();
+            self.pool.lock().push(reader);
+            self.pool.returned.notify_one();
+        }
+    }
+}
+
+// The settings of a reader when it's checked out, restored when it's returned so that the
+// changes of a borrower don't leak into the next checkout
+struct Settings {
+    header_size: u64,
+    view_end: Option<u64>,
+    file_size: u64,
+    chunk_size: usize,
+    delimiter: Delimiter,
+    #[cfg(feature = "index")]
+    indexed: bool,
+    #[cfg(feature = "index")]
+    line_starts: Arc<LineIndex>,
+    #[cfg(feature = "index")]
+    compressed_index: bool,
+    #[cfg(feature = "index")]
+    sparse_index: Option<Arc<SparseIndex>>,
+    #[cfg(feature = "index")]
+    lazy_index: bool,
+    #[cfg(feature = "index")]
+    index_fingerprint: Option<u64>,
+    #[cfg(feature = "index")]
+    stale_index_policy: Option<StaleIndexPolicy>,
+    section_anchors: Vec<(u64, u64)>,
+    bookmarks: BTreeMap<String, (u64, u64)>,
+    line_count_estimate: Option<LineCountEstimate>,
+    auto_refresh: bool,
+    truncation_policy: Option<TruncationPolicy>,
+    continuation: Option<Vec<u8>>,
+    comment_prefix: Option<Vec<u8>>,
+    skip_blank_lines: bool,
+    keep_terminators: bool,
+    trim: Trim,
+    max_line_length: Option<usize>,
+    #[cfg(feature = "encoding")]
+    encoding: Option<&'static encoding_rs::Encoding>,
+    #[cfg(feature = "rand")]
+    unbiased_random: bool,
+}
+
+impl Settings {
+    fn of<R>(reader: &EasyReader<R>) -> Self {
+        Settings {
+            header_size: reader.header_size,
+            view_end: reader.view_end,
+            file_size: reader.file_size,
+            chunk_size: reader.chunk_size,
+            delimiter: reader.delimiter.clone(),
+            #[cfg(feature = "index")]
+            indexed: reader.indexed,
+            #[cfg(feature = "index")]
+            line_starts: Arc::clone(&reader.line_starts),
+            #[cfg(feature = "index")]
+            compressed_index: reader.compressed_index,
+            #[cfg(feature = "index")]
+            sparse_index: reader.sparse_index.clone(),
+            #[cfg(feature = "index")]
+            lazy_index: reader.lazy_index.is_some(),
+            #[cfg(feature = "index")]
+            index_fingerprint: reader.index_fingerprint,
+            #[cfg(feature = "index")]
+            stale_index_policy: reader.stale_index_policy,
+            section_anchors: reader.section_anchors.clone(),
+            bookmarks: reader.bookmarks.clone(),
+            line_count_estimate: reader.line_count_estimate,
+            auto_refresh: reader.auto_refresh,
+            truncation_policy: reader.truncation_policy,
+            continuation: reader.continuation.clone(),
+            comment_prefix: reader.comment_prefix.clone(),
+            skip_blank_lines: reader.skip_blank_lines,
+            keep_terminators: reader.keep_terminators,
+            trim: reader.trim,
+            max_line_length: reader.max_line_length,
+            #[cfg(feature = "encoding")]
+            encoding: reader.encoding,
+            #[cfg(feature = "rand")]
+            unbiased_random: reader.unbiased_random,
+        }
+    }
+
+    fn restore<R: Read + Seek>(&self, reader: &mut EasyReader<R>) {
+        let view = (self.header_size, self.view_end, self.file_size);
+        if (reader.header_size, reader.view_end, reader.file_size) != view {
+            (reader.header_size, reader.view_end, reader.file_size) = view;
+            reader.clear_chunk_cache();
+            reader.pending_offset = None;
+            // The lines cached were those of another view
+            reader.set_delimiter(self.delimiter.clone());
+        } else if reader.delimiter != self.delimiter {
+            reader.set_delimiter(self.delimiter.clone());
+        }
+        reader.chunk_size = self.chunk_size;
+        #[cfg(feature = "index")]
+        {
+            reader.line_starts = Arc::clone(&self.line_starts);
+            reader.indexed = self.indexed;
+            reader.compressed_index = self.compressed_index;
+            reader.sparse_index = self.sparse_index.clone();
+            reader.index_fingerprint = self.index_fingerprint;
+            reader.stale_index_policy = self.stale_index_policy;
+            reader.lazy_index(self.lazy_index);
+        }
+        reader.section_anchors = self.section_anchors.clone();
+        reader.bookmarks = self.bookmarks.clone();
+        reader.line_count_estimate = self.line_count_estimate;
+        reader.auto_refresh = self.auto_refresh;
+        reader.truncation_policy = self.truncation_policy;
+        reader.continuation = self.continuation.clone();
+        reader.comment_prefix = self.comment_prefix.clone();
+        reader.skip_blank_lines = self.skip_blank_lines;
+        reader.keep_terminators = self.keep_terminators;
+        reader.trim = self.trim;
+        reader.max_line_length = self.max_line_length;
+        #[cfg(feature = "encoding")]
+        {
+            reader.encoding = self.encoding;
+        }
+        #[cfg(feature = "rand")]
+        {
+            reader.unbiased_random = self.unbiased_random;
+        }
+    }
+}
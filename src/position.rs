@@ -0,0 +1,52 @@
+use crate::EasyReader;
+use std::io::{self, prelude::*, Error, ErrorKind};
+
+/// Where the cursor of a reader is, see `EasyReader::position()`. It's also the
+/// `LineCursor::Position` of `EasyReader`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Position {
+    start: u64,
+    end: u64,
+    at_bof: bool,
+    // Left by `set_offset()`, the cursor isn't aligned to a line yet
+    unaligned: bool,
+}
+
+impl Position {
+    /// The byte offset of the start of the line (0 at the BOF, the file size at the EOF).
+    pub fn offset(&self) -> u64 {
+        self.start
+    }
+}
+
+impl<R: Read + Seek> EasyReader<R> {
+    /// Where the cursor is, to come back there later on with `seek_to()`.
+    pub fn position(&self) -> Position {
+        let (start, end, at_bof) = self.save_cursor();
+        Position {
+            start,
+            end,
+            at_bof,
+            unaligned: self.pending_offset == Some(start) && start == end && !at_bof,
+        }
+    }
+
+    /// Moves the cursor back to a position returned by `position()`: the following reads go on
+    /// exactly as they would have from there.
+    ///
+    /// Fails with `ErrorKind::InvalidInput` if the position is past the EOF (e.g. it was taken
+    /// before the file was truncated).
+    pub fn seek_to(&mut self, position: Position) -> io::Result<&mut Self> {
+        if position.end > self.file_size {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "Position out of bounds",
+            ));
+        }
+        if position.unaligned {
+            return Ok(self.set_offset(position.start));
+        }
+        self.restore_cursor((position.start, position.end, position.at_bof));
+        Ok(self)
+    }
+}
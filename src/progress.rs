@@ -0,0 +1,82 @@
+use crate::{EasyReader, LineIndex, ReadMode, ReaderError, SCAN_BLOCK_SIZE};
+use std::{
+    io::{self, prelude::*},
+    ops::ControlFlow,
+};
+
+/// What to do with the lines indexed so far when `EasyReader::build_index_with_progress()` is
+/// aborted.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PartialIndex {
+    /// Keeps them aside, the next `build_index_with_progress()` resumes from where this one
+    /// stopped.
+    Keep,
+    /// Throws them away, the next build starts over from the BOF.
+    Discard,
+}
+
+impl<R: Read + Seek> EasyReader<R> {
+    /// Like `build_index()`, but calls `progress` with the bytes indexed so far and the file
+    /// size every 64 KiB or so (and once done), e.g. to show a progress bar.
+    ///
+    /// Returning `ControlFlow::Break` aborts the build, which fails with a
+    /// `ReaderError::Cancelled` error leaving the reader unindexed and its cursor untouched. The
+    /// lines indexed so far are kept aside or discarded depending on the [`PartialIndex`] given.
+    pub fn build_index_with_progress<F>(&mut self, mut progress: F) -> io::Result<&mut Self>
+    where
+        F: FnMut(u64, u64) -> ControlFlow<PartialIndex>,
+    {
+        let cursor = self.save_cursor();
+        let mut line_starts = match self.partial_index.take() {
+            Some(partial) if partial.is_compressed() != self.compressed_index => {
+                partial.with_compression(self.compressed_index)
+            }
+            Some(partial) => partial,
+            None => LineIndex::new(self.compressed_index),
+        };
+        self.drop_index();
+
+        let total = self.file_size;
+        let scan = (|| -> io::Result<Option<PartialIndex>> {
+            // Resuming, the scan goes on from the last line indexed
+            match line_starts.last() {
+                Some(start) => {
+                    self.current_start_line_offset = start;
+                    let end = self.find_end_line()?;
+                    self.set_current_line(start, end);
+                }
+                None => {
+                    self.bof();
+                }
+            }
+
+            let mut next_report = 0;
+            while self.move_cursor(ReadMode::Next)? {
+                let done = self.current_start_line_offset;
+                if done >= next_report {
+                    if let ControlFlow::Break(partial) = progress(done, total) {
+                        return Ok(Some(partial));
+                    }
+                    next_report = done + SCAN_BLOCK_SIZE as u64;
+                }
+                line_starts.push(done);
+            }
+            Ok(None)
+        })();
+        self.restore_cursor(cursor);
+
+        match scan? {
+            None => {
+                self.set_index(line_starts)?;
+                let _ = progress(total, total);
+                Ok(self)
+            }
+            Some(partial) => {
+                if partial == PartialIndex::Keep {
+                    self.partial_index = Some(line_starts);
+                }
+                Err(ReaderError::Cancelled.into())
+            }
+        }
+    }
+}
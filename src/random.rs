@@ -0,0 +1,296 @@
+use crate::EasyReader;
+use rand::{rngs::StdRng, RngCore, SeedableRng};
+use std::{
+    collections::HashMap,
+    io::{self, prelude::*},
+    ops::Range,
+};
+
+// How many lines are read at once when sampling without index
+const SAMPLE_BLOCK_LINES: usize = 1024;
+// Lines between the entries of the sparse index built by random_line_in()
+#[cfg(feature = "index")]
+const RANDOM_SPARSE_EVERY: usize = 1024;
+
+impl<R: Read + Seek> EasyReader<R> {
+    /// Draws the random lines (and the samples of `estimate_line_count()`) with `rng` instead
+    /// of `rand::thread_rng()`, e.g. a seeded RNG to get the same random lines from a run to
+    /// the next.
+    ///
+    /// The forks and the partitions of the reader use `rand::thread_rng()`.
+    pub fn rng<G: RngCore + Send + Sync + 'static>(&mut self, rng: G) -> &mut Self {
+        self.rng = Some(Box::new(rng));
+        self
+    }
+
+    /// Like `rng()`, with a `StdRng` seeded with `seed`: the random lines are reproducible, as
+    /// long as the file and the index (or its absence) are the same.
+    pub fn seed(&mut self, seed: u64) -> &mut Self {
+        self.rng(StdRng::seed_from_u64(seed))
+    }
+
+    /// Goes back to `rand::thread_rng()` for the random draws.
+    pub fn thread_rng(&mut self) -> &mut Self {
+        self.rng = None;
+        self
+    }
+
+    /// Like `random_line()`, but draws the line with `rng`, leaving the RNG of the reader
+    /// alone: e.g. to get the same random line for the same state of a simulation.
+    pub fn random_line_with_rng<G: RngCore + ?Sized>(
+        &mut self,
+        rng: &mut G,
+    ) -> io::Result<Option<String>> {
+        // A line may take several draws (see `unbiased_random()`), all of them derived from a
+        // single number of `rng`
+        self.call_rng = Some(StdRng::seed_from_u64(rng.next_u64()));
+        let line = self.random_line();
+        self.call_rng = None;
+        line
+    }
+
+    /// Without index, a random line is the one containing a random byte of the file, so a line
+    /// twice as long as another is twice as likely to be drawn. Once enabled, the draws are
+    /// rejected until the byte drawn is the first of its line: all the lines are equally
+    /// likely, but it takes as many draws as the average length of the lines (in bytes).
+    ///
+    /// Has no effect with an index (the lines are drawn by number, with no bias).
+    pub fn unbiased_random(&mut self, enabled: bool) -> &mut Self {
+        self.unbiased_random = enabled;
+        self
+    }
+
+    /// Like `random_line()`, but draws the line among the (zero-based) line numbers `lines`, e.g.
+    /// within a shard of a dataset. Returns `None`, without moving the cursor, if none of them
+    /// exists.
+    ///
+    /// Without an index, a sparse index with an entry every 1024 lines is built by the first
+    /// call (see `build_index_every()`): the following draws only count the lines from the
+    /// closest entry. Without the `index` feature, the lines are counted on every draw (see
+    /// `line_count()`) and up to the line drawn.
+    pub fn random_line_in(&mut self, lines: Range<u64>) -> io::Result<Option<String>> {
+        #[cfg(feature = "index")]
+        if self.index().is_none() && self.sparse_lines().is_none() {
+            self.index_sparse(RANDOM_SPARSE_EVERY, None)?;
+        }
+        let end = lines.end.min(self.line_count()?);
+        if lines.start >= end {
+            return Ok(None);
+        }
+        let line = lines.start + self.random_below(end - lines.start);
+
+        self.pending_offset = None;
+        if self.index().is_some() {
+            match self.index_entry(line)? {
+                Some((start, end)) => self.set_current_line(start, end),
+                None => return Ok(None),
+            }
+        } else {
+            match self.find_line_start(line)? {
+                Some(start) => self.align_to_line(start)?,
+                None => return Ok(None),
+            }
+        }
+        self.current_line()
+    }
+
+    /// Like `random_line()`, but draws the line containing a random byte of `bytes`, e.g. within
+    /// the part of a log covering a time window. The line may start before `bytes` (unless
+    /// drawn with `unbiased_random()`, then `None` is returned if no line starts in `bytes`).
+    /// Returns `None`, without moving the cursor, if `bytes` is out of the file.
+    pub fn random_line_in_bytes(&mut self, bytes: Range<u64>) -> io::Result<Option<String>> {
+        let bytes = bytes.start..bytes.end.min(self.file_size);
+        if bytes.is_empty() {
+            return Ok(None);
+        }
+        if self.unbiased_random && bytes.start > 0 {
+            // The last line reached by `bytes` is the last one which may start in it
+            let cursor = self.save_cursor();
+            let last_start = self.align_to_line(bytes.end - 1);
+            let last_start = last_start.map(|_| self.current_start_line_offset);
+            self.restore_cursor(cursor);
+            if last_start? < bytes.start {
+                return Ok(None);
+            }
+        }
+        let offset = self.random_offset(bytes)?;
+        self.line_at_offset(offset)
+    }
+
+    // A random offset of `range` (not empty) from which the line containing it is read
+    pub(crate) fn random_offset(&mut self, range: Range<u64>) -> io::Result<u64> {
+        loop {
+            let offset = range.start + self.random_below(range.end - range.start);
+            if !self.unbiased_random || offset == 0 {
+                return Ok(offset);
+            }
+            // The start of a line if the byte before it ends a terminator
+            let before = self.read_bytes(offset - 1, 1)?;
+            if self.ends_delimiter(&before, offset - 1, 0)? {
+                return Ok(offset);
+            }
+        }
+    }
+
+    /// `k` distinct random lines (all the lines if there are fewer), in no particular order,
+    /// without moving the cursor.
+    ///
+    /// With an index only the lines drawn are read, otherwise (or when lines are ignored, see
+    /// `comment_prefix()` and `skip_blank_lines()`) the whole file is read once (keeping a
+    /// reservoir of `k` lines).
+    pub fn sample(&mut self, k: usize) -> io::Result<Vec<String>> {
+        let lines = match self.index() {
+            Some(line_starts) if !self.ignores_lines() => line_starts.len() as u64,
+            _ => return self.sample_scanning(k),
+        };
+
+        // The first k steps of a Fisher-Yates shuffle of the line numbers, only the numbers
+        // swapped being stored
+        let k = (k as u64).min(lines);
+        let mut swapped = HashMap::new();
+        let mut sample = Vec::with_capacity(k as usize);
+        for i in 0..k {
+            let j = i + self.random_below(lines - i);
+            let line = *swapped.get(&j).unwrap_or(&j);
+            swapped.insert(j, *swapped.get(&i).unwrap_or(&i));
+            if let Some((start, end)) = self.index_entry(line)? {
+                sample.push(self.line_at(start, end)?);
+            }
+        }
+        Ok(sample)
+    }
+
+    fn sample_scanning(&mut self, k: usize) -> io::Result<Vec<String>> {
+        let mut sample = Vec::with_capacity(k);
+        if k == 0 {
+            return Ok(sample);
+        }
+        let (mut lines, mut offsets) = (Vec::new(), Vec::new());
+        // From the BOF, then after the terminator of the last line read
+        let (mut pos, mut skip_terminator, mut seen) = (0, false, 0);
+        loop {
+            self.lines_from(
+                pos,
+                skip_terminator,
+                SAMPLE_BLOCK_LINES,
+                &mut lines,
+                &mut offsets,
+            )?;
+            match offsets.last() {
+                Some(&(_, end)) => (pos, skip_terminator) = (end, true),
+                None => return Ok(sample),
+            }
+            for line in lines.drain(..) {
+                if self.is_ignored(line.as_bytes()) {
+                    continue;
+                }
+                seen += 1;
+                if sample.len() < k {
+                    sample.push(line);
+                } else {
+                    let j = self.random_below(seen) as usize;
+                    if j < k {
+                        sample[j] = line;
+                    }
+                }
+            }
+            offsets.clear();
+        }
+    }
+
+    /// `n` random lines drawn like `random_line()` does (so a line may be drawn more than once),
+    /// returned in the order of the file, without moving the cursor.
+    ///
+    /// The lines are drawn first, then read in order, those close to each other at once: far
+    /// fewer reads than `n` calls to `random_line()` when `n` is large.
+    pub fn random_lines(&mut self, n: usize) -> io::Result<Vec<String>> {
+        if self.file_size == 0 {
+            // Emptied by a rotation
+            return Ok(Vec::new());
+        }
+        if self.ignores_lines() {
+            return self.random_lines_unignored(n);
+        }
+        if let Some(lines) = self.index().map(|line_starts| line_starts.len() as u64) {
+            let mut draws: Vec<u64> = (0..n).map(|_| self.random_below(lines)).collect();
+            draws.sort_unstable();
+            // Each line up to the start of the next one, its terminator dropped once read
+            let spans: Vec<(u64, u64)> = match self.index() {
+                Some(line_starts) => draws
+                    .into_iter()
+                    .filter_map(|line| {
+                        let line = line as usize;
+                        let start = line_starts.get(line)?;
+                        Some((start, line_starts.get(line + 1).unwrap_or(self.file_size)))
+                    })
+                    .collect(),
+                None => Vec::new(),
+            };
+            return self.read_spans(&spans, true);
+        }
+
+        let mut offsets = Vec::with_capacity(n);
+        if let Some(lines) = self.sparse_lines() {
+            for _ in 0..n {
+                let line = self.random_below(lines);
+                offsets.push(self.find_line_start(line)?.unwrap_or(0));
+            }
+        } else {
+            for _ in 0..n {
+                offsets.push(self.random_offset(0..self.file_size)?);
+            }
+        }
+        offsets.sort_unstable();
+        let cursor = self.save_cursor();
+        let spans = offsets
+            .into_iter()
+            .map(|offset| {
+                self.align_to_line(offset)?;
+                Ok((self.current_start_line_offset, self.current_end_line_offset))
+            })
+            .collect::<io::Result<Vec<_>>>();
+        self.restore_cursor(cursor);
+        self.read_spans(&spans?, false)
+    }
+
+    // Like random_lines(), drawing the lines one by one to skip the ignored lines
+    fn random_lines_unignored(&mut self, n: usize) -> io::Result<Vec<String>> {
+        let cursor = self.save_cursor();
+        let pending_offset = self.pending_offset;
+        let mut lines = Vec::with_capacity(n);
+        let drawn: io::Result<()> = (|| {
+            for _ in 0..n {
+                match self.random_line()? {
+                    Some(line) => lines.push((self.current_start_line_offset, line)),
+                    None => break,
+                }
+            }
+            Ok(())
+        })();
+        self.restore_cursor(cursor);
+        self.pending_offset = pending_offset;
+        drawn?;
+        lines.sort_by_key(|&(start, _)| start);
+        Ok(lines.into_iter().map(|(_, line)| line).collect())
+    }
+
+    // The line at `start..end`, the cursor left where it is
+    fn line_at(&mut self, start: u64, end: u64) -> io::Result<String> {
+        let cursor = self.save_cursor();
+        self.set_current_line(start, end);
+        let line = self.read_current_line();
+        self.restore_cursor(cursor);
+        line
+    }
+
+    // A random number in 0..n (n > 0)
+    pub(crate) fn random_below(&mut self, n: u64) -> u64 {
+        let draw = match (self.call_rng.as_mut(), self.rng.as_mut()) {
+            (Some(rng), _) => rng.next_u64(),
+            (None, Some(rng)) => rng.next_u64(),
+            (None, None) => rand::thread_rng().next_u64(),
+        };
+        // Scaled rather than reduced modulo n, the bias is at most n / 2^64
+        ((u128::from(draw) * u128::from(n)) >> 64) as u64
+    }
+}
@@ -0,0 +1,103 @@
+use crate::{EasyReader, ReadMode};
+use std::io::{self, prelude::*, SeekFrom};
+#[cfg(feature = "index")]
+use std::{mem, sync::Arc};
+
+impl<R: Read + Seek> EasyReader<R> {
+    /// Picks up the data appended to the file since the reader was created (or last refreshed),
+    /// extending the index if there's one. Returns whether the file grew.
+    ///
+    /// Once the data left in the file has been read, a rotated file is reopened (see
+    /// `reopen()`), which counts as growth.
+    pub fn refresh(&mut self) -> io::Result<bool> {
+        let file_size = self.seek_file_size()?;
+        if file_size <= self.file_size {
+            return self.reopen();
+        }
+
+        // Moving forward from the EOF would skip the first new line: the cursor goes back
+        // to the last complete line
+        if !self.at_bof && self.current_start_line_offset == self.file_size {
+            let moved = self.move_cursor(ReadMode::Prev)?
+                && (self.current_end_line_offset < self.file_size
+                    || self.move_cursor(ReadMode::Prev)?);
+            if !moved {
+                self.bof();
+            }
+        }
+        let on_last_line = !self.at_bof && self.current_end_line_offset == self.file_size;
+        #[cfg(feature = "index")]
+        self.forget_last_line();
+
+        self.file_size = file_size;
+        self.line_count_estimate = None;
+        self.clear_chunk_cache();
+        if on_last_line {
+            // The line may have been incomplete
+            self.current_end_line_offset = self.find_end_line()?;
+        }
+        #[cfg(feature = "index")]
+        {
+            if self.indexed {
+                self.extend_index()?;
+            } else {
+                self.extend_sparse_index()?;
+            }
+            self.fingerprint_index()?;
+        }
+        Ok(true)
+    }
+
+    /// Makes `next_line()` (and the other forward reads) refresh the reader when reaching
+    /// the EOF, to see the lines appended since.
+    pub fn auto_refresh(&mut self, enabled: bool) -> &mut Self {
+        self.auto_refresh = enabled;
+        self
+    }
+
+    // The size of the file now (past the header, up to the end of the view), which may differ
+    // from `file_size`
+    pub(crate) fn seek_file_size(&mut self) -> io::Result<u64> {
+        self.stats.seeks += 1;
+        let mut file_size = self.file.seek(SeekFrom::End(0))?;
+        if let Some(end) = self.view_end {
+            file_size = file_size.min(end);
+        }
+        Ok(file_size.saturating_sub(self.header_size))
+    }
+
+    #[cfg(feature = "index")]
+    fn extend_index(&mut self) -> io::Result<()> {
+        let cursor = self.save_cursor();
+        let mut line_starts = mem::take(&mut self.line_starts);
+        self.indexed = false;
+
+        // The last line may have been incomplete, it's indexed again
+        let scan = (|| -> io::Result<Vec<u64>> {
+            let last_start = Arc::make_mut(&mut line_starts).pop();
+            match (line_starts.last(), last_start) {
+                (Some(start), Some(next)) => {
+                    let end = self.line_end_before(start, next)?;
+                    self.set_current_line(start, end);
+                }
+                _ => {
+                    self.bof();
+                }
+            }
+            let mut starts = Vec::new();
+            while self.move_cursor(ReadMode::Next)? {
+                starts.push(self.current_start_line_offset);
+            }
+            Ok(starts)
+        })();
+        self.restore_cursor(cursor);
+
+        let starts = Arc::make_mut(&mut line_starts);
+        for start in scan? {
+            starts.push(start);
+        }
+        self.line_starts = line_starts;
+        self.indexed = true;
+        Ok(())
+    }
+}
@@ -0,0 +1,114 @@
+use crate::EasyReader;
+use std::{
+    fs::{self, File, Metadata},
+    io::{self, prelude::*, ErrorKind},
+    path::{Path, PathBuf},
+};
+
+// Opens the file at the original path if it's been replaced, see EasyReader::open()
+pub(crate) type Reopen<R> = Box<dyn FnMut() -> io::Result<Option<R>> + Send + Sync>;
+
+impl EasyReader<File> {
+    /// Opens the file at `path`, keeping track of it to survive log rotations, see `reopen()`.
+    ///
+    /// With the `index` feature, its sidecar index (see `sidecar_index_path()`) is loaded if
+    /// it's there and matches the file.
+    pub fn open<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let file = File::open(&path)?;
+        let id = file_id(&file.metadata()?);
+
+        let mut reader = EasyReader::new(file)?;
+        #[cfg(feature = "index")]
+        {
+            // A missing or stale sidecar just leaves the reader unindexed
+            let _ = reader.load_index(EasyReader::sidecar_index_path(&path));
+        }
+        reader.track_path(path, id);
+        Ok(reader)
+    }
+
+    // Keeps track of the file at `path` (`id` being the one currently open) for `reopen()`
+    pub(crate) fn track_path(&mut self, path: PathBuf, mut id: Option<(u64, u64)>) {
+        self.path = Some(path.clone());
+        self.reopen = Some(Box::new(move || {
+            let metadata = match fs::metadata(&path) {
+                Ok(metadata) => metadata,
+                // Moved away, the new file isn't there yet
+                Err(err) if err.kind() == ErrorKind::NotFound => return Ok(None),
+                Err(err) => return Err(err),
+            };
+            // An empty new file is only picked up once written to
+            if file_id(&metadata) == id || metadata.len() == 0 {
+                return Ok(None);
+            }
+            let file = File::open(&path)?;
+            id = file_id(&file.metadata()?);
+            Ok(Some(file))
+        }));
+    }
+}
+
+impl<R: Read + Seek> EasyReader<R> {
+    /// Detects whether the file has been rotated and starts over from the BOF of the new one
+    /// if so, returning whether it happened.
+    ///
+    /// A file shrinking in place (e.g. rotated with logrotate's `copytruncate`) counts as a
+    /// rotation, unless another `truncation_policy()` is set. A file replaced by another one at
+    /// the same path (moved or deleted, then created again) is only detected for readers created
+    /// with `EasyReader::open()`, on Unix. The index (full or sparse), if any, is rebuilt for
+    /// the new file. `follow()` reopens the file by itself.
+    pub fn reopen(&mut self) -> io::Result<bool> {
+        let file_size = self.seek_file_size()?;
+        let replaced = match self.reopen.as_mut() {
+            Some(reopen) => reopen()?,
+            None => None,
+        };
+
+        match replaced {
+            Some(file) => {
+                self.file = file;
+                let file_size = self.seek_file_size()?;
+                self.start_over(file_size)?;
+            }
+            None if file_size < self.file_size => self.truncated(file_size)?,
+            None => return Ok(false),
+        }
+        Ok(true)
+    }
+
+    pub(crate) fn start_over(&mut self, file_size: u64) -> io::Result<()> {
+        self.file_size = file_size;
+        self.clear_chunk_cache();
+        self.bof();
+        self.line_count_estimate = None;
+        self.section_anchors.clear();
+        #[cfg(feature = "index")]
+        {
+            self.partial_index = None;
+            if let Some(lines) = self.lazy_index.as_mut() {
+                lines.clear();
+            }
+            let (indexed, sparse_every) = (self.indexed, self.sparse_every());
+            self.drop_index();
+            // Emptied, the index is kept (empty) to be extended as the file grows again
+            if indexed {
+                self.index_lines(None, None)?;
+            } else if let Some(every) = sparse_every {
+                self.index_sparse(every, None)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(unix)]
+pub(crate) fn file_id(metadata: &Metadata) -> Option<(u64, u64)> {
+    use std::os::unix::fs::MetadataExt;
+    Some((metadata.dev(), metadata.ino()))
+}
+
+#[cfg(not(unix))]
+pub(crate) fn file_id(_metadata: &Metadata) -> Option<(u64, u64)> {
+    None
+}
@@ -0,0 +1,71 @@
+#[cfg(feature = "index")]
+use crate::{Cancel, EasyReader, LineIndex};
+#[cfg(feature = "index")]
+use std::io::{self, prelude::*};
+
+// The file is scanned in blocks of this size when looking for every single-byte terminator
+pub(crate) const BULK_BLOCK_SIZE: usize = 4 * 1024 * 1024;
+
+// The positions of `byte` in `block`, found with SIMD instructions with the `simd` feature
+pub(crate) fn byte_positions(block: &[u8], byte: u8) -> impl Iterator<Item = usize> + '_ {
+    #[cfg(feature = "simd")]
+    return memchr::memchr_iter(byte, block);
+    #[cfg(not(feature = "simd"))]
+    return block
+        .iter()
+        .enumerate()
+        .filter(move |(_, b)| **b == byte)
+        .map(|(i, _)| i);
+}
+
+// The position of the first occurrence of `needle` (not empty) in `haystack`, found with SIMD
+// instructions with the `simd` feature
+pub(crate) fn find_literal(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    #[cfg(feature = "simd")]
+    return memchr::memmem::find(haystack, needle);
+    #[cfg(not(feature = "simd"))]
+    return haystack
+        .windows(needle.len())
+        .position(|window| window == needle);
+}
+
+#[cfg(feature = "index")]
+impl<R: Read + Seek> EasyReader<R> {
+    // Builds the index in bulk, for a single-byte delimiter: its occurrences are searched in
+    // large blocks instead of moving from a line to the next. Gives up past `max_lines` lines
+    // like index_lines(), returning None.
+    pub(crate) fn bulk_line_starts(
+        &mut self,
+        delimiter: u8,
+        max_lines: Option<usize>,
+        cancel: Option<&Cancel>,
+    ) -> io::Result<Option<LineIndex>> {
+        let mut line_starts = LineIndex::new(self.compressed_index);
+        // An empty view (or file) holds no line
+        if self.file_size == 0 {
+            return Ok(Some(line_starts));
+        }
+        line_starts.push(0);
+        let mut block = Vec::new();
+        let mut offset = 0;
+        while offset < self.file_size {
+            Cancel::check_opt(cancel)?;
+            let len = (self.file_size - offset).min(BULK_BLOCK_SIZE as u64) as usize;
+            block.clear();
+            self.read_into(offset, len, &mut block)?;
+            for i in byte_positions(&block, delimiter) {
+                let start = offset + i as u64 + 1;
+                // A trailing line terminator doesn't open a new line
+                if start == self.file_size {
+                    break;
+                }
+                if Some(line_starts.len()) == max_lines {
+                    return Ok(None);
+                }
+                line_starts.push(start);
+            }
+            offset += len as u64;
+        }
+        Ok(Some(line_starts))
+    }
+}
@@ -0,0 +1,588 @@
+use crate::{
+    scan::{byte_positions, find_literal, BULK_BLOCK_SIZE},
+    utf8_error, Cancel, EasyReader, ReadMode,
+};
+#[cfg(feature = "regex")]
+use regex::Regex;
+use std::{
+    cmp::Ordering,
+    io::{self, prelude::*},
+    ops::Range,
+    str,
+};
+
+// How many lines are read at once while searching
+const SEARCH_BLOCK_LINES: usize = 1024;
+
+/// A line with the lines around it, as `grep -C` shows them, see `EasyReader::line_context()`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LineContext {
+    /// The lines before the line, in the order of the file.
+    pub before: Vec<String>,
+    pub line: String,
+    /// The lines after the line.
+    pub after: Vec<String>,
+}
+
+impl<R: Read + Seek> EasyReader<R> {
+    /// Moves to the first line after the cursor matching `pattern` and returns it, like
+    /// `next_line_matching()` does.
+    #[cfg(feature = "regex")]
+    pub fn find_next(&mut self, pattern: &Regex) -> io::Result<Option<String>> {
+        self.next_line_matching(|line| pattern.is_match(line))
+    }
+
+    /// Like `find_next()`, but moving backward from the cursor to the first line before it
+    /// matching `pattern`.
+    #[cfg(feature = "regex")]
+    pub fn find_prev(&mut self, pattern: &Regex) -> io::Result<Option<String>> {
+        self.prev_line_matching(|line| pattern.is_match(line))
+    }
+
+    /// Like `find_next()`, but gives up as soon as `cancel` is cancelled, without moving the
+    /// cursor.
+    #[cfg(feature = "regex")]
+    pub fn find_next_cancellable(
+        &mut self,
+        pattern: &Regex,
+        cancel: &Cancel,
+    ) -> io::Result<Option<String>> {
+        self.next_line_matching_cancellable(|line| pattern.is_match(line), cancel)
+    }
+
+    /// Like `find_prev()`, but gives up as soon as `cancel` is cancelled, without moving the
+    /// cursor.
+    #[cfg(feature = "regex")]
+    pub fn find_prev_cancellable(
+        &mut self,
+        pattern: &Regex,
+        cancel: &Cancel,
+    ) -> io::Result<Option<String>> {
+        self.prev_line_matching_cancellable(|line| pattern.is_match(line), cancel)
+    }
+
+    /// Moves to the first line after the cursor for which `predicate` is true and returns it,
+    /// e.g. to jump to the next error of a log. Returns `None`, without moving the cursor, if
+    /// there's none.
+    ///
+    /// The lines are read in large blocks, like `next_lines()` does.
+    pub fn next_line_matching<P: FnMut(&str) -> bool>(
+        &mut self,
+        predicate: P,
+    ) -> io::Result<Option<String>> {
+        self.search(true, predicate, None)
+    }
+
+    /// Like `next_line_matching()`, but moving backward from the cursor.
+    pub fn prev_line_matching<P: FnMut(&str) -> bool>(
+        &mut self,
+        predicate: P,
+    ) -> io::Result<Option<String>> {
+        self.search(false, predicate, None)
+    }
+
+    /// Like `next_line_matching()`, but gives up as soon as `cancel` is cancelled (checked
+    /// between the blocks of lines read), without moving the cursor.
+    pub fn next_line_matching_cancellable<P: FnMut(&str) -> bool>(
+        &mut self,
+        predicate: P,
+        cancel: &Cancel,
+    ) -> io::Result<Option<String>> {
+        self.search(true, predicate, Some(cancel))
+    }
+
+    /// Like `prev_line_matching()`, but gives up as soon as `cancel` is cancelled, without
+    /// moving the cursor.
+    pub fn prev_line_matching_cancellable<P: FnMut(&str) -> bool>(
+        &mut self,
+        predicate: P,
+        cancel: &Cancel,
+    ) -> io::Result<Option<String>> {
+        self.search(false, predicate, Some(cancel))
+    }
+
+    /// Like `find_next()`, returning the line found with up to `before` lines before it and
+    /// `after` lines after it, like `grep -C`. The cursor is left on the line found.
+    #[cfg(feature = "regex")]
+    pub fn find_next_with_context(
+        &mut self,
+        pattern: &Regex,
+        before: usize,
+        after: usize,
+    ) -> io::Result<Option<LineContext>> {
+        match self.find_next(pattern)? {
+            Some(line) => self.context_of(line, before, after).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    /// Like `find_prev()`, returning the line found with its context like
+    /// `find_next_with_context()`.
+    #[cfg(feature = "regex")]
+    pub fn find_prev_with_context(
+        &mut self,
+        pattern: &Regex,
+        before: usize,
+        after: usize,
+    ) -> io::Result<Option<LineContext>> {
+        match self.find_prev(pattern)? {
+            Some(line) => self.context_of(line, before, after).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    /// The current line with up to `before` lines before it and `after` lines after it (fewer
+    /// close to the BOF or the EOF), without moving the cursor. The lines around are read in
+    /// blocks, like `prev_lines()` and `next_lines()` do.
+    pub fn line_context(&mut self, before: usize, after: usize) -> io::Result<Option<LineContext>> {
+        match self.current_line()? {
+            Some(line) => self.context_of(line, before, after).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    fn context_of(&mut self, line: String, before: usize, after: usize) -> io::Result<LineContext> {
+        let mut context = LineContext {
+            before: Vec::new(),
+            line,
+            after: Vec::new(),
+        };
+        self.read_behind(before, &mut context.before, &mut Vec::new())?;
+        context.before.reverse();
+        self.read_ahead(after, &mut context.after, &mut Vec::new())?;
+        Ok(context)
+    }
+
+    /// Moves to the first line after the cursor containing `needle` and returns it. Returns
+    /// `None`, without moving the cursor, if no line does.
+    ///
+    /// The bytes of the file are searched in large blocks, not line by line (with SIMD
+    /// instructions with the `simd` feature): the lines are only located and decoded once
+    /// found. `needle` is looked for as is, so it shouldn't hold any line terminator, nor be
+    /// UTF-8 with another `encoding()`.
+    pub fn find_next_literal<N: AsRef<[u8]>>(&mut self, needle: N) -> io::Result<Option<String>> {
+        self.find_literal_after(needle.as_ref(), None)
+    }
+
+    /// Like `find_next_literal()`, but gives up as soon as `cancel` is cancelled (checked
+    /// between the blocks searched), without moving the cursor.
+    pub fn find_next_literal_cancellable<N: AsRef<[u8]>>(
+        &mut self,
+        needle: N,
+        cancel: &Cancel,
+    ) -> io::Result<Option<String>> {
+        self.find_literal_after(needle.as_ref(), Some(cancel))
+    }
+
+    fn find_literal_after(
+        &mut self,
+        needle: &[u8],
+        cancel: Option<&Cancel>,
+    ) -> io::Result<Option<String>> {
+        if needle.is_empty() {
+            return self.next_line();
+        }
+
+        // From the line next_line() would return
+        let mut pos = match self.pending_offset {
+            Some(offset) => {
+                let cursor = self.save_cursor();
+                let aligned = self.align_to_line(offset);
+                let start = self.current_start_line_offset;
+                self.restore_cursor(cursor);
+                aligned.map(|_| start)?
+            }
+            None if self.at_bof => 0,
+            None => self.current_end_line_offset,
+        };
+        let mut block = Vec::new();
+        while pos < self.file_size {
+            Cancel::check_opt(cancel)?;
+            let len = (self.file_size - pos).min(BULK_BLOCK_SIZE as u64) as usize;
+            block.clear();
+            self.read_into(pos, len, &mut block)?;
+            if let Some(i) = find_literal(&block, needle) {
+                let offset = pos + i as u64;
+                match self.ignored_line_end(offset)? {
+                    // Past the ignored line
+                    Some(end) => pos = end,
+                    None => return self.line_at_offset(offset),
+                }
+                continue;
+            }
+            // The blocks overlap, for the occurrences across two of them
+            pos += len.saturating_sub(needle.len() - 1).max(1) as u64;
+            if pos + needle.len() as u64 > self.file_size {
+                break;
+            }
+        }
+        Ok(None)
+    }
+
+    // The end of the line containing `offset` if it's ignored (see `comment_prefix()`), without
+    // moving the cursor
+    fn ignored_line_end(&mut self, offset: u64) -> io::Result<Option<u64>> {
+        if !self.ignores_lines() {
+            return Ok(None);
+        }
+        let cursor = self.save_cursor();
+        let ignored = self
+            .align_to_line(offset)
+            .and_then(|_| self.on_ignored_line());
+        let end = self.current_end_line_offset;
+        self.restore_cursor(cursor);
+        Ok(if ignored? { Some(end) } else { None })
+    }
+
+    /// Counts the lines of the file for which `predicate` is true, without moving the cursor.
+    ///
+    /// The lines are split out of large blocks and decoded one after the other into the same
+    /// buffer: nothing is allocated for each line. Like `head()`, the lines are split at the
+    /// line terminators only (see `continuation()`) and the lines ignored (see
+    /// `comment_prefix()` and `skip_blank_lines()`) aren't counted.
+    pub fn count_matching<P: FnMut(&str) -> bool>(&mut self, predicate: P) -> io::Result<u64> {
+        self.count_lines_matching(0..self.file_size, predicate, None)
+    }
+
+    /// Like `count_matching()`, but only among the lines starting in the byte range `bytes`.
+    pub fn count_matching_in<P>(&mut self, bytes: Range<u64>, predicate: P) -> io::Result<u64>
+    where
+        P: FnMut(&str) -> bool,
+    {
+        self.count_lines_matching(bytes, predicate, None)
+    }
+
+    /// Like `count_matching()`, but gives up as soon as `cancel` is cancelled (checked between
+    /// the blocks read).
+    pub fn count_matching_cancellable<P>(
+        &mut self,
+        predicate: P,
+        cancel: &Cancel,
+    ) -> io::Result<u64>
+    where
+        P: FnMut(&str) -> bool,
+    {
+        self.count_lines_matching(0..self.file_size, predicate, Some(cancel))
+    }
+
+    fn count_lines_matching<P>(
+        &mut self,
+        bytes: Range<u64>,
+        mut predicate: P,
+        cancel: Option<&Cancel>,
+    ) -> io::Result<u64>
+    where
+        P: FnMut(&str) -> bool,
+    {
+        let end = bytes.end.min(self.file_size);
+        if bytes.start >= end {
+            return Ok(0);
+        }
+        let cr = self.delimiter.cr();
+        let delimiter_len = self.delimiter.len();
+        // Past the BOF, the first line starts after the first terminator ending in `bytes`
+        let mut skip_terminator = bytes.start > 0;
+        let mut pos = bytes.start.saturating_sub(1);
+        // The bytes from `buffer_start` to `pos`, not split into lines yet
+        let (mut buffer, mut buffer_start) = (Vec::new(), pos);
+        let (mut terminator_ends, mut line) = (Vec::new(), Vec::new());
+        let mut count = 0;
+        while pos < self.file_size && buffer_start < end {
+            Cancel::check_opt(cancel)?;
+            let len = (self.file_size - pos).min(BULK_BLOCK_SIZE as u64) as usize;
+            // The bytes before the new block were already scanned
+            let scanned = buffer.len();
+            self.read_into(pos, len, &mut buffer)?;
+            pos += len as u64;
+
+            terminator_ends.clear();
+            match self.delimiter.byte() {
+                Some(byte) => terminator_ends
+                    .extend(byte_positions(&buffer[scanned..], byte).map(|i| scanned + i)),
+                None => {
+                    for i in scanned..buffer.len() {
+                        if self.ends_delimiter(&buffer, buffer_start, i)? {
+                            terminator_ends.push(i);
+                        }
+                    }
+                }
+            }
+
+            let mut consumed = 0;
+            for &i in &terminator_ends {
+                if skip_terminator {
+                    skip_terminator = false;
+                } else {
+                    let start = buffer_start + consumed as u64;
+                    if start >= end {
+                        break;
+                    }
+                    let mut bytes = &buffer[consumed..i + 1 - delimiter_len];
+                    if let Some(cr) = cr.filter(|cr| bytes.ends_with(cr)) {
+                        bytes = &bytes[..bytes.len() - cr.len()];
+                    }
+                    if self.line_matches(bytes, start, &mut line, &mut predicate)? {
+                        count += 1;
+                    }
+                }
+                consumed = i + 1;
+            }
+            buffer.drain(..consumed);
+            buffer_start += consumed as u64;
+        }
+        // The last line of the file may have no terminator
+        if !skip_terminator
+            && buffer_start < end
+            && !buffer.is_empty()
+            && self.line_matches(&buffer, buffer_start, &mut line, &mut predicate)?
+        {
+            count += 1;
+        }
+        Ok(count)
+    }
+
+    /// Like `count_matching()`, counting the lines matching `pattern`.
+    #[cfg(feature = "regex")]
+    pub fn count_matching_regex(&mut self, pattern: &Regex) -> io::Result<u64> {
+        self.count_matching(|line| pattern.is_match(line))
+    }
+
+    // Decodes the `bytes` of the line starting at `start` into `line` and applies `predicate`
+    fn line_matches<P>(
+        &mut self,
+        bytes: &[u8],
+        start: u64,
+        line: &mut Vec<u8>,
+        predicate: &mut P,
+    ) -> io::Result<bool>
+    where
+        P: FnMut(&str) -> bool,
+    {
+        // Like the reads, the ignored lines are skipped before being decoded
+        if self.is_ignored(bytes) {
+            return Ok(false);
+        }
+        let end = start + bytes.len() as u64;
+        self.check_line_length(start, end - start)?;
+        line.clear();
+        line.extend_from_slice(bytes);
+        self.transcode(line, 0, start, end)?;
+        self.trim_line(line, 0);
+        self.stats.lines_decoded += 1;
+        let text = str::from_utf8(line).map_err(|err| utf8_error(start, end, err, line.clone()))?;
+        Ok(predicate(text))
+    }
+
+    /// Binary searches a file whose lines are sorted (a dictionary, a sorted dump...) for a line
+    /// which `compare` finds `Equal`, like `slice::binary_search_by()`: the byte range left is
+    /// halved at each step, so only O(log n) lines are read. Moves to the line found and returns
+    /// it, or returns `Err` with the offset where a matching line would be inserted (the start of
+    /// the first greater line, or the file size) without moving the cursor.
+    ///
+    /// If several lines match any of them may be returned. The result is meaningless if the
+    /// lines aren't sorted.
+    pub fn binary_search_by<F>(&mut self, mut compare: F) -> io::Result<Result<String, u64>>
+    where
+        F: FnMut(&str) -> Ordering,
+    {
+        self.bisect(|_, line| Ok(compare(line)), None)
+    }
+
+    /// Like `binary_search_by()`, but gives up as soon as `cancel` is cancelled (checked at each
+    /// step), without moving the cursor.
+    pub fn binary_search_by_cancellable<F>(
+        &mut self,
+        mut compare: F,
+        cancel: &Cancel,
+    ) -> io::Result<Result<String, u64>>
+    where
+        F: FnMut(&str) -> Ordering,
+    {
+        self.bisect(|_, line| Ok(compare(line)), Some(cancel))
+    }
+
+    /// Moves to the first line for which `predicate` is false and returns it, in a file where
+    /// `predicate` is true for the lines before that one and false for those after it (e.g. the
+    /// first record past a date in a sorted log), like `slice::partition_point()`. Bisects the
+    /// file like `binary_search_by()`. Returns `None`, without moving the cursor, if `predicate`
+    /// is true for all the lines.
+    pub fn partition_point<P>(&mut self, mut predicate: P) -> io::Result<Option<String>>
+    where
+        P: FnMut(&str) -> bool,
+    {
+        let compare = |line: &str| match predicate(line) {
+            true => Ordering::Less,
+            false => Ordering::Greater,
+        };
+        match self.binary_search_by(compare)? {
+            Ok(line) => Ok(Some(line)),
+            Err(offset) => self.line_at_offset(offset),
+        }
+    }
+
+    /// Moves to the first line of a time-sorted log whose time is at or after `target` and
+    /// returns it, `parser` extracting the time of a line. Bisects the file like
+    /// `binary_search_by()`, so even huge logs are sliced right away. The lines without a time
+    /// (stack traces, continued messages...) are taken as part of the record of the line
+    /// before them. Returns `None`, without moving the cursor, if all the lines are earlier.
+    pub fn seek_to_time<T, F>(&mut self, target: T, parser: F) -> io::Result<Option<String>>
+    where
+        T: Ord,
+        F: FnMut(&str) -> Option<T>,
+    {
+        self.seek_to_time_in(target, parser, None)
+    }
+
+    /// Like `seek_to_time()`, but gives up as soon as `cancel` is cancelled, without moving the
+    /// cursor.
+    pub fn seek_to_time_cancellable<T, F>(
+        &mut self,
+        target: T,
+        parser: F,
+        cancel: &Cancel,
+    ) -> io::Result<Option<String>>
+    where
+        T: Ord,
+        F: FnMut(&str) -> Option<T>,
+    {
+        self.seek_to_time_in(target, parser, Some(cancel))
+    }
+
+    fn seek_to_time_in<T, F>(
+        &mut self,
+        target: T,
+        mut parser: F,
+        cancel: Option<&Cancel>,
+    ) -> io::Result<Option<String>>
+    where
+        T: Ord,
+        F: FnMut(&str) -> Option<T>,
+    {
+        let found = self.bisect(
+            |reader, line| {
+                let mut time = parser(line);
+                if time.is_none() {
+                    let has_time = |line: &str| {
+                        time = parser(line);
+                        time.is_some()
+                    };
+                    reader.search(false, has_time, cancel)?;
+                }
+                // The lines before the first time are earlier than any time
+                Ok(time.map_or(Ordering::Less, |time| match time < target {
+                    true => Ordering::Less,
+                    false => Ordering::Greater,
+                }))
+            },
+            cancel,
+        )?;
+        match found {
+            Ok(line) => Ok(Some(line)),
+            Err(offset) => self.line_at_offset(offset),
+        }
+    }
+
+    // Moves to the first line after (or before) the cursor for which `predicate` is true, the
+    // cursor left where it was if none is (or on an error)
+    fn search<P>(
+        &mut self,
+        forward: bool,
+        mut predicate: P,
+        cancel: Option<&Cancel>,
+    ) -> io::Result<Option<String>>
+    where
+        P: FnMut(&str) -> bool,
+    {
+        let cursor = self.save_cursor();
+        let pending_offset = self.pending_offset;
+        let found = (|| {
+            // The lines which have to be read one by one (see `iter()`)
+            if !self.can_read_ahead() {
+                loop {
+                    Cancel::check_opt(cancel)?;
+                    let line = if forward {
+                        self.next_line()?
+                    } else {
+                        self.prev_line()?
+                    };
+                    match line {
+                        Some(line) if predicate(&line) => return Ok(Some(line)),
+                        Some(_) => (),
+                        None => return Ok(None),
+                    }
+                }
+            }
+
+            let (mut lines, mut offsets) = (Vec::new(), Vec::new());
+            loop {
+                Cancel::check_opt(cancel)?;
+                let read = if forward {
+                    self.read_ahead(SEARCH_BLOCK_LINES, &mut lines, &mut offsets)
+                } else {
+                    self.read_behind(SEARCH_BLOCK_LINES, &mut lines, &mut offsets)
+                };
+                for (line, &(start, end)) in lines.drain(..).zip(offsets.iter()) {
+                    if predicate(&line) {
+                        self.set_current_line(start, end);
+                        return Ok(Some(line));
+                    }
+                }
+                read?;
+                match offsets.last() {
+                    Some(&(start, end)) => self.set_current_line(start, end),
+                    None => return Ok(None),
+                }
+                offsets.clear();
+            }
+        })();
+        if !matches!(found, Ok(Some(_))) {
+            self.restore_cursor(cursor);
+            self.pending_offset = pending_offset;
+        }
+        found
+    }
+
+    // Bisects the file like binary_search_by(), `compare` being free to move the cursor
+    fn bisect<F>(
+        &mut self,
+        mut compare: F,
+        cancel: Option<&Cancel>,
+    ) -> io::Result<Result<String, u64>>
+    where
+        F: FnMut(&mut Self, &str) -> io::Result<Ordering>,
+    {
+        let cursor = self.save_cursor();
+        let pending_offset = self.pending_offset;
+        let found = (|| {
+            // The lines starting in lo..hi are left to compare, lo and hi being line starts (or
+            // the file size)
+            let (mut lo, mut hi) = (0, self.file_size);
+            while lo < hi {
+                Cancel::check_opt(cancel)?;
+                let line = match self.line_at_offset(lo + (hi - lo) / 2)? {
+                    Some(line) => line,
+                    None => break,
+                };
+                let (start, end) = self.current_offsets();
+                match compare(self, &line)? {
+                    Ordering::Equal => return Ok(Ok(line)),
+                    Ordering::Greater => hi = start,
+                    Ordering::Less => {
+                        self.set_current_line(start, end);
+                        lo = if self.move_cursor(ReadMode::Next)? {
+                            self.current_start_line_offset
+                        } else {
+                            self.file_size
+                        }
+                    }
+                }
+            }
+            Ok(Err(lo))
+        })();
+        if !matches!(found, Ok(Ok(_))) {
+            self.restore_cursor(cursor);
+            self.pending_offset = pending_offset;
+        }
+        found
+    }
+}
@@ -0,0 +1,77 @@
+use crate::EasyReader;
+#[cfg(feature = "regex")]
+use regex::Regex;
+use std::io::{self, prelude::*};
+
+impl<R: Read + Seek> EasyReader<R> {
+    /// Scans the whole file for the lines matching `pattern` and uses them as section anchors.
+    ///
+    /// Returns the number of sections found. The cursor is left untouched.
+    #[cfg(feature = "regex")]
+    pub fn anchors(&mut self, pattern: &Regex) -> io::Result<usize> {
+        self.anchors_by(|line| pattern.is_match(line))
+    }
+
+    /// Like `anchors`, but with an arbitrary predicate deciding which lines open a section.
+    pub fn anchors_by<P: FnMut(&str) -> bool>(&mut self, mut predicate: P) -> io::Result<usize> {
+        let cursor = self.save_cursor();
+
+        self.section_anchors.clear();
+        self.bof();
+        let scan = (|| {
+            while let Some(line) = self.next_line()? {
+                if predicate(&line) {
+                    self.section_anchors
+                        .push((self.current_start_line_offset, self.current_end_line_offset));
+                }
+            }
+            Ok(())
+        })();
+
+        self.restore_cursor(cursor);
+        scan.map(|_| self.section_anchors.len())
+    }
+
+    pub fn sections_count(&self) -> usize {
+        self.section_anchors.len()
+    }
+
+    /// Moves to the next section anchor after the cursor and returns its line.
+    pub fn next_section(&mut self) -> io::Result<Option<String>> {
+        let (current, at_bof) = (self.current_start_line_offset, self.at_bof);
+        let next = self
+            .section_anchors
+            .iter()
+            .position(|&(start, _)| start > current || (at_bof && start == 0));
+
+        match next {
+            Some(i) => self.goto_section(i),
+            None => Ok(None),
+        }
+    }
+
+    /// Moves to the previous section anchor before the cursor and returns its line.
+    pub fn prev_section(&mut self) -> io::Result<Option<String>> {
+        let current = self.current_start_line_offset;
+        let prev = self
+            .section_anchors
+            .iter()
+            .rposition(|&(start, _)| start < current);
+
+        match prev {
+            Some(i) => self.goto_section(i),
+            None => Ok(None),
+        }
+    }
+
+    /// Moves to the `i`-th (zero-based) section anchor and returns its line.
+    pub fn goto_section(&mut self, i: usize) -> io::Result<Option<String>> {
+        match self.section_anchors.get(i) {
+            Some(&(start, end)) => {
+                self.set_current_line(start, end);
+                self.current_line()
+            }
+            None => Ok(None),
+        }
+    }
+}
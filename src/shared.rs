@@ -0,0 +1,181 @@
+use crate::{clamp_len, EasyReader, SCAN_BLOCK_SIZE};
+#[cfg(any(unix, windows))]
+use std::fs::File;
+use std::{
+    convert::TryFrom,
+    io::{self, prelude::*, Error, ErrorKind},
+};
+
+/// Positional reads, which don't move a cursor shared by all the reads of the source.
+///
+/// A reader over a `ReadAt` source can read lines from `&self` (see `EasyReader::get_line()`),
+/// so that a single reader can serve several threads without being locked.
+pub trait ReadAt {
+    /// Reads some bytes starting at `offset` into `buf`, returning how many were read (0 at
+    /// the EOF).
+    fn read_at(&self, buf: &mut [u8], offset: u64) -> io::Result<usize>;
+}
+
+#[cfg(unix)]
+impl ReadAt for File {
+    fn read_at(&self, buf: &mut [u8], offset: u64) -> io::Result<usize> {
+        std::os::unix::fs::FileExt::read_at(self, buf, offset)
+    }
+}
+
+// seek_read() moves the cursor of the file, but every other read seeks before reading
+#[cfg(windows)]
+impl ReadAt for File {
+    fn read_at(&self, buf: &mut [u8], offset: u64) -> io::Result<usize> {
+        std::os::windows::fs::FileExt::seek_read(self, buf, offset)
+    }
+}
+
+impl<T: AsRef<[u8]>> ReadAt for io::Cursor<T> {
+    fn read_at(&self, buf: &mut [u8], offset: u64) -> io::Result<usize> {
+        let bytes = self.get_ref().as_ref();
+        let from = (offset.min(bytes.len() as u64)) as usize;
+        let len = buf.len().min(bytes.len() - from);
+        buf[..len].copy_from_slice(&bytes[from..from + len]);
+        Ok(len)
+    }
+}
+
+pub(crate) fn read_exact_at<S: ReadAt + ?Sized>(
+    source: &S,
+    mut buffer: &mut [u8],
+    mut offset: u64,
+) -> io::Result<()> {
+    while !buffer.is_empty() {
+        match source.read_at(buffer, offset) {
+            Ok(0) => {
+                return Err(Error::new(
+                    ErrorKind::UnexpectedEof,
+                    "The file is shorter than expected",
+                ))
+            }
+            Ok(read) => {
+                buffer = &mut buffer[read..];
+                offset += read as u64;
+            }
+            Err(err) if err.kind() == ErrorKind::Interrupted => (),
+            Err(err) => return Err(err),
+        }
+    }
+    Ok(())
+}
+
+pub(crate) fn read_vec_at<S: ReadAt + ?Sized>(
+    source: &S,
+    offset: u64,
+    len: usize,
+) -> io::Result<Vec<u8>> {
+    let mut buffer = vec![0; len];
+    read_exact_at(source, &mut buffer, offset)?;
+    Ok(buffer)
+}
+
+impl<R: Read + Seek + ReadAt> EasyReader<R> {
+    /// Reads the `line`-th line (zero-based) without moving the cursor. Being positional, the
+    /// read only needs `&self`: a reader shared between threads (e.g. in an `Arc`) can serve
+    /// lines to all of them at once.
+    ///
+    /// Immediate with a full index. Otherwise the line is found by scanning from the closest
+    /// line of the sparse index, if any, or from the BOF.
+    pub fn get_line(&self, line: u64) -> io::Result<Option<String>> {
+        let (start, end) = match self.index() {
+            Some(line_starts) => {
+                let line = match usize::try_from(line) {
+                    Ok(line) => line,
+                    Err(_) => return Ok(None),
+                };
+                match line_starts.get(line) {
+                    Some(start) => {
+                        let next = line_starts.get(line + 1).unwrap_or(self.file_size);
+                        (start, self.line_end_before_at(start, next)?)
+                    }
+                    None => return Ok(None),
+                }
+            }
+            None => match self.scan_line_start_at(line)? {
+                Some(start) => (start, self.scan_line_end_at(start)?),
+                None => return Ok(None),
+            },
+        };
+
+        let mut buffer = vec![0; (end - start) as usize];
+        read_exact_at(&self.file, &mut buffer, self.header_size + start)?;
+        self.decode(buffer, start, end).map(Some)
+    }
+
+    fn scan_line_start_at(&self, line: u64) -> io::Result<Option<u64>> {
+        let (mut current_line, mut pos) = self.line_checkpoint(line);
+        if current_line == line {
+            // An empty file has no first line
+            return Ok(Some(pos).filter(|&s| s < self.file_size));
+        }
+
+        let mut block = vec![0; SCAN_BLOCK_SIZE];
+        while pos < self.file_size {
+            let len = (self.file_size - pos).min(SCAN_BLOCK_SIZE as u64) as usize;
+            read_exact_at(&self.file, &mut block[..len], self.header_size + pos)?;
+            for i in 0..len {
+                if self.ends_delimiter_at(&block[..len], pos, i)? {
+                    current_line += 1;
+                    if current_line == line {
+                        let line_start = pos + i as u64 + 1;
+                        // A trailing line terminator doesn't open a new line
+                        return Ok(Some(line_start).filter(|&s| s < self.file_size));
+                    }
+                }
+            }
+            pos += len as u64;
+        }
+        Ok(None)
+    }
+
+    // End of the line starting at `start`, read chunk by chunk up to its terminator
+    fn scan_line_end_at(&self, start: u64) -> io::Result<u64> {
+        let mut chunk = vec![0; self.chunk_size.max(1)];
+        let mut pos = start;
+        while pos < self.file_size {
+            let len = (self.file_size - pos).min(chunk.len() as u64) as usize;
+            read_exact_at(&self.file, &mut chunk[..len], self.header_size + pos)?;
+            for i in 0..len {
+                if self.ends_delimiter_at(&chunk[..len], pos, i)? {
+                    return self.line_end_before_at(start, pos + i as u64 + 1);
+                }
+            }
+            pos += len as u64;
+        }
+        Ok(self.file_size)
+    }
+
+    // Like line_end_before(), from `&self`
+    fn line_end_before_at(&self, start: u64, next: u64) -> io::Result<u64> {
+        self.delimiter.terminator_start(start, next, |offset, len| {
+            read_vec_at(
+                &self.file,
+                self.header_size + offset,
+                clamp_len(offset, len, self.file_size),
+            )
+        })
+    }
+
+    // Like ends_delimiter(), from `&self`
+    pub(crate) fn ends_delimiter_at(
+        &self,
+        block: &[u8],
+        block_start: u64,
+        i: usize,
+    ) -> io::Result<bool> {
+        self.delimiter
+            .ends_after(block, block_start, i, |offset, len| {
+                read_vec_at(
+                    &self.file,
+                    self.header_size + offset,
+                    clamp_len(offset, len, self.file_size),
+                )
+            })
+    }
+}
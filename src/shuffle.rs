@@ -0,0 +1,318 @@
+use crate::EasyReader;
+use std::{
+    io::{self, prelude::*, Error, ErrorKind},
+    iter::FusedIterator,
+    ops::Range,
+    panic,
+    sync::mpsc::{self, Receiver},
+    thread::{self, JoinHandle},
+};
+
+// Rounds of the Feistel network permuting the line numbers
+const FEISTEL_ROUNDS: usize = 4;
+// How many lines are read at once looking for the ignored lines
+const SCAN_BLOCK_LINES: usize = 1024;
+
+/// An iterator over all the lines of the file, each once, in a pseudo-random order, see
+/// `EasyReader::shuffled_lines()`.
+///
+/// Each item moves the cursor to the line returned. The iteration stops after the first error.
+pub struct ShuffledLines<'a, R> {
+    reader: &'a mut EasyReader<R>,
+    permutation: Permutation,
+    next: u64,
+    failed: bool,
+}
+
+impl<R: Read + Seek> Iterator for ShuffledLines<'_, R> {
+    type Item = io::Result<String>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.failed || self.next >= self.permutation.len {
+            return None;
+        }
+        let line = self.permutation.line(self.next);
+        self.next += 1;
+        let line = match self.reader.index_entry(line) {
+            Ok(Some((start, end))) => {
+                self.reader.set_current_line(start, end);
+                self.reader.current_line().transpose()
+            }
+            Ok(None) => None,
+            Err(err) => Some(Err(err)),
+        };
+        self.failed = !matches!(line, Some(Ok(_)));
+        line
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let left = (self.permutation.len - self.next) as usize;
+        (if self.failed { 0 } else { left }, Some(left))
+    }
+}
+
+impl<R: Read + Seek> FusedIterator for ShuffledLines<'_, R> {}
+
+/// An iterator over all the lines of the file, each once, in batches of shuffled lines, see
+/// `EasyReader::batches()`.
+///
+/// The cursor doesn't move. The iteration stops after the first error.
+pub struct Batches<'a, R> {
+    reader: &'a mut EasyReader<R>,
+    permutation: Permutation,
+    batch_size: u64,
+    next: u64,
+    failed: bool,
+}
+
+impl<R: Read + Seek> Iterator for Batches<'_, R> {
+    type Item = io::Result<Vec<String>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.failed || self.next >= self.permutation.len {
+            return None;
+        }
+        let end = (self.next + self.batch_size).min(self.permutation.len);
+        let batch = self.reader.read_batch(&self.permutation, self.next..end);
+        self.next = end;
+        self.failed = batch.is_err();
+        Some(batch)
+    }
+}
+
+impl<R: Read + Seek> FusedIterator for Batches<'_, R> {}
+
+/// Like [`Batches`], but owning the reader and reading the next batch on a thread of its own
+/// while the current one is being processed, see `EasyReader::into_batches()`.
+pub struct IntoBatches<R> {
+    receiver: Receiver<io::Result<Vec<String>>>,
+    worker: JoinHandle<EasyReader<R>>,
+}
+
+impl<R> IntoBatches<R> {
+    /// Stops reading the batches and gives the reader back, e.g. for the next epoch.
+    pub fn into_inner(self) -> EasyReader<R> {
+        let IntoBatches { receiver, worker } = self;
+        // The worker stops as soon as it can't send its next batch
+        drop(receiver);
+        worker
+            .join()
+            .unwrap_or_else(|err| panic::resume_unwind(err))
+    }
+}
+
+impl<R> Iterator for IntoBatches<R> {
+    type Item = io::Result<Vec<String>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.receiver.recv().ok()
+    }
+}
+
+impl<R> FusedIterator for IntoBatches<R> {}
+
+// A permutation of 0..len given by a seed, computed number by number: a Feistel network over
+// the smallest power of 4 covering len, walking its cycles until a number below len is reached
+struct Permutation {
+    len: u64,
+    half_bits: u32,
+    keys: [u64; FEISTEL_ROUNDS],
+    // The numbers of the lines permuted, if some lines are ignored
+    kept: Option<Vec<u64>>,
+}
+
+impl Permutation {
+    fn new(len: u64, seed: u64) -> Self {
+        let bits = 64 - len.saturating_sub(1).leading_zeros();
+        let mut keys = [0; FEISTEL_ROUNDS];
+        let mut state = seed;
+        for key in keys.iter_mut() {
+            state = state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+            *key = mix(state);
+        }
+        Permutation {
+            len,
+            half_bits: bits.div_ceil(2).max(1),
+            keys,
+            kept: None,
+        }
+    }
+
+    // Permutes the lines `kept` only
+    fn of_lines(kept: Vec<u64>, seed: u64) -> Self {
+        let len = kept.len() as u64;
+        Permutation {
+            kept: Some(kept),
+            ..Permutation::new(len, seed)
+        }
+    }
+
+    // The number of the line at `i` in the permutation
+    fn line(&self, i: u64) -> u64 {
+        let i = self.get(i);
+        self.kept.as_ref().map_or(i, |kept| kept[i as usize])
+    }
+
+    fn get(&self, i: u64) -> u64 {
+        let mut i = self.feistel(i);
+        while i >= self.len {
+            i = self.feistel(i);
+        }
+        i
+    }
+
+    fn feistel(&self, i: u64) -> u64 {
+        let mask = (1 << self.half_bits) - 1;
+        let (mut left, mut right) = (i >> self.half_bits, i & mask);
+        for key in self.keys.iter() {
+            let round = (left ^ mix(right ^ key)) & mask;
+            left = right;
+            right = round;
+        }
+        (left << self.half_bits) | right
+    }
+}
+
+// The finalizer of SplitMix64
+fn mix(mut x: u64) -> u64 {
+    x = (x ^ (x >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    x = (x ^ (x >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    x ^ (x >> 31)
+}
+
+impl<R: Read + Seek> EasyReader<R> {
+    /// Iterates over all the lines of the file, each exactly once, in an order given by `seed`
+    /// (e.g. a new one for each training epoch): unlike `random_line()`, no line is drawn
+    /// twice before all of them are. The reader is indexed first if it isn't.
+    ///
+    /// The order is computed line by line, without shuffling a list of the lines in memory.
+    /// The lines ignored (see `comment_prefix()` and `skip_blank_lines()`) are left out, the
+    /// file being read once to list the others.
+    pub fn shuffled_lines(&mut self, seed: u64) -> io::Result<ShuffledLines<'_, R>> {
+        let permutation = self.permutation(seed)?;
+        Ok(ShuffledLines {
+            reader: self,
+            permutation,
+            next: 0,
+            failed: false,
+        })
+    }
+
+    /// Iterates over all the lines of the file, each exactly once, in batches of `batch_size`
+    /// lines (the last one may be smaller) shuffled like `shuffled_lines()` does with `seed`:
+    /// e.g. the mini-batches of a training epoch. The lines of a batch are read in the order of
+    /// the file, those close to each other at once. The reader is indexed first if it isn't.
+    ///
+    /// Fails with `ErrorKind::InvalidInput` if `batch_size` is 0.
+    pub fn batches(&mut self, batch_size: usize, seed: u64) -> io::Result<Batches<'_, R>> {
+        if batch_size == 0 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "The batch size can't be 0",
+            ));
+        }
+        let permutation = self.permutation(seed)?;
+        Ok(Batches {
+            reader: self,
+            permutation,
+            batch_size: batch_size as u64,
+            next: 0,
+            failed: false,
+        })
+    }
+
+    // The permutation of the lines of the file given by `seed`, the ignored lines left out,
+    // indexing the reader first if it isn't
+    fn permutation(&mut self, seed: u64) -> io::Result<Permutation> {
+        if self.index().is_none() {
+            self.build_index()?;
+        }
+        if self.ignores_lines() {
+            return Ok(Permutation::of_lines(self.kept_lines()?, seed));
+        }
+        let lines = self
+            .index()
+            .map_or(0, |line_starts| line_starts.len() as u64);
+        Ok(Permutation::new(lines, seed))
+    }
+
+    // The numbers of the lines which aren't ignored, read in blocks from the BOF
+    pub(crate) fn kept_lines(&mut self) -> io::Result<Vec<u64>> {
+        let (mut kept, mut number) = (Vec::new(), 0);
+        let (mut lines, mut offsets) = (Vec::new(), Vec::new());
+        // From the BOF, then after the terminator of the last line read
+        let (mut pos, mut skip_terminator) = (0, false);
+        loop {
+            self.lines_from(
+                pos,
+                skip_terminator,
+                SCAN_BLOCK_LINES,
+                &mut lines,
+                &mut offsets,
+            )?;
+            match offsets.last() {
+                Some(&(_, end)) => (pos, skip_terminator) = (end, true),
+                None => return Ok(kept),
+            }
+            for line in lines.drain(..) {
+                if !self.is_ignored(line.as_bytes()) {
+                    kept.push(number);
+                }
+                number += 1;
+            }
+            offsets.clear();
+        }
+    }
+
+    // The lines `numbers` of the permutation, read in the order of the file
+    fn read_batch(
+        &mut self,
+        permutation: &Permutation,
+        numbers: Range<u64>,
+    ) -> io::Result<Vec<String>> {
+        let mut spans = Vec::with_capacity((numbers.end - numbers.start) as usize);
+        if let Some(line_starts) = self.index() {
+            for (i, number) in numbers.enumerate() {
+                let line = permutation.line(number) as usize;
+                if let Some(start) = line_starts.get(line) {
+                    let next = line_starts.get(line + 1).unwrap_or(self.file_size);
+                    spans.push((start, next, i));
+                }
+            }
+        }
+        spans.sort_unstable();
+        let offsets: Vec<_> = spans
+            .iter()
+            .map(|&(start, next, _)| (start, next))
+            .collect();
+        let lines = self.read_spans(&offsets, true)?;
+
+        // Back to the order of the permutation
+        let mut batch: Vec<_> = spans.iter().map(|&(_, _, i)| i).zip(lines).collect();
+        batch.sort_unstable_by_key(|&(i, _)| i);
+        Ok(batch.into_iter().map(|(_, line)| line).collect())
+    }
+}
+
+impl<R: Read + Seek + Send + 'static> EasyReader<R> {
+    /// Like `batches()`, but consumes the reader to read the next batch on another thread
+    /// while the current one is being processed, like a data loader prefetching the next
+    /// mini-batch. `IntoBatches::into_inner()` gives the reader back.
+    pub fn into_batches(mut self, batch_size: usize, seed: u64) -> io::Result<IntoBatches<R>> {
+        // The errors of the arguments and of the index build are returned right away
+        self.batches(batch_size, seed)?;
+        // A batch waits in the channel while the next one is being read
+        let (sender, receiver) = mpsc::sync_channel(1);
+        let worker = thread::spawn(move || {
+            if let Ok(batches) = self.batches(batch_size, seed) {
+                for batch in batches {
+                    if sender.send(batch).is_err() {
+                        break;
+                    }
+                }
+            }
+            self
+        });
+        Ok(IntoBatches { receiver, worker })
+    }
+}
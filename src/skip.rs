@@ -0,0 +1,159 @@
+use crate::{EasyReader, ReadMode, SCAN_BLOCK_SIZE};
+use std::io::{self, prelude::*};
+
+impl<R: Read + Seek> EasyReader<R> {
+    /// Moves `lines` lines forward (backward if negative) in one go and returns the line
+    /// reached: `skip_lines(1)` is like `next_line()` and `skip_lines(-1)` like `prev_line()`.
+    ///
+    /// The lines skipped aren't read: the target is found in the index if there's one,
+    /// otherwise by counting the line terminators block by block. If there aren't that many
+    /// lines in that direction, the cursor doesn't move and `None` is returned.
+    ///
+    /// The lines ignored (see `comment_prefix()` and `skip_blank_lines()`) don't count: then
+    /// the lines are moved through one by one.
+    pub fn skip_lines(&mut self, lines: i64) -> io::Result<Option<String>> {
+        if lines == 0 {
+            return self.current_line();
+        }
+        if self.ignores_lines() {
+            return self.skip_unignored(lines);
+        }
+        if self.pending_offset.is_some() {
+            let mode = if lines > 0 {
+                ReadMode::Next
+            } else {
+                ReadMode::Prev
+            };
+            self.resolve_offset(&mode)?;
+        }
+
+        let line = if let Some(line_starts) = self.index() {
+            let current = match self.index_line() {
+                Some(line) => line as i64,
+                None if self.at_bof => -1,
+                // At the EOF
+                None => line_starts.len() as i64,
+            };
+            match current.checked_add(lines) {
+                Some(line) if line >= 0 => self.index_entry(line as u64)?,
+                _ => None,
+            }
+        } else {
+            let start = if lines > 0 {
+                self.skip_forward(lines as u64)?
+            } else {
+                self.skip_backward(lines.unsigned_abs())?
+            };
+            match start {
+                Some(start) => {
+                    self.current_start_line_offset = start;
+                    Some((start, self.find_end_line()?))
+                }
+                None => None,
+            }
+        };
+
+        match line {
+            Some((start, end)) => {
+                self.set_current_line(start, end);
+                self.current_line()
+            }
+            None => Ok(None),
+        }
+    }
+
+    // Moves `lines` lines like skip_lines(), counting only the lines which aren't ignored
+    fn skip_unignored(&mut self, lines: i64) -> io::Result<Option<String>> {
+        let (cursor, pending_offset) = (self.save_cursor(), self.pending_offset);
+        let mode = if lines > 0 {
+            ReadMode::Next
+        } else {
+            ReadMode::Prev
+        };
+        let moved = (|| -> io::Result<bool> {
+            for _ in 0..lines.unsigned_abs() {
+                if !self.navigate(mode.clone())? {
+                    return Ok(false);
+                }
+            }
+            Ok(true)
+        })();
+        if !matches!(moved, Ok(true)) {
+            self.restore_cursor(cursor);
+            self.pending_offset = pending_offset;
+        }
+        match moved? {
+            true => self.current_line(),
+            false => Ok(None),
+        }
+    }
+
+    // The start of the `lines`-th line after the current one
+    fn skip_forward(&mut self, lines: u64) -> io::Result<Option<u64>> {
+        // An empty view (or file) holds no line
+        if self.file_size == 0 {
+            return Ok(None);
+        }
+        // From the BOF, the first line is the one starting there
+        let (mut pos, mut left) = if self.at_bof {
+            (0, lines - 1)
+        } else {
+            (self.current_end_line_offset, lines)
+        };
+        if left == 0 {
+            return Ok(Some(0));
+        }
+        while pos < self.file_size {
+            let len = (self.file_size - pos).min(SCAN_BLOCK_SIZE as u64) as usize;
+            let block = self.read_bytes(pos, len)?;
+            for i in 0..len {
+                if self.ends_delimiter(&block, pos, i)? {
+                    left -= 1;
+                    if left == 0 {
+                        let line_start = pos + i as u64 + 1;
+                        // A trailing line terminator doesn't open a new line
+                        return Ok(Some(line_start).filter(|&s| s < self.file_size));
+                    }
+                }
+            }
+            pos += len as u64;
+        }
+        Ok(None)
+    }
+
+    // The start of the `lines`-th line before the current one
+    pub(crate) fn skip_backward(&mut self, lines: u64) -> io::Result<Option<u64>> {
+        if self.at_bof || self.file_size == 0 {
+            return Ok(None);
+        }
+
+        // The line `lines` lines before starts after the terminator of the one before it, the
+        // terminator preceding the cursor being the first one met. From the EOF, the last line
+        // is the first one before the cursor (and a trailing terminator doesn't count).
+        let mut pos = self.current_start_line_offset;
+        let mut left = lines + 1;
+        if pos == self.file_size {
+            left = lines;
+            let last = self.read_bytes(pos - 1, 1)?;
+            if self.ends_delimiter(&last, pos - 1, 0)? {
+                pos -= self.delimiter.len() as u64;
+            }
+        }
+        while pos > 0 {
+            let len = pos.min(SCAN_BLOCK_SIZE as u64);
+            let from = pos - len;
+            let block = self.read_bytes(from, len as usize)?;
+            for i in (0..len as usize).rev() {
+                if self.ends_delimiter(&block, from, i)? {
+                    left -= 1;
+                    if left == 0 {
+                        return Ok(Some(from + i as u64 + 1));
+                    }
+                }
+            }
+            pos = from;
+        }
+        // Only the first line is left, it has no terminator before it
+        Ok(Some(0).filter(|_| left == 1))
+    }
+}
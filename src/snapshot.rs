@@ -0,0 +1,114 @@
+use crate::EasyReader;
+use std::{fs::File, io, path::Path};
+
+impl EasyReader<File> {
+    /// Opens a point-in-time copy of the file at `path`, cloned with a reflink.
+    ///
+    /// Reflinks (Btrfs, XFS, APFS...) are copy-on-write: cloning is cheap whatever the file size
+    /// and the reader keeps seeing the same content while the original file is appended to or
+    /// rewritten. The clone is created next to the file and unlinked right away, so it never
+    /// outlives the reader.
+    ///
+    /// Fails with `ErrorKind::Unsupported` on filesystems (or platforms) without reflinks.
+    pub fn open_snapshot<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        EasyReader::new(reflink::clone(path.as_ref())?)
+    }
+}
+
+#[cfg(any(
+    target_os = "linux",
+    target_os = "android",
+    target_os = "macos",
+    target_os = "ios"
+))]
+mod reflink {
+    use std::{
+        fs::{self, File},
+        io::{self, Error, ErrorKind},
+        path::{Path, PathBuf},
+        process,
+        sync::atomic::{AtomicUsize, Ordering},
+    };
+
+    static CLONES_COUNT: AtomicUsize = AtomicUsize::new(0);
+
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    pub fn clone(path: &Path) -> io::Result<File> {
+        use std::os::unix::io::AsRawFd;
+
+        let source = File::open(path)?;
+        let clone_path = clone_path(path);
+        let clone = fs::OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create_new(true)
+            .open(&clone_path)?;
+        let _ = fs::remove_file(&clone_path);
+
+        if unsafe { libc::ioctl(clone.as_raw_fd(), libc::FICLONE, source.as_raw_fd()) } == -1 {
+            return Err(clone_error(Error::last_os_error()));
+        }
+        Ok(clone)
+    }
+
+    #[cfg(any(target_os = "macos", target_os = "ios"))]
+    pub fn clone(path: &Path) -> io::Result<File> {
+        use std::{ffi::CString, os::unix::ffi::OsStrExt};
+
+        let to_c_string = |path: &Path| {
+            CString::new(path.as_os_str().as_bytes())
+                .map_err(|err| Error::new(ErrorKind::InvalidInput, err))
+        };
+        let clone_path = clone_path(path);
+        let (source, destination) = (to_c_string(path)?, to_c_string(&clone_path)?);
+
+        if unsafe { libc::clonefile(source.as_ptr(), destination.as_ptr(), 0) } == -1 {
+            return Err(clone_error(Error::last_os_error()));
+        }
+        let clone = File::open(&clone_path);
+        let _ = fs::remove_file(&clone_path);
+        clone
+    }
+
+    // Reflinks only work within a filesystem, the clone is placed next to the original file
+    fn clone_path(path: &Path) -> PathBuf {
+        let name = path.file_name().unwrap_or_default().to_string_lossy();
+        path.with_file_name(format!(
+            ".{}.easy_reader-snapshot-{}-{}",
+            name,
+            process::id(),
+            CLONES_COUNT.fetch_add(1, Ordering::Relaxed)
+        ))
+    }
+
+    fn clone_error(err: Error) -> Error {
+        match err.raw_os_error() {
+            Some(libc::EOPNOTSUPP | libc::EXDEV | libc::EINVAL | libc::ENOTTY) => Error::new(
+                ErrorKind::Unsupported,
+                "The filesystem doesn't support reflinks",
+            ),
+            _ => err,
+        }
+    }
+}
+
+#[cfg(not(any(
+    target_os = "linux",
+    target_os = "android",
+    target_os = "macos",
+    target_os = "ios"
+)))]
+mod reflink {
+    use std::{
+        fs::File,
+        io::{self, Error, ErrorKind},
+        path::Path,
+    };
+
+    pub fn clone(_path: &Path) -> io::Result<File> {
+        Err(Error::new(
+            ErrorKind::Unsupported,
+            "Reflinks aren't supported on this platform",
+        ))
+    }
+}
@@ -0,0 +1,141 @@
+use crate::{EasyReader, SCAN_BLOCK_SIZE};
+use std::{
+    io::{self, prelude::*, Error, ErrorKind},
+    sync::Arc,
+};
+
+// The start offsets of one line every `every` lines
+pub(crate) struct SparseIndex {
+    every: usize,
+    starts: Vec<u64>,
+    lines: u64,
+}
+
+impl<R: Read + Seek> EasyReader<R> {
+    /// Builds a sparse index, locating only one line every `lines` lines: the lines in between
+    /// are found by scanning from the closest indexed one.
+    ///
+    /// Sequential reads don't use it, but line numbers (`offset_of()`, `position_of()`,
+    /// `goto_line_approx()`...) are resolved without scanning the whole file and random lines
+    /// are drawn with a perfect distribution, for a fraction of the memory of a full index.
+    /// It replaces the full index, if any.
+    pub fn build_index_every(&mut self, lines: usize) -> io::Result<&mut Self> {
+        if lines == 0 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "The lines between sparse index entries can't be 0",
+            ));
+        }
+        self.index_sparse(lines, None)?;
+        Ok(self)
+    }
+
+    // Builds the sparse index, giving up (and leaving the reader unindexed) past `max_entries`
+    pub(crate) fn index_sparse(
+        &mut self,
+        every: usize,
+        max_entries: Option<usize>,
+    ) -> io::Result<bool> {
+        self.drop_index();
+        // A cached estimate would be less accurate than the index
+        self.line_count_estimate = None;
+        let sparse = SparseIndex {
+            every,
+            starts: vec![0],
+            // An empty file has no first line
+            lines: u64::from(self.file_size > 0),
+        };
+        match self.scan_sparse(sparse, 0, max_entries)? {
+            Some(sparse) => {
+                self.sparse_index = Some(Arc::new(sparse));
+                self.fingerprint_index()?;
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+
+    // Indexes the lines appended since the sparse index was built
+    pub(crate) fn extend_sparse_index(&mut self) -> io::Result<()> {
+        if let Some(sparse) = self.sparse_index.take() {
+            let sparse = Arc::try_unwrap(sparse).unwrap_or_else(|shared| SparseIndex {
+                starts: shared.starts.clone(),
+                ..*shared
+            });
+            // Scanning again from the last indexed line, the last line may have been incomplete
+            let last_entry = sparse.starts.len() - 1;
+            let sparse = SparseIndex {
+                lines: (last_entry * sparse.every) as u64 + 1,
+                ..sparse
+            };
+            let from = sparse.starts[last_entry];
+            self.sparse_index = self.scan_sparse(sparse, from, None)?.map(Arc::new);
+        }
+        Ok(())
+    }
+
+    pub(crate) fn sparse_every(&self) -> Option<usize> {
+        self.sparse_index.as_ref().map(|sparse| sparse.every)
+    }
+
+    // Closest (line, start offset) at or before the `line`-th line
+    pub(crate) fn line_checkpoint(&self, line: u64) -> (u64, u64) {
+        match &self.sparse_index {
+            Some(sparse) => {
+                let last_entry = sparse.starts.len() as u64 - 1;
+                let entry = (line / sparse.every as u64).min(last_entry) as usize;
+                (entry as u64 * sparse.every as u64, sparse.starts[entry])
+            }
+            None => (0, 0),
+        }
+    }
+
+    // Closest (line, start offset) of a line starting at or before `offset`
+    pub(crate) fn offset_checkpoint(&self, offset: u64) -> (u64, u64) {
+        match &self.sparse_index {
+            Some(sparse) => {
+                let entry = sparse
+                    .starts
+                    .partition_point(|&start| start <= offset)
+                    .saturating_sub(1);
+                (entry as u64 * sparse.every as u64, sparse.starts[entry])
+            }
+            None => (0, 0),
+        }
+    }
+
+    // Number of lines, if known from the sparse index
+    pub(crate) fn sparse_lines(&self) -> Option<u64> {
+        self.sparse_index.as_ref().map(|sparse| sparse.lines)
+    }
+
+    // Counts the lines starting after `from` (the start of the last line counted so far)
+    fn scan_sparse(
+        &mut self,
+        mut sparse: SparseIndex,
+        from: u64,
+        max_entries: Option<usize>,
+    ) -> io::Result<Option<SparseIndex>> {
+        let mut pos = from;
+        while pos < self.file_size {
+            let len = (self.file_size - pos).min(SCAN_BLOCK_SIZE as u64) as usize;
+            let block = self.read_bytes(pos, len)?;
+            for i in 0..len {
+                let line_start = pos + i as u64 + 1;
+                // A trailing line terminator doesn't open a new line
+                if line_start == self.file_size || !self.ends_delimiter(&block, pos, i)? {
+                    continue;
+                }
+                if sparse.lines % sparse.every as u64 == 0 {
+                    if Some(sparse.starts.len()) == max_entries {
+                        return Ok(None);
+                    }
+                    sparse.starts.push(line_start);
+                }
+                sparse.lines += 1;
+            }
+            pos += len as u64;
+        }
+        Ok(Some(sparse))
+    }
+}
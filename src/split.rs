@@ -0,0 +1,133 @@
+use crate::{shared::read_exact_at, EasyReader, ReadAt};
+use std::{
+    fs::File,
+    io::{self, prelude::*, Error, ErrorKind, SeekFrom},
+    ops::Range,
+    sync::Arc,
+};
+
+/// A line-aligned byte range of a file, as returned by `EasyReader::split_into()`. Reads are
+/// positional on a handle shared by all the partitions of the file, so each partition can be
+/// moved to its own thread.
+pub struct Partition {
+    file: Arc<File>,
+    range: Range<u64>,
+    position: u64,
+}
+
+impl Partition {
+    /// The byte range of the file covered by the partition, e.g. to hand it to another process.
+    pub fn range(&self) -> Range<u64> {
+        self.range.clone()
+    }
+}
+
+impl Read for Partition {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let read = self.read_at(buf, self.position)?;
+        self.position += read as u64;
+        Ok(read)
+    }
+}
+
+impl Seek for Partition {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        let position = match pos {
+            SeekFrom::Start(offset) => Some(offset),
+            SeekFrom::End(offset) => (self.range.end - self.range.start).checked_add_signed(offset),
+            SeekFrom::Current(offset) => self.position.checked_add_signed(offset),
+        };
+        match position {
+            Some(position) => {
+                self.position = position;
+                Ok(position)
+            }
+            None => Err(Error::new(
+                ErrorKind::InvalidInput,
+                "Invalid seek to a negative or overflowing position",
+            )),
+        }
+    }
+}
+
+impl ReadAt for Partition {
+    fn read_at(&self, buf: &mut [u8], offset: u64) -> io::Result<usize> {
+        let left = (self.range.end - self.range.start).saturating_sub(offset);
+        let len = (buf.len() as u64).min(left) as usize;
+        if len == 0 {
+            return Ok(0);
+        }
+        self.file
+            .read_at(&mut buf[..len], self.range.start + offset)
+    }
+}
+
+impl EasyReader<File> {
+    /// Splits the file into `n` partitions of about the same size, each one starting at the
+    /// start of a line and ending after a line terminator (or at the EOF), returned as readers
+    /// to process them in parallel (on worker threads, or in other processes given the
+    /// `Partition::range()` of each one). Offsets and line numbers are relative to the start of
+    /// the partition.
+    ///
+    /// Lines longer than a partition make for fewer partitions: none of them is empty. The
+    /// cursor doesn't move. Fails with `ErrorKind::InvalidInput` if `n` is 0.
+    pub fn split_into(&self, n: usize) -> io::Result<Vec<EasyReader<Partition>>> {
+        if n == 0 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "At least one partition is needed",
+            ));
+        }
+
+        let mut bounds = vec![0];
+        for i in 1..n as u64 {
+            let target = (self.file_size as u128 * i as u128 / n as u128) as u64;
+            let bound = self.line_start_from(target)?;
+            if bound > *bounds.last().unwrap() && bound < self.file_size {
+                bounds.push(bound);
+            }
+        }
+        bounds.push(self.file_size);
+
+        let file = Arc::new(self.file.try_clone()?);
+        bounds
+            .windows(2)
+            .map(|bounds| {
+                let mut partition = EasyReader::new(Partition {
+                    file: Arc::clone(&file),
+                    range: self.header_size + bounds[0]..self.header_size + bounds[1],
+                    position: 0,
+                })?;
+                partition.chunk_size = self.chunk_size;
+                partition.chunk_cache(self.chunk_cache_capacity());
+                partition.delimiter = self.delimiter.clone();
+                partition.max_line_length = self.max_line_length;
+                #[cfg(feature = "encoding")]
+                {
+                    partition.encoding = self.encoding;
+                }
+                Ok(partition)
+            })
+            .collect()
+    }
+
+    // The start of the first line starting at or after `offset` (the file size if none does)
+    fn line_start_from(&self, offset: u64) -> io::Result<u64> {
+        if offset == 0 {
+            return Ok(0);
+        }
+        let mut chunk = vec![0; self.chunk_size.max(1)];
+        let mut pos = offset - 1;
+        while pos < self.file_size {
+            let len = (self.file_size - pos).min(chunk.len() as u64) as usize;
+            read_exact_at(&self.file, &mut chunk[..len], self.header_size + pos)?;
+            for i in 0..len {
+                if self.ends_delimiter_at(&chunk[..len], pos, i)? {
+                    return Ok(pos + i as u64 + 1);
+                }
+            }
+            pos += len as u64;
+        }
+        Ok(self.file_size)
+    }
+}
@@ -0,0 +1,167 @@
+use std::{
+    env,
+    fs::{self, File, OpenOptions},
+    io::{self, prelude::*, Error, ErrorKind, SeekFrom},
+    path::PathBuf,
+    process,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+const SPOOL_BLOCK_SIZE: usize = 64 * 1024;
+
+static SPOOLS_COUNT: AtomicUsize = AtomicUsize::new(0);
+
+enum Storage {
+    Memory(Vec<u8>),
+    // The path is only kept where the file can't be unlinked while open
+    File(File, Option<PathBuf>),
+}
+
+/// Makes a non-seekable source (stdin, a pipe, a socket...) seekable by copying what's read
+/// from it into memory or into a temporary file, so it can be navigated by an `EasyReader`:
+///
+/// ```no_run
+/// use easy_reader::{EasyReader, Spooled};
+/// use std::io;
+///
+/// fn last_line() -> io::Result<Option<String>> {
+///     let mut reader = EasyReader::new(Spooled::in_temp_file(io::stdin())?)?;
+///     reader.eof();
+///     reader.prev_line()
+/// }
+/// ```
+///
+/// The source is consumed as far as needed: seeking from the end (which `EasyReader::new()`
+/// does to get the size) consumes it entirely.
+pub struct Spooled<R> {
+    source: R,
+    storage: Storage,
+    len: u64,
+    position: u64,
+    exhausted: bool,
+}
+
+impl<R: Read> Spooled<R> {
+    pub fn in_memory(source: R) -> Self {
+        Spooled::with_storage(source, Storage::Memory(Vec::new()))
+    }
+
+    /// Spools into an anonymous file of the system's temporary directory, removed on drop.
+    pub fn in_temp_file(source: R) -> io::Result<Self> {
+        let path = env::temp_dir().join(format!(
+            "easy_reader-spool-{}-{}",
+            process::id(),
+            SPOOLS_COUNT.fetch_add(1, Ordering::Relaxed)
+        ));
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create_new(true)
+            .open(&path)?;
+
+        #[cfg(unix)]
+        let storage = {
+            fs::remove_file(&path)?;
+            Storage::File(file, None)
+        };
+        #[cfg(not(unix))]
+        let storage = Storage::File(file, Some(path));
+
+        Ok(Spooled::with_storage(source, storage))
+    }
+
+    /// Number of bytes consumed from the source so far.
+    pub fn spooled_len(&self) -> u64 {
+        self.len
+    }
+
+    fn with_storage(source: R, storage: Storage) -> Self {
+        Spooled {
+            source,
+            storage,
+            len: 0,
+            position: 0,
+            exhausted: false,
+        }
+    }
+
+    // Consumes the source up to `offset`, or entirely with `None`
+    fn spool_to(&mut self, offset: Option<u64>) -> io::Result<()> {
+        let mut block = vec![0; SPOOL_BLOCK_SIZE];
+        while !self.exhausted && offset.is_none_or(|offset| self.len < offset) {
+            let read = match self.source.read(&mut block) {
+                Ok(0) => {
+                    self.exhausted = true;
+                    break;
+                }
+                Ok(read) => read,
+                Err(err) if err.kind() == ErrorKind::Interrupted => continue,
+                Err(err) => return Err(err),
+            };
+            match &mut self.storage {
+                Storage::Memory(bytes) => bytes.extend_from_slice(&block[..read]),
+                Storage::File(file, _) => {
+                    file.seek(SeekFrom::Start(self.len))?;
+                    file.write_all(&block[..read])?;
+                }
+            }
+            self.len += read as u64;
+        }
+        Ok(())
+    }
+}
+
+impl<R: Read> Read for Spooled<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.spool_to(Some(self.position + buf.len() as u64))?;
+        if self.position >= self.len {
+            return Ok(0);
+        }
+
+        let len = (self.len - self.position).min(buf.len() as u64) as usize;
+        let read = match &mut self.storage {
+            Storage::Memory(bytes) => {
+                let start = self.position as usize;
+                buf[..len].copy_from_slice(&bytes[start..start + len]);
+                len
+            }
+            Storage::File(file, _) => {
+                file.seek(SeekFrom::Start(self.position))?;
+                file.read(&mut buf[..len])?
+            }
+        };
+        self.position += read as u64;
+        Ok(read)
+    }
+}
+
+impl<R: Read> Seek for Spooled<R> {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        let position = match pos {
+            SeekFrom::Start(offset) => Some(offset),
+            SeekFrom::End(offset) => {
+                self.spool_to(None)?;
+                self.len.checked_add_signed(offset)
+            }
+            SeekFrom::Current(offset) => self.position.checked_add_signed(offset),
+        };
+        match position {
+            Some(position) => {
+                self.position = position;
+                Ok(position)
+            }
+            None => Err(Error::new(
+                ErrorKind::InvalidInput,
+                "Invalid seek to a negative or overflowing position",
+            )),
+        }
+    }
+}
+
+impl<R> Drop for Spooled<R> {
+    fn drop(&mut self) {
+        if let Storage::File(_, Some(path)) = &self.storage {
+            let _ = fs::remove_file(path);
+        }
+    }
+}
@@ -0,0 +1,68 @@
+use crate::{EasyReader, ReaderError};
+use std::io::{self, prelude::*};
+
+/// What to do when the index is found out of date, see `EasyReader::stale_index_policy()`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StaleIndexPolicy {
+    /// Rebuilds the index (full or sparse) and starts over from the BOF.
+    Rebuild,
+    /// Drops the index and starts over from the BOF, the lines being located by scanning.
+    Drop,
+    /// Drops the index like `Drop` and fails with a [`ReaderError::IndexStale`] error (once).
+    Error,
+}
+
+impl<R: Read + Seek> EasyReader<R> {
+    /// Tells whether the file changed since the index (full or sparse) was built or loaded:
+    /// it's shorter or its first and last bytes differ. The data appended since doesn't make
+    /// it stale, `refresh()` indexes it.
+    ///
+    /// Always false without index.
+    pub fn is_index_stale(&mut self) -> io::Result<bool> {
+        let fingerprint = match self.index_fingerprint {
+            Some(fingerprint) => fingerprint,
+            None => return Ok(false),
+        };
+        let file_size = self.seek_file_size()?;
+        Ok(file_size < self.file_size || self.fingerprint()? != fingerprint)
+    }
+
+    /// Checks that the index still matches the file before every read, applying `policy` as
+    /// soon as it doesn't.
+    ///
+    /// The check reads a few KiB at both ends of the file, on top of the line itself.
+    pub fn stale_index_policy(&mut self, policy: StaleIndexPolicy) -> &mut Self {
+        self.stale_index_policy = Some(policy);
+        self
+    }
+
+    pub(crate) fn check_index(&mut self) -> io::Result<()> {
+        if !self.is_index_stale()? {
+            return Ok(());
+        }
+
+        let file_size = self.seek_file_size()?;
+        match self.stale_index_policy.unwrap_or(StaleIndexPolicy::Rebuild) {
+            StaleIndexPolicy::Rebuild => self.start_over(file_size),
+            StaleIndexPolicy::Drop => {
+                self.drop_index();
+                self.start_over(file_size)
+            }
+            StaleIndexPolicy::Error => {
+                self.drop_index();
+                self.start_over(file_size)?;
+                Err(ReaderError::IndexStale.into())
+            }
+        }
+    }
+
+    // Remembers what the file looks like, for the index to be checked against it later on
+    pub(crate) fn fingerprint_index(&mut self) -> io::Result<()> {
+        self.index_fingerprint = if self.indexed || self.sparse_index.is_some() {
+            Some(self.fingerprint()?)
+        } else {
+            None
+        };
+        Ok(())
+    }
+}
@@ -0,0 +1,34 @@
+use crate::EasyReader;
+use std::io::prelude::*;
+
+/// What the reader did to the file so far, see `EasyReader::stats()`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ReaderStats {
+    /// The seeks in the file (to read or to get its size).
+    pub seeks: u64,
+    /// The calls to `read()` on the file.
+    pub reads: u64,
+    /// The bytes read from the file.
+    pub bytes_read: u64,
+    /// The blocks found in the chunk cache (see `EasyReader::chunk_cache()`).
+    pub cache_hits: u64,
+    /// The blocks read from the file into the chunk cache.
+    pub cache_misses: u64,
+    /// The lines read and returned.
+    pub lines_decoded: u64,
+}
+
+impl<R: Read + Seek> EasyReader<R> {
+    /// The I/O of the reader since it was created (or since `reset_stats()`): e.g. to compare
+    /// the reads of a `chunk_size()` to another, or with and without an index. The reads from
+    /// `&self` (`get_line()`, `par_lines()`...) aren't counted.
+    pub fn stats(&self) -> ReaderStats {
+        self.stats
+    }
+
+    /// Sets all the counters of `stats()` back to 0.
+    pub fn reset_stats(&mut self) -> &mut Self {
+        self.stats = ReaderStats::default();
+        self
+    }
+}
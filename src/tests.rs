@@ -255,6 +255,7 @@ fn test_iterations() {
     );
 }
 
+#[cfg(feature = "index")]
 #[test]
 fn test_indexed() {
     let file = File::open("resources/test-file-lf").unwrap();
@@ -475,3 +476,4511 @@ fn test_file_with_blank_line_at_the_beginning() {
         "The file should only have two lines"
     );
 }
+
+#[test]
+fn test_viewport() {
+    let file = File::open("resources/test-file-lf").unwrap();
+    let reader = EasyReader::new(file).unwrap();
+    let mut viewport = Viewport::new(reader, 2).unwrap();
+
+    assert_eq!(viewport.lines(), ["AAAA AAAA", "B B BB BBB"]);
+
+    assert_eq!(viewport.scroll_down(2).unwrap(), 2);
+    assert_eq!(
+        viewport.lines(),
+        ["CCCC  CCCCC", "DDDD  DDDDD DD DDD DDD DD"]
+    );
+
+    assert_eq!(
+        viewport.scroll_down(5).unwrap(),
+        1,
+        "Only one line is left below the viewport"
+    );
+    assert_eq!(
+        viewport.lines(),
+        ["DDDD  DDDDD DD DDD DDD DD", "EEEE  EEEEE  EEEE  EEEEE"]
+    );
+
+    assert_eq!(viewport.scroll_up(1).unwrap(), 1);
+    assert_eq!(
+        viewport.lines(),
+        ["CCCC  CCCCC", "DDDD  DDDDD DD DDD DDD DD"]
+    );
+
+    viewport.resize(3).unwrap();
+    assert_eq!(
+        viewport.lines(),
+        [
+            "CCCC  CCCCC",
+            "DDDD  DDDDD DD DDD DDD DD",
+            "EEEE  EEEEE  EEEE  EEEEE"
+        ]
+    );
+
+    viewport.resize(4).unwrap();
+    assert_eq!(
+        viewport.lines()[0],
+        "B B BB BBB",
+        "At the EOF the viewport should grow upwards"
+    );
+
+    viewport.resize(1).unwrap();
+    assert_eq!(viewport.lines(), ["B B BB BBB"]);
+
+    assert_eq!(viewport.scroll_up(10).unwrap(), 1);
+    assert_eq!(viewport.lines(), ["AAAA AAAA"]);
+}
+
+#[test]
+fn test_pager() {
+    let file = File::open("resources/test-file-lf").unwrap();
+    let mut pager = Pager::new(EasyReader::new(file).unwrap(), 2).unwrap();
+    let page = |first_line, lines: &[&str]| {
+        Some(Page {
+            first_line,
+            lines: lines.iter().map(|line| line.to_string()).collect(),
+        })
+    };
+
+    assert!(pager.page_up().unwrap().is_none());
+    assert_eq!(
+        pager.page_down().unwrap(),
+        page(0, &["AAAA AAAA", "B B BB BBB"])
+    );
+    assert_eq!(
+        pager.page_down().unwrap(),
+        page(2, &["CCCC  CCCCC", "DDDD  DDDDD DD DDD DDD DD"])
+    );
+    assert_eq!(
+        pager.page_down().unwrap(),
+        page(4, &["EEEE  EEEEE  EEEE  EEEEE"]),
+        "The last page is shorter"
+    );
+    assert!(pager.page_down().unwrap().is_none());
+    assert_eq!(
+        pager.page_up().unwrap(),
+        page(2, &["CCCC  CCCCC", "DDDD  DDDDD DD DDD DDD DD"])
+    );
+    assert_eq!(
+        pager.page_up().unwrap(),
+        page(0, &["AAAA AAAA", "B B BB BBB"])
+    );
+    assert!(pager.page_up().unwrap().is_none());
+    assert_eq!(
+        pager.page_down().unwrap(),
+        page(2, &["CCCC  CCCCC", "DDDD  DDDDD DD DDD DDD DD"])
+    );
+
+    let file = File::open("resources/test-file-crlf").unwrap();
+    assert_eq!(
+        Pager::new(EasyReader::new(file).unwrap(), 0)
+            .err()
+            .unwrap()
+            .kind(),
+        io::ErrorKind::InvalidInput
+    );
+}
+
+#[cfg(feature = "index")]
+#[test]
+fn test_position_mapping() {
+    for indexed in [false, true] {
+        let file = File::open("resources/test-file-crlf").unwrap();
+        let mut reader = EasyReader::new(file).unwrap();
+        if indexed {
+            reader.build_index().unwrap();
+        }
+
+        assert_eq!(reader.position_of(0).unwrap(), (0, 0));
+        assert_eq!(reader.position_of(3).unwrap(), (0, 3));
+        // "AAAA AAAA\r\n" is 11 bytes long
+        assert_eq!(reader.position_of(11).unwrap(), (1, 0));
+        assert_eq!(reader.position_of(14).unwrap(), (1, 3));
+        assert!(reader.position_of(1000).is_err());
+
+        assert_eq!(reader.offset_of(0, 0).unwrap(), 0);
+        assert_eq!(reader.offset_of(1, 3).unwrap(), 14);
+        assert_eq!(reader.offset_of(4, 24).unwrap(), 87);
+        assert!(
+            reader.offset_of(0, 10).is_err(),
+            "The first line of test-file-crlf is only 9 characters long"
+        );
+        assert!(reader.offset_of(5, 0).is_err());
+
+        let (line, column) = reader.position_of(50).unwrap();
+        assert_eq!(reader.offset_of(line, column).unwrap(), 50);
+
+        // The EOF, after a trailing line terminator or not
+        assert_eq!(reader.position_of(87).unwrap(), (4, 24));
+        for (file, eof) in [("a\nb\n", (2, 0)), ("a\nb", (1, 1)), ("a\r\n", (1, 0))] {
+            let mut reader = EasyReader::new(io::Cursor::new(file.as_bytes().to_vec())).unwrap();
+            #[cfg(feature = "index")]
+            if indexed {
+                reader.build_index().unwrap();
+            }
+            let size = file.len() as u64;
+            assert_eq!(reader.position_of(size).unwrap(), eof, "{:?}", file);
+            assert_eq!(reader.offset_of(eof.0, eof.1).unwrap(), size, "{:?}", file);
+            assert!(reader.offset_of(eof.0 + 1, 0).is_err());
+        }
+    }
+}
+
+#[test]
+fn test_line_at_offset() {
+    #[cfg(feature = "index")]
+    let indexes = [false, true];
+    #[cfg(not(feature = "index"))]
+    let indexes = [false];
+    for &indexed in &indexes {
+        let file = File::open("resources/test-file-crlf").unwrap();
+        let mut reader = EasyReader::new(file).unwrap();
+        #[cfg(feature = "index")]
+        if indexed {
+            reader.build_index().unwrap();
+        }
+
+        assert_eq!(reader.line_at_offset(0).unwrap().unwrap(), "AAAA AAAA");
+        for offset in &[9, 10] {
+            assert_eq!(
+                reader.line_at_offset(*offset).unwrap().unwrap(),
+                "AAAA AAAA",
+                "The terminator should belong to the line it ends (indexed: {})",
+                indexed
+            );
+        }
+        assert_eq!(reader.line_at_offset(14).unwrap().unwrap(), "B B BB BBB");
+        assert_eq!(reader.next_line().unwrap().unwrap(), "CCCC  CCCCC");
+        assert_eq!(
+            reader.line_at_offset(80).unwrap().unwrap().chars().next(),
+            Some('E')
+        );
+        assert!(reader.next_line().unwrap().is_none());
+
+        let cursor = reader.save_cursor();
+        assert!(reader.line_at_offset(1000).unwrap().is_none());
+        assert_eq!(reader.save_cursor(), cursor);
+    }
+}
+
+#[test]
+fn test_current_offsets() {
+    let file = File::open("resources/test-file-crlf").unwrap();
+    let mut reader = EasyReader::new(file).unwrap();
+    assert_eq!(reader.current_offsets(), (0, 0));
+    reader.next_line().unwrap();
+    assert_eq!(reader.current_offsets(), (0, 9));
+    reader.next_line().unwrap();
+    assert_eq!(reader.current_offsets(), (11, 21));
+    reader.eof();
+    assert_eq!(reader.current_offsets(), (87, 87));
+    let line = reader.prev_line().unwrap().unwrap();
+    let (start, end) = reader.current_offsets();
+    assert_eq!((end - start) as usize, line.len());
+}
+
+#[test]
+fn test_set_offset() {
+    #[cfg(feature = "index")]
+    let indexes = [false, true];
+    #[cfg(not(feature = "index"))]
+    let indexes = [false];
+    for &indexed in &indexes {
+        let file = File::open("resources/test-file-crlf").unwrap();
+        let mut reader = EasyReader::new(file).unwrap();
+        #[cfg(feature = "index")]
+        if indexed {
+            reader.build_index().unwrap();
+        }
+
+        reader.set_offset(14);
+        assert_eq!(reader.next_line().unwrap().unwrap(), "B B BB BBB");
+        assert_eq!(reader.next_line().unwrap().unwrap(), "CCCC  CCCCC");
+        reader.set_offset(14);
+        assert_eq!(reader.prev_line().unwrap().unwrap(), "AAAA AAAA");
+        reader.set_offset(14);
+        assert_eq!(reader.current_line().unwrap().unwrap(), "B B BB BBB");
+        reader.set_offset(3);
+        assert_eq!(reader.next_line().unwrap().unwrap(), "AAAA AAAA");
+        reader.set_offset(3);
+        assert!(reader.prev_line().unwrap().is_none());
+        assert_eq!(reader.next_line().unwrap().unwrap(), "AAAA AAAA");
+
+        reader.set_offset(40);
+        let (prev, current, next) = reader.neighbors().unwrap();
+        assert_eq!(prev.unwrap(), "CCCC  CCCCC");
+        assert!(current.starts_with("DDDD"));
+        assert!(next.unwrap().starts_with("EEEE"));
+        assert_eq!(
+            reader.skip_lines(2).unwrap().unwrap(),
+            "EEEE  EEEEE  EEEE  EEEEE"
+        );
+        reader.set_offset(40);
+        assert_eq!(reader.skip_lines(-2).unwrap().unwrap(), "B B BB BBB");
+
+        reader.set_offset(40).bof();
+        assert_eq!(
+            reader.next_line().unwrap().unwrap(),
+            "AAAA AAAA",
+            "Moving the cursor again should cancel the alignment (indexed: {})",
+            indexed
+        );
+        reader.set_offset(1000);
+        assert!(reader.prev_line().unwrap().unwrap().starts_with("EEEE"));
+    }
+}
+
+#[test]
+fn test_position() {
+    let file = File::open("resources/test-file-lf").unwrap();
+    let mut reader = EasyReader::new(file).unwrap();
+    let bof = reader.position();
+    assert_eq!(bof.offset(), 0);
+    reader.next_line().unwrap();
+    reader.next_line().unwrap();
+    let position = reader.position();
+    let next = reader.next_line().unwrap();
+
+    reader.eof();
+    reader.prev_line().unwrap();
+    #[cfg(feature = "rand")]
+    reader.random_line().unwrap();
+    reader.seek_to(position).unwrap();
+    assert_eq!(reader.position(), position);
+    assert_eq!(reader.next_line().unwrap(), next);
+    reader.seek_to(bof).unwrap();
+    assert!(reader.prev_line().unwrap().is_none());
+    assert_eq!(reader.next_line().unwrap().unwrap(), "AAAA AAAA");
+
+    reader.set_offset(12);
+    let unaligned = reader.position();
+    let line = reader.next_line().unwrap();
+    reader.seek_to(unaligned).unwrap();
+    assert_eq!(reader.next_line().unwrap(), line);
+
+    let mut short = EasyReader::new(io::Cursor::new(b"short".to_vec())).unwrap();
+    assert_eq!(
+        short.seek_to(position).err().unwrap().kind(),
+        ErrorKind::InvalidInput
+    );
+}
+
+#[test]
+fn test_line_columns() {
+    let file = File::open("resources/unicode-file").unwrap();
+    let mut reader = EasyReader::new(file).unwrap();
+
+    reader.next_line().unwrap();
+    assert_eq!(reader.current_line_chars(..5).unwrap().unwrap(), "Caffè");
+    assert_eq!(reader.current_line_chars(6..).unwrap().unwrap(), "lungo");
+    assert_eq!(reader.current_line_chars(4..=4).unwrap().unwrap(), "è");
+    assert_eq!(
+        reader.current_line_chars(6..=usize::MAX).unwrap().unwrap(),
+        "lungo"
+    );
+    assert_eq!(
+        reader
+            .current_line_chars((
+                std::ops::Bound::Excluded(usize::MAX),
+                std::ops::Bound::Unbounded
+            ))
+            .unwrap()
+            .unwrap(),
+        ""
+    );
+    assert_eq!(
+        reader.current_line_chars(20..30).unwrap().unwrap(),
+        "",
+        "Columns past the end of the line should yield an empty string"
+    );
+
+    reader.next_line().unwrap();
+    // "e\u{301}" is a single grapheme made of two chars
+    assert_eq!(
+        reader.current_line_chars(7..9).unwrap().unwrap(),
+        "e\u{301}"
+    );
+    #[cfg(feature = "unicode-segmentation")]
+    assert_eq!(
+        reader.current_line_graphemes(6..).unwrap().unwrap(),
+        "e\u{301}té"
+    );
+}
+
+#[test]
+fn test_sections() {
+    let file = File::open("resources/sections-file").unwrap();
+    let mut reader = EasyReader::new(file).unwrap();
+
+    assert_eq!(
+        reader.anchors_by(|line| line.starts_with("== ")).unwrap(),
+        3
+    );
+    assert_eq!(reader.next_section().unwrap().unwrap(), "== Intro");
+    assert_eq!(reader.next_section().unwrap().unwrap(), "== Usage");
+    assert_eq!(reader.next_line().unwrap().unwrap(), "usage text");
+    assert_eq!(reader.prev_section().unwrap().unwrap(), "== Usage");
+    assert_eq!(reader.prev_section().unwrap().unwrap(), "== Intro");
+    assert!(reader.prev_section().unwrap().is_none());
+
+    assert_eq!(reader.goto_section(2).unwrap().unwrap(), "== Notes");
+    assert!(reader.next_section().unwrap().is_none());
+    assert_eq!(reader.next_line().unwrap().unwrap(), "notes");
+    assert!(reader.goto_section(3).unwrap().is_none());
+
+    #[cfg(feature = "regex")]
+    {
+        let pattern = regex::Regex::new(r"^== [IN]").unwrap();
+        assert_eq!(reader.anchors(&pattern).unwrap(), 2);
+        assert_eq!(
+            reader.current_line().unwrap().unwrap(),
+            "notes",
+            "Scanning for anchors shouldn't move the cursor"
+        );
+        assert_eq!(reader.prev_section().unwrap().unwrap(), "== Notes");
+        assert_eq!(reader.prev_section().unwrap().unwrap(), "== Intro");
+    }
+
+    // From an empty first line, which is an anchor
+    let mut reader = EasyReader::new(io::Cursor::new(b"\nfoo\n\nbar\n".to_vec())).unwrap();
+    assert_eq!(reader.anchors_by(str::is_empty).unwrap(), 2);
+    assert_eq!(reader.next_section().unwrap().unwrap(), "");
+    assert_eq!(reader.current_offsets(), (0, 0));
+    assert_eq!(reader.next_section().unwrap().unwrap(), "");
+    assert_eq!(reader.next_line().unwrap().unwrap(), "bar");
+}
+
+#[test]
+fn test_bookmarks() {
+    let path = std::env::temp_dir().join("easy_reader_test_bookmarks");
+
+    let file = File::open("resources/test-file-lf").unwrap();
+    let mut reader = EasyReader::new(file).unwrap();
+
+    reader.next_line().unwrap();
+    reader.bookmark("first");
+    reader.next_line().unwrap();
+    reader.next_line().unwrap();
+    reader.bookmark("with spaces");
+    reader.eof();
+
+    assert_eq!(reader.goto_bookmark("first").unwrap().unwrap(), "AAAA AAAA");
+    assert!(reader.goto_bookmark("missing").unwrap().is_none());
+    reader.bookmark("removed");
+    assert_eq!(
+        reader.bookmarks().collect::<Vec<_>>(),
+        ["first", "removed", "with spaces"]
+    );
+    assert!(reader.remove_bookmark("removed"));
+    assert!(!reader.remove_bookmark("removed"));
+    reader.save_bookmarks(&path).unwrap();
+
+    let file = File::open("resources/test-file-lf").unwrap();
+    let mut reader = EasyReader::new(file).unwrap();
+    assert_eq!(reader.load_bookmarks(&path).unwrap(), 2);
+    assert_eq!(
+        reader.goto_bookmark("with spaces").unwrap().unwrap(),
+        "CCCC  CCCCC"
+    );
+    assert_eq!(
+        reader.next_line().unwrap().unwrap(),
+        "DDDD  DDDDD DD DDD DDD DD"
+    );
+
+    let file = File::open("resources/test-file-crlf").unwrap();
+    let mut reader = EasyReader::new(file).unwrap();
+    let err = reader.load_bookmarks(&path).unwrap_err();
+    assert_eq!(
+        err.kind(),
+        ErrorKind::InvalidData,
+        "Bookmarks saved for test-file-lf shouldn't be loaded for test-file-crlf"
+    );
+
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn test_neighbors() {
+    let file = File::open("resources/test-file-crlf").unwrap();
+    let mut reader = EasyReader::new(file).unwrap();
+
+    reader.next_line().unwrap();
+    assert_eq!(
+        reader.neighbors().unwrap(),
+        (
+            None,
+            "AAAA AAAA".to_string(),
+            Some("B B BB BBB".to_string())
+        )
+    );
+
+    reader.next_line().unwrap();
+    reader.next_line().unwrap();
+    assert_eq!(
+        reader.neighbors().unwrap(),
+        (
+            Some("B B BB BBB".to_string()),
+            "CCCC  CCCCC".to_string(),
+            Some("DDDD  DDDDD DD DDD DDD DD".to_string())
+        )
+    );
+    assert_eq!(
+        reader.current_line().unwrap().unwrap(),
+        "CCCC  CCCCC",
+        "neighbors() shouldn't move the cursor"
+    );
+
+    reader.eof();
+    assert_eq!(
+        reader.neighbors().unwrap(),
+        (
+            Some("DDDD  DDDDD DD DDD DDD DD".to_string()),
+            "EEEE  EEEEE  EEEE  EEEEE".to_string(),
+            None
+        )
+    );
+    assert_eq!(
+        reader.prev_line().unwrap().unwrap(),
+        "EEEE  EEEEE  EEEE  EEEEE"
+    );
+}
+
+#[cfg(feature = "index")]
+#[test]
+fn test_indexed_watcher() {
+    let path = std::env::temp_dir().join("easy_reader_test_indexed_watcher");
+    std::fs::write(&path, "first\r\nsecond\nthi").unwrap();
+
+    let mut watcher = IndexedWatcher::open(&path).unwrap();
+    assert_eq!(
+        watcher.lines_count(),
+        2,
+        "The incomplete last line shouldn't be indexed"
+    );
+    let mut snapshot = watcher.snapshot().unwrap();
+
+    let mut file = std::fs::OpenOptions::new()
+        .append(true)
+        .open(&path)
+        .unwrap();
+    file.write_all(b"rd\nfourth\n").unwrap();
+    assert_eq!(watcher.update().unwrap(), 2);
+    assert_eq!(watcher.update().unwrap(), 0);
+
+    snapshot.eof();
+    assert_eq!(
+        snapshot.prev_line().unwrap().unwrap(),
+        "second",
+        "The snapshot shouldn't see the lines appended after its creation"
+    );
+
+    let mut reader = watcher.snapshot().unwrap();
+    let mut lines = Vec::new();
+    while let Some(line) = reader.next_line().unwrap() {
+        lines.push(line);
+    }
+    assert_eq!(lines, ["first", "second", "third", "fourth"]);
+    assert_eq!(reader.prev_line().unwrap().unwrap(), "third");
+
+    // The snapshots share the index
+    let snapshot = watcher.snapshot().unwrap();
+    assert!(Arc::ptr_eq(&snapshot.line_starts, &reader.line_starts));
+
+    std::fs::write(&path, "").unwrap();
+    let err = watcher.update().unwrap_err();
+    assert_eq!(
+        ReaderError::of(&err),
+        Some(&ReaderError::Truncated {
+            previous_size: 27,
+            size: 0
+        })
+    );
+
+    // Other terminators
+    std::fs::write(&path, "a\0b\0c").unwrap();
+    let mut watcher = IndexedWatcher::open(&path).unwrap();
+    assert_eq!(watcher.lines_count(), 0);
+    watcher.delimiter(b'\0').unwrap();
+    assert_eq!(watcher.lines_count(), 2);
+    let mut file = std::fs::OpenOptions::new()
+        .append(true)
+        .open(&path)
+        .unwrap();
+    file.write_all(b"\0d\0").unwrap();
+    assert_eq!(watcher.update().unwrap(), 2);
+    let mut reader = watcher.snapshot().unwrap();
+    let lines: Vec<String> = reader.iter().collect::<io::Result<_>>().unwrap();
+    assert_eq!(lines, ["a", "b", "c", "d"]);
+
+    std::fs::write(&path, "a\rb\rc").unwrap();
+    let mut watcher = IndexedWatcher::open(&path).unwrap();
+    watcher.delimiter(b'\r').unwrap();
+    assert_eq!(watcher.lines_count(), 2);
+    assert_eq!(watcher.snapshot().unwrap().tail(1).unwrap(), ["b"]);
+
+    std::fs::remove_file(path).unwrap();
+}
+
+#[cfg(feature = "index")]
+#[test]
+fn test_trailing_line_terminator() {
+    let file = File::open("resources/file-with-blank-line-at-the-beginning").unwrap();
+    let mut reader = EasyReader::new(file).unwrap();
+
+    for indexed in [false, true] {
+        if indexed {
+            reader.build_index().unwrap();
+        }
+        reader.bof();
+
+        assert!(
+            reader.next_line().unwrap().unwrap().is_empty(),
+            "The blank line at the BOF should be empty"
+        );
+        assert_eq!(reader.next_line().unwrap().unwrap(), "Blank line above!");
+        assert!(
+            reader.next_line().unwrap().is_none(),
+            "The trailing line terminator shouldn't open a new line"
+        );
+        assert!(reader.prev_line().unwrap().unwrap().is_empty());
+    }
+}
+
+#[cfg(feature = "index")]
+#[test]
+fn test_reader_pool() {
+    let pool = ReaderPool::open("resources/test-file-lf", 2).unwrap();
+    assert_eq!(pool.available(), 2);
+
+    {
+        let mut first = pool.checkout();
+        let mut second = pool.checkout();
+        assert!(pool.try_checkout().is_none(), "The pool should be empty");
+        assert!(
+            Arc::ptr_eq(&first.line_starts, &second.line_starts),
+            "The readers of a pool should share the index"
+        );
+
+        first.eof();
+        assert_eq!(
+            first.prev_line().unwrap().unwrap(),
+            "EEEE  EEEEE  EEEE  EEEEE"
+        );
+        assert_eq!(second.next_line().unwrap().unwrap(), "AAAA AAAA");
+    }
+    assert_eq!(pool.available(), 2);
+
+    std::thread::scope(|scope| {
+        for _ in 0..4 {
+            scope.spawn(|| {
+                let mut reader = pool.checkout();
+                assert_eq!(
+                    reader.next_line().unwrap().unwrap(),
+                    "AAAA AAAA",
+                    "Returned readers should be rewound to the BOF"
+                );
+                reader.next_line().unwrap();
+            });
+        }
+    });
+    assert_eq!(pool.available(), 2);
+
+    // The settings changed by a borrower don't leak into the next checkout
+    let pool = ReaderPool::open("resources/test-file-lf", 1).unwrap();
+    {
+        let mut reader = pool.checkout();
+        reader.view_lines(1..3).unwrap();
+        reader.skip_header_lines(1).unwrap();
+        reader
+            .delimiter(b' ')
+            .comment_prefix("A")
+            .skip_blank_lines(true)
+            .trim(Trim::Both)
+            .max_line_length(1);
+    }
+    let mut reader = pool.checkout();
+    assert!(reader.indexed, "The shared index should be kept");
+    assert_eq!(reader.line_count().unwrap(), 5);
+    assert_eq!(reader.next_line().unwrap().unwrap(), "AAAA AAAA");
+    reader.eof();
+    assert_eq!(
+        reader.prev_line().unwrap().unwrap(),
+        "EEEE  EEEEE  EEEE  EEEEE"
+    );
+}
+
+#[test]
+fn test_fork() {
+    let mut reader = EasyReader::open("resources/test-file-lf").unwrap();
+    #[cfg(feature = "index")]
+    reader.build_index().unwrap();
+    reader.next_line().unwrap();
+    reader.bookmark("first");
+    reader.next_line().unwrap();
+
+    let mut fork = reader.fork().unwrap();
+    #[cfg(feature = "index")]
+    assert!(
+        Arc::ptr_eq(&reader.line_starts, &fork.line_starts),
+        "A fork should share the index"
+    );
+    assert_eq!(fork.position(), reader.position());
+    std::thread::scope(|scope| {
+        scope.spawn(|| {
+            assert_eq!(fork.next_line().unwrap().unwrap(), "CCCC  CCCCC");
+            assert_eq!(fork.goto_bookmark("first").unwrap().unwrap(), "AAAA AAAA");
+        });
+        assert_eq!(reader.prev_line().unwrap().unwrap(), "AAAA AAAA");
+    });
+    assert_eq!(reader.next_line().unwrap().unwrap(), "B B BB BBB");
+
+    let reader = EasyReader::new(File::open("resources/test-file-lf").unwrap()).unwrap();
+    assert_eq!(reader.fork().err().unwrap().kind(), ErrorKind::Unsupported);
+    let mut fork = reader
+        .fork_with(io::Cursor::new(
+            std::fs::read("resources/test-file-lf").unwrap(),
+        ))
+        .unwrap();
+    assert_eq!(fork.next_line().unwrap().unwrap(), "AAAA AAAA");
+    assert!(reader
+        .fork_with(io::Cursor::new(b"short".to_vec()))
+        .is_err());
+}
+
+#[cfg(feature = "index")]
+#[test]
+fn test_memory_budget() {
+    let file = File::open("resources/fatty_lipsum_lf").unwrap();
+    let mut reader = EasyReader::new(file).unwrap();
+    assert_eq!(reader.index_strategy(), IndexStrategy::None);
+
+    reader.with_memory_budget(64 * 1024 * 1024).unwrap();
+    assert_eq!(reader.index_strategy(), IndexStrategy::Full);
+    assert!(reader.next_line().unwrap().is_some());
+
+    reader.with_memory_budget(1024).unwrap();
+    assert!(
+        matches!(reader.index_strategy(), IndexStrategy::Sparse { .. }),
+        "The full index of fatty_lipsum_lf doesn't fit in 1KB"
+    );
+    assert!(reader.next_line().unwrap().is_some());
+
+    reader.with_memory_budget(4).unwrap();
+    assert_eq!(reader.index_strategy(), IndexStrategy::None);
+    assert!(
+        reader.next_line().unwrap().is_some(),
+        "The cursor should be preserved"
+    );
+}
+
+#[test]
+fn test_estimate_line_count() {
+    let file = File::open("resources/test-file-lf").unwrap();
+    let mut reader = EasyReader::new(file).unwrap();
+    let estimate = reader.estimate_line_count().unwrap();
+    assert!(estimate.is_exact(), "Small files should be counted exactly");
+    assert_eq!(estimate.lines, 5);
+
+    let file = File::open("resources/file-with-blank-line-at-the-beginning").unwrap();
+    let mut reader = EasyReader::new(file).unwrap();
+    assert_eq!(reader.estimate_line_count().unwrap().lines, 2);
+
+    let mut content = String::new();
+    for i in 0..100_000 {
+        content.push_str(&"x".repeat(i % 50));
+        content.push('\n');
+    }
+    let mut reader = EasyReader::new(io::Cursor::new(content.into_bytes())).unwrap();
+    let estimate = reader.estimate_line_count().unwrap();
+    assert!(!estimate.is_exact());
+    assert!(estimate.low <= estimate.lines && estimate.lines <= estimate.high);
+    assert!(
+        (95_000..105_000).contains(&estimate.lines),
+        "Estimated {} lines instead of 100000",
+        estimate.lines
+    );
+}
+
+#[test]
+fn test_line_count() {
+    for path in &[
+        "resources/test-file-lf",
+        "resources/test-file-crlf",
+        "resources/file-with-blank-line-at-the-beginning",
+        "resources/one-line-file",
+        "resources/fatty_lipsum_lf",
+    ] {
+        let mut reader = EasyReader::new(File::open(path).unwrap()).unwrap();
+        let lines = reader.iter().count() as u64;
+        reader.bof().next_line().unwrap();
+        let cursor = reader.save_cursor();
+        assert_eq!(reader.line_count().unwrap(), lines, "{}", path);
+        assert_eq!(reader.save_cursor(), cursor);
+        #[cfg(feature = "index")]
+        {
+            reader.build_index().unwrap();
+            assert_eq!(reader.line_count().unwrap(), lines);
+        }
+    }
+
+    let content = "x\n".repeat(100_000) + "last";
+    let mut reader = EasyReader::new(io::Cursor::new(content.into_bytes())).unwrap();
+    assert_eq!(reader.line_count().unwrap(), 100_001);
+}
+
+#[cfg(feature = "index")]
+#[test]
+fn test_goto_line_approx() {
+    let file = File::open("resources/test-file-lf").unwrap();
+    let mut reader = EasyReader::new(file).unwrap();
+    let reached = reader.goto_line_approx(2).unwrap().unwrap();
+    assert_eq!(
+        (reached.line.as_str(), reached.is_exact()),
+        ("CCCC  CCCCC", true),
+        "The lines of small files should be reached exactly"
+    );
+    assert_eq!(
+        reader.next_line().unwrap().unwrap(),
+        "DDDD  DDDDD DD DDD DDD DD"
+    );
+    assert!(reader.goto_line_approx(5).unwrap().is_none());
+
+    reader.build_index().unwrap();
+    let reached = reader.goto_line_approx(0).unwrap().unwrap();
+    assert_eq!(
+        (reached.line.as_str(), reached.is_exact()),
+        ("AAAA AAAA", true)
+    );
+    assert_eq!(reader.next_line().unwrap().unwrap(), "B B BB BBB");
+
+    let mut content = String::new();
+    for i in 0..100_000 {
+        content.push_str(&format!("{} ", i));
+        content.push_str(&"x".repeat(i % 50));
+        content.push('\n');
+    }
+    let mut reader = EasyReader::new(io::Cursor::new(content.into_bytes())).unwrap();
+    let reached = reader.goto_line_approx(50_000).unwrap().unwrap();
+    assert!(
+        !reached.is_exact(),
+        "Past the first MiB the line is only approximate"
+    );
+    let line = reached.line;
+    let line_number: u64 = line.split(' ').next().unwrap().parse().unwrap();
+    assert!(
+        (48_000..52_000).contains(&line_number),
+        "Landed on line {} instead of ~50000",
+        line_number
+    );
+    assert_eq!(
+        reader
+            .next_line()
+            .unwrap()
+            .unwrap()
+            .split(' ')
+            .next()
+            .unwrap(),
+        (line_number + 1).to_string(),
+        "The cursor should land on a whole line"
+    );
+    assert!(!reader.estimate_line_count().unwrap().is_exact());
+    for &line in &[0, 1, 1_000, 20_000] {
+        let reached = reader.goto_line_approx(line).unwrap().unwrap();
+        assert!(
+            reached.line.starts_with(&format!("{} ", line)) && reached.is_exact(),
+            "The lines in the first MiB should be reached exactly"
+        );
+    }
+
+    // Exactly from the closest line of a sparse index
+    reader.build_index_every(1_000).unwrap();
+    for &line in &[50_000, 73_456, 99_999] {
+        let reached = reader.goto_line_approx(line).unwrap().unwrap();
+        assert!(reached.line.starts_with(&format!("{} ", line)) && reached.is_exact());
+    }
+}
+
+#[test]
+fn test_trace_replay() {
+    let file = File::open("resources/test-file-crlf").unwrap();
+    let mut reader = EasyReader::new(file).unwrap();
+
+    reader.record_trace();
+    let mut lines = vec![reader.next_line().unwrap(), reader.next_line().unwrap()];
+    reader.bookmark("second");
+    reader.eof();
+    lines.push(reader.prev_line().unwrap());
+    lines.push(reader.next_line().unwrap());
+    lines.push(reader.goto_bookmark("second").unwrap());
+    #[cfg(feature = "rand")]
+    lines.push(reader.random_line().unwrap());
+    let trace = reader.take_trace();
+    assert!(
+        reader.take_trace().is_empty(),
+        "The recording should be over"
+    );
+
+    let trace: Trace = trace.to_string().parse().unwrap();
+    let file = File::open("resources/test-file-crlf").unwrap();
+    let mut reader = EasyReader::new(file).unwrap();
+    assert_eq!(reader.replay(&trace).unwrap(), lines);
+
+    let file = File::open("resources/test-file-lf").unwrap();
+    let mut reader = EasyReader::new(file).unwrap();
+    assert_eq!(
+        reader.replay(&trace).unwrap_err().kind(),
+        ErrorKind::InvalidData,
+        "The trace of test-file-crlf shouldn't be replayable on test-file-lf"
+    );
+
+    assert!("next 0 0".parse::<Trace>().is_err());
+}
+
+#[test]
+fn test_open_snapshot() {
+    let dir = std::env::temp_dir().join("easy_reader_test_open_snapshot");
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("log");
+    std::fs::write(&path, "first\nsecond").unwrap();
+
+    match EasyReader::open_snapshot(&path) {
+        Ok(mut reader) => {
+            std::fs::write(&path, "rewritten\n").unwrap();
+            assert_eq!(reader.next_line().unwrap().unwrap(), "first");
+            assert_eq!(reader.next_line().unwrap().unwrap(), "second");
+        }
+        Err(err) => assert_eq!(err.kind(), ErrorKind::Unsupported),
+    }
+    assert_eq!(
+        std::fs::read_dir(&dir).unwrap().count(),
+        1,
+        "The clone shouldn't be left next to the file"
+    );
+
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[cfg(feature = "bytes")]
+#[test]
+fn test_bytes_lines() {
+    let file = File::open("resources/test-file-crlf").unwrap();
+    let mut reader = EasyReader::new(file).unwrap();
+
+    let line = reader.next_line_bytes().unwrap().unwrap();
+    assert_eq!(line, "AAAA AAAA");
+    let shared = line.slice(5..);
+    assert_eq!(shared, "AAAA");
+
+    assert_eq!(reader.next_line_bytes().unwrap().unwrap(), "B B BB BBB");
+    assert_eq!(reader.prev_line_bytes().unwrap().unwrap(), "AAAA AAAA");
+    assert_eq!(reader.current_line_bytes().unwrap().unwrap(), "AAAA AAAA");
+    assert!(reader.prev_line_bytes().unwrap().is_none());
+
+    reader.eof();
+    assert_eq!(
+        reader.prev_line_bytes().unwrap().unwrap(),
+        "EEEE  EEEEE  EEEE  EEEEE"
+    );
+    #[cfg(feature = "rand")]
+    assert!(!reader.random_line_bytes().unwrap().unwrap().is_empty());
+}
+
+#[test]
+fn test_line_buf() {
+    let file = File::open("resources/test-file-crlf").unwrap();
+    let mut reader = EasyReader::new(file).unwrap();
+
+    let line: Vec<u8> = reader.next_line_as().unwrap().unwrap();
+    assert_eq!(line, b"AAAA AAAA");
+    let line: std::rc::Rc<str> = reader.next_line_as().unwrap().unwrap();
+    assert_eq!(&*line, "B B BB BBB");
+    let line = reader
+        .current_line_as::<std::sync::Arc<str>>()
+        .unwrap()
+        .unwrap();
+    assert_eq!(&*line, "B B BB BBB");
+    let line = reader.prev_line_as::<Box<str>>().unwrap().unwrap();
+    assert_eq!(&*line, "AAAA AAAA");
+    #[cfg(feature = "bytes")]
+    assert_eq!(
+        reader.next_line_as::<bytes::Bytes>().unwrap().unwrap(),
+        "B B BB BBB"
+    );
+
+    struct LineLength(u64);
+    impl LineBuf for LineLength {
+        fn from_line_bytes(_bytes: Vec<u8>, start: u64, end: u64) -> io::Result<Self> {
+            Ok(LineLength(end - start))
+        }
+    }
+    reader.eof();
+    assert_eq!(reader.prev_line_as::<LineLength>().unwrap().unwrap().0, 24);
+}
+
+#[cfg(feature = "index")]
+#[test]
+fn test_cancel() {
+    let file = File::open("resources/test-file-lf").unwrap();
+    let mut reader = EasyReader::new(file).unwrap();
+    reader.next_line().unwrap();
+
+    let cancel = Cancel::new();
+    cancel.clone().cancel();
+    assert!(cancel.is_cancelled());
+    let err = reader.build_index_cancellable(&cancel).err().unwrap();
+    assert_eq!(ReaderError::of(&err), Some(&ReaderError::Cancelled));
+    assert_ne!(
+        err.kind(),
+        ErrorKind::Interrupted,
+        "Interrupted reads are retried"
+    );
+    assert!(
+        !reader.indexed,
+        "A cancelled build shouldn't leave an index"
+    );
+    assert_eq!(
+        reader.next_line().unwrap().unwrap(),
+        "B B BB BBB",
+        "A cancelled build shouldn't move the cursor"
+    );
+
+    reader.build_index_cancellable(&Cancel::new()).unwrap();
+    assert!(reader.indexed);
+}
+
+#[test]
+fn test_cancel_search() {
+    let file = File::open("resources/test-file-lf").unwrap();
+    let mut reader = EasyReader::new(file).unwrap();
+    reader.next_line().unwrap();
+
+    let cancelled = Cancel::new();
+    cancelled.cancel();
+    let is_cancelled = |err: io::Error| ReaderError::of(&err) == Some(&ReaderError::Cancelled);
+    let has_e = |line: &str| line.contains('E');
+    assert!(is_cancelled(
+        reader
+            .next_line_matching_cancellable(has_e, &cancelled)
+            .unwrap_err()
+    ));
+    reader.eof();
+    assert!(is_cancelled(
+        reader
+            .prev_line_matching_cancellable(has_e, &cancelled)
+            .unwrap_err()
+    ));
+    reader.bof();
+    reader.next_line().unwrap();
+    assert!(is_cancelled(
+        reader
+            .find_next_literal_cancellable("E", &cancelled)
+            .unwrap_err()
+    ));
+    assert!(is_cancelled(
+        reader
+            .count_matching_cancellable(has_e, &cancelled)
+            .unwrap_err()
+    ));
+    assert!(is_cancelled(
+        reader
+            .binary_search_by_cancellable(|line| line.cmp("C"), &cancelled)
+            .unwrap_err()
+    ));
+    assert!(is_cancelled(
+        reader
+            .seek_to_time_cancellable(0, |_| Some(0), &cancelled)
+            .unwrap_err()
+    ));
+    assert_eq!(
+        reader.current_line().unwrap().unwrap(),
+        "AAAA AAAA",
+        "A cancelled search shouldn't move the cursor"
+    );
+
+    let cancel = Cancel::new();
+    assert_eq!(
+        reader
+            .next_line_matching_cancellable(has_e, &cancel)
+            .unwrap()
+            .unwrap(),
+        "EEEE  EEEEE  EEEE  EEEEE"
+    );
+    assert_eq!(
+        reader.count_matching_cancellable(has_e, &cancel).unwrap(),
+        1
+    );
+    assert_eq!(
+        reader.find_next_literal_cancellable("D", &cancel).unwrap(),
+        None
+    );
+}
+
+#[test]
+fn test_line_cursor() {
+    // A line store unrelated to files, positions are cursors
+    struct MemoryLines {
+        lines: Vec<String>,
+        // Zero is before the first line, `lines.len() + 1` after the last one
+        cursor: usize,
+    }
+
+    impl LineCursor for MemoryLines {
+        type Position = usize;
+
+        fn next_line(&mut self) -> io::Result<Option<String>> {
+            if self.cursor >= self.lines.len() {
+                return Ok(None);
+            }
+            self.cursor += 1;
+            self.current_line()
+        }
+
+        fn prev_line(&mut self) -> io::Result<Option<String>> {
+            if self.cursor <= 1 {
+                return Ok(None);
+            }
+            self.cursor -= 1;
+            self.current_line()
+        }
+
+        fn current_line(&mut self) -> io::Result<Option<String>> {
+            let line = self
+                .cursor
+                .checked_sub(1)
+                .and_then(|line| self.lines.get(line));
+            Ok(line.cloned())
+        }
+
+        fn bof(&mut self) {
+            self.cursor = 0;
+        }
+
+        fn eof(&mut self) {
+            self.cursor = self.lines.len() + 1;
+        }
+
+        fn position(&self) -> usize {
+            self.cursor
+        }
+
+        fn goto(&mut self, cursor: &usize) -> io::Result<()> {
+            self.cursor = *cursor;
+            Ok(())
+        }
+    }
+
+    let mut lines = MemoryLines {
+        lines: ["one", "two", "three"].map(String::from).to_vec(),
+        cursor: 0,
+    };
+    let mut viewport = Viewport::new(&mut lines, 2).unwrap();
+    assert_eq!(viewport.lines(), ["one", "two"]);
+    assert_eq!(viewport.scroll_down(5).unwrap(), 1);
+    assert_eq!(viewport.lines(), ["two", "three"]);
+    assert_eq!(lines.position(), 3);
+
+    // The helpers written against the trait
+    lines.bof();
+    assert_eq!(lines.next_lines(2).unwrap(), ["one", "two"]);
+    assert_eq!(lines.prev_lines(5).unwrap(), ["one"]);
+    let mut has_e = |line: &str| line.contains('e');
+    assert_eq!(
+        lines.next_line_matching(&mut has_e).unwrap().unwrap(),
+        "three"
+    );
+    assert_eq!(lines.next_line_matching(&mut has_e).unwrap(), None);
+    assert_eq!(lines.position(), 3, "The cursor shouldn't move");
+    assert_eq!(
+        lines.prev_line_matching(&mut has_e).unwrap().unwrap(),
+        "one"
+    );
+    lines.eof();
+    assert_eq!(lines.next_line_matching(&mut has_e).unwrap(), None);
+    assert_eq!(lines.prev_line().unwrap().unwrap(), "three");
+    #[cfg(feature = "rand")]
+    {
+        lines.goto(&2).unwrap();
+        let mut sample = lines.sample(5).unwrap();
+        sample.sort();
+        assert_eq!(sample, ["one", "three", "two"]);
+        assert_eq!(lines.sample(2).unwrap().len(), 2);
+        assert_eq!(lines.position(), 2);
+    }
+    let mut pager = Pager::new(&mut lines, 2).unwrap();
+    assert_eq!(pager.page_down().unwrap().unwrap().lines, ["one", "two"]);
+    let page = pager.page_down().unwrap().unwrap();
+    assert_eq!(
+        (page.first_line, page.lines),
+        (2, vec!["three".to_string()])
+    );
+    assert_eq!(pager.page_up().unwrap().unwrap().lines, ["one", "two"]);
+
+    let file = File::open("resources/test-file-lf").unwrap();
+    let mut reader = EasyReader::new(file).unwrap();
+    reader.next_line().unwrap();
+    // The trait and the reader agree on the positions
+    let position = LineCursor::position(&reader);
+    assert_eq!(position, reader.position());
+    reader.eof();
+    let eof = LineCursor::position(&reader);
+    LineCursor::goto(&mut reader, &position).unwrap();
+    assert_eq!(reader.next_line().unwrap().unwrap(), "B B BB BBB");
+    LineCursor::goto(&mut reader, &eof).unwrap();
+    assert_eq!(reader.next_line().unwrap(), None);
+    reader.seek_to(position).unwrap();
+    assert_eq!(reader.current_line().unwrap().unwrap(), "AAAA AAAA");
+    reader.bof();
+    let cursor: &mut dyn LineCursor<Position = Position> = &mut reader;
+    let line = cursor.next_line_matching(&mut |line| line.starts_with('C'));
+    assert_eq!(line.unwrap().unwrap(), "CCCC  CCCCC");
+    assert_eq!(cursor.prev_lines(5).unwrap(), ["B B BB BBB", "AAAA AAAA"]);
+
+    // Over a view of the file
+    reader.view_lines(1..3).unwrap();
+    let viewport = Viewport::new(&mut reader, 5).unwrap();
+    assert_eq!(viewport.lines(), ["B B BB BBB", "CCCC  CCCCC"]);
+}
+
+#[cfg(feature = "faulty")]
+#[test]
+fn test_faulty_reader() {
+    let file = File::open("resources/test-file-lf").unwrap();
+    let mut faulty = FaultyReader::new(file).unwrap();
+    faulty
+        .fault_at(0, Fault::Interrupted)
+        .fault_at(1, Fault::ShortRead(4))
+        .fault_at(2, Fault::Truncate(9))
+        .fault_at(2, Fault::Append(b"\nZ".to_vec()));
+
+    let mut buffer = [0; 16];
+    assert_eq!(
+        faulty.read(&mut buffer).err().unwrap().kind(),
+        ErrorKind::Interrupted
+    );
+    assert_eq!(faulty.read(&mut buffer).unwrap(), 4);
+    assert_eq!(&buffer[..4], b"AAAA");
+    assert_eq!(faulty.read(&mut buffer).unwrap(), 5);
+    assert_eq!(&buffer[..5], b" AAAA");
+    assert_eq!(faulty.read(&mut buffer).unwrap(), 2);
+    assert_eq!(&buffer[..2], b"\nZ");
+    assert_eq!(faulty.seek(SeekFrom::End(0)).unwrap(), 11);
+    assert_eq!(faulty.calls(), 4);
+
+    let file = File::open("resources/test-file-lf").unwrap();
+    let mut faulty = FaultyReader::new(file).unwrap();
+    faulty
+        .fault_at(0, Fault::Interrupted)
+        .fault_at(1, Fault::ShortRead(1))
+        .fault_at(2, Fault::ShortRead(3));
+    let mut reader = EasyReader::new(faulty).unwrap();
+    assert_eq!(reader.next_line().unwrap().unwrap(), "AAAA AAAA");
+    assert_eq!(reader.next_line().unwrap().unwrap(), "B B BB BBB");
+
+    let file = File::open("resources/test-file-lf").unwrap();
+    let mut faulty = FaultyReader::new(file).unwrap();
+    faulty.fault_at(0, Fault::Truncate(4));
+    let mut reader = EasyReader::new(faulty).unwrap();
+    assert_eq!(
+        reader.next_line().err().unwrap().kind(),
+        ErrorKind::UnexpectedEof
+    );
+    assert_eq!(
+        reader.current_offsets(),
+        (0, 0),
+        "A failed read shouldn't move the cursor"
+    );
+}
+
+#[test]
+fn test_iter() {
+    let file = File::open("resources/test-file-lf").unwrap();
+    let mut reader = EasyReader::new(file).unwrap();
+
+    let lengths: Vec<usize> = reader.iter().map(|line| line.unwrap().len()).collect();
+    assert_eq!(lengths, [9, 10, 11, 25, 24]);
+    assert!(
+        reader.iter().next().is_none(),
+        "The iteration should continue from the cursor, already at the last line"
+    );
+
+    reader.bof();
+    reader.next_line().unwrap();
+    let lines: Vec<String> = (&mut reader)
+        .into_iter()
+        .take(2)
+        .collect::<io::Result<_>>()
+        .unwrap();
+    assert_eq!(lines, ["B B BB BBB", "CCCC  CCCCC"]);
+    assert_eq!(
+        reader.current_line().unwrap().unwrap(),
+        "CCCC  CCCCC",
+        "The cursor should be left on the last line yielded"
+    );
+
+    let file = File::open("resources/test-file-lf").unwrap();
+    let mut reader = EasyReader::new(file).unwrap();
+    let mut count = 0;
+    for line in &mut reader {
+        assert!(!line.unwrap().is_empty());
+        count += 1;
+    }
+    assert_eq!(count, 5);
+}
+
+#[test]
+fn test_rev_lines() {
+    let file = File::open("resources/test-file-crlf").unwrap();
+    let mut reader = EasyReader::new(file).unwrap();
+    reader.next_line().unwrap();
+
+    let lines: Vec<String> = reader.rev_lines().collect::<io::Result<_>>().unwrap();
+    assert_eq!(
+        lines,
+        [
+            "EEEE  EEEEE  EEEE  EEEEE",
+            "DDDD  DDDDD DD DDD DDD DD",
+            "CCCC  CCCCC",
+            "B B BB BBB",
+            "AAAA AAAA"
+        ],
+        "The reverse iteration should start from the EOF whatever the cursor position"
+    );
+    assert_eq!(reader.next_line().unwrap().unwrap(), "B B BB BBB");
+
+    let last = reader.rev_lines().next().unwrap().unwrap();
+    assert_eq!(last, "EEEE  EEEEE  EEEE  EEEEE");
+
+    let mut lines = reader.into_rev_lines();
+    assert_eq!(
+        lines
+            .by_ref()
+            .take(2)
+            .collect::<io::Result<Vec<_>>>()
+            .unwrap(),
+        ["EEEE  EEEEE  EEEE  EEEEE", "DDDD  DDDDD DD DDD DDD DD"]
+    );
+    let mut reader = lines.into_inner();
+    assert_eq!(
+        reader.next_line().unwrap().unwrap(),
+        "EEEE  EEEEE  EEEE  EEEEE"
+    );
+    let first = reader.into_rev_lines().last().unwrap().unwrap();
+    assert_eq!(first, "AAAA AAAA");
+}
+
+#[test]
+fn test_spooled() {
+    // Only implements Read, like a pipe
+    struct Pipe(io::Cursor<Vec<u8>>);
+    impl Read for Pipe {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            // Trickles the bytes, like a pipe would
+            let len = buf.len().min(7);
+            self.0.read(&mut buf[..len])
+        }
+    }
+    let pipe = || {
+        Pipe(io::Cursor::new(
+            std::fs::read("resources/test-file-crlf").unwrap(),
+        ))
+    };
+
+    let mut spooled = Spooled::in_memory(pipe());
+    let mut head = [0; 4];
+    spooled.read_exact(&mut head).unwrap();
+    assert_eq!(&head, b"AAAA");
+    assert!(
+        spooled.spooled_len() < 89,
+        "Reading the head shouldn't consume the whole source"
+    );
+
+    for spooled in [
+        Spooled::in_memory(pipe()),
+        Spooled::in_temp_file(pipe()).unwrap(),
+    ] {
+        let mut reader = EasyReader::new(spooled).unwrap();
+        reader.eof();
+        assert_eq!(
+            reader.prev_line().unwrap().unwrap(),
+            "EEEE  EEEEE  EEEE  EEEEE"
+        );
+        assert_eq!(
+            reader.prev_line().unwrap().unwrap(),
+            "DDDD  DDDDD DD DDD DDD DD"
+        );
+        reader.bof();
+        assert_eq!(reader.next_line().unwrap().unwrap(), "AAAA AAAA");
+        #[cfg(feature = "rand")]
+        assert!(!reader.random_line().unwrap().unwrap().is_empty());
+    }
+}
+
+#[test]
+fn test_borrowed_lines() {
+    let content = std::fs::read("resources/test-file-crlf").unwrap();
+    let mut reader = EasyReader::new(io::Cursor::new(&content[..])).unwrap();
+
+    assert_eq!(reader.next_line_ref().unwrap(), Some("AAAA AAAA"));
+    let line = reader.next_line_ref().unwrap().unwrap();
+    assert_eq!(line, "B B BB BBB");
+    assert_eq!(
+        line.as_ptr(),
+        content[11..].as_ptr(),
+        "The line should borrow from the buffer"
+    );
+    assert_eq!(reader.current_line_ref().unwrap(), Some("B B BB BBB"));
+    assert_eq!(reader.prev_line_ref().unwrap(), Some("AAAA AAAA"));
+    assert_eq!(reader.prev_line_ref().unwrap(), None);
+    #[cfg(feature = "rand")]
+    assert!(!reader.random_line_ref().unwrap().unwrap().is_empty());
+
+    let mut reader = EasyReader::new(io::Cursor::new(b"ok\n\xff\n".to_vec())).unwrap();
+    reader.eof();
+    assert!(reader.prev_line_ref().is_err());
+}
+
+#[cfg(feature = "tokio")]
+#[test]
+fn test_async_reader() {
+    use std::{
+        pin::Pin,
+        task::{Context, Poll},
+    };
+    use tokio::io::{AsyncRead, AsyncSeek, ReadBuf};
+
+    // Every read is pending once before completing, like a read from a real file would
+    struct SlowReads(io::Cursor<Vec<u8>>, bool);
+    impl AsyncRead for SlowReads {
+        fn poll_read(
+            mut self: Pin<&mut Self>,
+            cx: &mut Context<'_>,
+            buf: &mut ReadBuf<'_>,
+        ) -> Poll<io::Result<()>> {
+            self.1 = !self.1;
+            if self.1 {
+                cx.waker().wake_by_ref();
+                return Poll::Pending;
+            }
+            Pin::new(&mut self.0).poll_read(cx, buf)
+        }
+    }
+    impl AsyncSeek for SlowReads {
+        fn start_seek(mut self: Pin<&mut Self>, position: SeekFrom) -> io::Result<()> {
+            Pin::new(&mut self.0).start_seek(position)
+        }
+        fn poll_complete(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<u64>> {
+            Pin::new(&mut self.0).poll_complete(cx)
+        }
+    }
+
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .build()
+        .unwrap();
+    runtime.block_on(async {
+        for indexed in [false, true] {
+            let content = std::fs::read("resources/test-file-crlf").unwrap();
+            let file = SlowReads(io::Cursor::new(content), false);
+            let mut reader = AsyncEasyReader::new(file).await.unwrap();
+            if indexed {
+                reader.build_index().await.unwrap();
+            }
+
+            tokio::select! {
+                biased;
+                _ = reader.next_line() => {}
+                _ = std::future::ready(()) => {}
+            }
+            assert_eq!(
+                reader.next_line().await.unwrap().unwrap(),
+                "AAAA AAAA",
+                "A cancelled read shouldn't move the cursor"
+            );
+            assert_eq!(reader.next_line().await.unwrap().unwrap(), "B B BB BBB");
+            assert_eq!(reader.current_line().await.unwrap().unwrap(), "B B BB BBB");
+            assert_eq!(reader.prev_line().await.unwrap().unwrap(), "AAAA AAAA");
+            assert!(reader.prev_line().await.unwrap().is_none());
+
+            reader.eof();
+            assert_eq!(
+                reader.prev_line().await.unwrap().unwrap(),
+                "EEEE  EEEEE  EEEE  EEEEE"
+            );
+            assert!(reader.next_line().await.unwrap().is_none());
+            #[cfg(feature = "rand")]
+            assert!(!reader.random_line().await.unwrap().unwrap().is_empty());
+        }
+
+        let file = tokio::fs::File::open("resources/file-with-blank-line-at-the-beginning")
+            .await
+            .unwrap();
+        let mut reader = AsyncEasyReader::new(file).await.unwrap();
+        let cancel = Cancel::new();
+        cancel.cancel();
+        let err = reader.build_index_cancellable(&cancel).await.err().unwrap();
+        assert_eq!(ReaderError::of(&err), Some(&ReaderError::Cancelled));
+        assert!(reader.next_line().await.unwrap().unwrap().is_empty());
+        assert_eq!(
+            reader.next_line().await.unwrap().unwrap(),
+            "Blank line above!"
+        );
+        assert!(reader.next_line().await.unwrap().is_none());
+        assert!(reader.prev_line().await.unwrap().unwrap().is_empty());
+
+        // Another delimiter, the CRs are kept. A chunk size of 0 reads a byte at a time.
+        for indexed in [false, true] {
+            let file = io::Cursor::new(b"a\r;bb;;c;".to_vec());
+            let mut reader = AsyncEasyReader::new(file).await.unwrap();
+            reader.chunk_size(0).delimiter(b';');
+            if indexed {
+                reader.build_index().await.unwrap();
+            }
+            let mut lines = Vec::new();
+            while let Some(line) = reader.next_line().await.unwrap() {
+                lines.push(line);
+            }
+            assert_eq!(lines, ["a\r", "bb", "", "c"]);
+            assert_eq!(reader.prev_line().await.unwrap().unwrap(), "");
+            assert_eq!(reader.current_line().await.unwrap().unwrap(), "");
+            reader.eof();
+            assert_eq!(reader.prev_line().await.unwrap().unwrap(), "c");
+            reader.bof();
+            assert_eq!(reader.next_line().await.unwrap().unwrap(), "a\r");
+            #[cfg(feature = "rand")]
+            assert!(lines.contains(&reader.random_line().await.unwrap().unwrap()));
+        }
+    });
+}
+
+#[cfg(feature = "stream")]
+#[test]
+fn test_line_streams() {
+    use futures_util::{StreamExt, TryStreamExt};
+
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .build()
+        .unwrap();
+    runtime.block_on(async {
+        let open = || async {
+            let file = tokio::fs::File::open("resources/test-file-lf")
+                .await
+                .unwrap();
+            AsyncEasyReader::new(file).await.unwrap()
+        };
+
+        let lines: Vec<String> = open().await.into_stream().try_collect().await.unwrap();
+        assert_eq!(lines.len(), 5);
+        assert_eq!(lines[0], "AAAA AAAA");
+
+        let mut reader = open().await;
+        reader.next_line().await.unwrap();
+        let mut stream = std::pin::pin!(reader.into_stream());
+        let second = stream.next().await.unwrap().unwrap();
+        assert_eq!(
+            second, "B B BB BBB",
+            "The stream should start from the cursor"
+        );
+
+        let lines: Vec<String> = open()
+            .await
+            .into_rev_stream()
+            .take(2)
+            .try_collect()
+            .await
+            .unwrap();
+        assert_eq!(
+            lines,
+            ["EEEE  EEEEE  EEEE  EEEEE", "DDDD  DDDDD DD DDD DDD DD"]
+        );
+    });
+}
+
+#[test]
+fn test_follow() {
+    use std::{thread, time::Duration};
+
+    let path = std::env::temp_dir().join("easy_reader_test_follow");
+    std::fs::write(&path, "one\ntw").unwrap();
+    let append = |content: &str| {
+        let mut file = std::fs::OpenOptions::new()
+            .append(true)
+            .open(&path)
+            .unwrap();
+        file.write_all(content.as_bytes()).unwrap();
+    };
+
+    let mut reader = EasyReader::new(File::open(&path).unwrap()).unwrap();
+    reader.eof();
+    append("o\nthree\nfo");
+
+    let mut follow = reader.follow(Duration::from_millis(5));
+    assert_eq!(
+        follow.next().unwrap().unwrap(),
+        "two",
+        "The line being written at the EOF should be yielded once complete"
+    );
+    assert_eq!(follow.next().unwrap().unwrap(), "three");
+
+    thread::scope(|scope| {
+        scope.spawn(|| {
+            thread::sleep(Duration::from_millis(50));
+            append("ur\n");
+        });
+        assert_eq!(
+            follow.next().unwrap().unwrap(),
+            "four",
+            "An incomplete line shouldn't be yielded"
+        );
+    });
+
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn test_refresh() {
+    let path = std::env::temp_dir().join("easy_reader_test_refresh");
+    std::fs::write(&path, "one\ntw").unwrap();
+    let append = |content: &str| {
+        let mut file = std::fs::OpenOptions::new()
+            .append(true)
+            .open(&path)
+            .unwrap();
+        file.write_all(content.as_bytes()).unwrap();
+    };
+
+    let mut reader = EasyReader::new(File::open(&path).unwrap()).unwrap();
+    #[cfg(feature = "index")]
+    reader.build_index().unwrap();
+    reader.next_line().unwrap();
+    assert_eq!(reader.next_line().unwrap().unwrap(), "tw");
+    assert!(!reader.refresh().unwrap(), "The file didn't grow yet");
+
+    append("o\nthree\n");
+    assert!(reader.refresh().unwrap());
+    assert_eq!(
+        reader.current_line().unwrap().unwrap(),
+        "two",
+        "The line under the cursor should be completed"
+    );
+    assert_eq!(reader.next_line().unwrap().unwrap(), "three");
+    assert!(reader.next_line().unwrap().is_none());
+    #[cfg(feature = "index")]
+    {
+        assert!(reader.indexed, "The index should be extended");
+        assert_eq!(reader.line_starts.len(), 3);
+    }
+
+    append("four\n");
+    assert!(
+        reader.next_line().unwrap().is_none(),
+        "Without auto-refresh the new line shouldn't be seen"
+    );
+    reader.auto_refresh(true);
+    assert_eq!(reader.next_line().unwrap().unwrap(), "four");
+    reader.bof();
+    let lines: Vec<String> = reader.iter().collect::<io::Result<_>>().unwrap();
+    assert_eq!(lines, ["one", "two", "three", "four"]);
+
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[cfg(unix)]
+#[test]
+fn test_reopen() {
+    let path = std::env::temp_dir().join("easy_reader_test_reopen");
+    let rotated_path = std::env::temp_dir().join("easy_reader_test_reopen.1");
+    std::fs::write(&path, "old 1\nold 2\n").unwrap();
+
+    let mut reader = EasyReader::open(&path).unwrap();
+    reader.next_line().unwrap();
+    assert!(!reader.reopen().unwrap(), "The file hasn't been rotated");
+
+    std::fs::rename(&path, &rotated_path).unwrap();
+    assert!(!reader.reopen().unwrap(), "The new file isn't there yet");
+    std::fs::write(&path, "new 1\nnew 2\nnew 3\n").unwrap();
+    assert!(reader.reopen().unwrap());
+    assert_eq!(reader.next_line().unwrap().unwrap(), "new 1");
+
+    // copytruncate
+    std::fs::write(&path, "short\n").unwrap();
+    assert!(reader.reopen().unwrap());
+    assert_eq!(reader.next_line().unwrap().unwrap(), "short");
+
+    std::fs::rename(&path, &rotated_path).unwrap();
+    std::fs::write(&path, "newer\n").unwrap();
+    let mut follow = reader.follow(std::time::Duration::from_millis(5));
+    assert_eq!(
+        follow.next().unwrap().unwrap(),
+        "newer",
+        "follow() should go on with the new file"
+    );
+
+    std::fs::remove_file(&path).unwrap();
+    std::fs::remove_file(&rotated_path).unwrap();
+}
+
+#[test]
+fn test_truncation_policy() {
+    let path = std::env::temp_dir().join("easy_reader_test_truncation_policy");
+    let content = "first line\nsecond line\nthird line\n";
+
+    for policy in [
+        TruncationPolicy::StartOver,
+        TruncationPolicy::Eof,
+        TruncationPolicy::Error,
+    ] {
+        std::fs::write(&path, content).unwrap();
+        let mut reader = EasyReader::new(File::open(&path).unwrap()).unwrap();
+        reader.truncation_policy(policy);
+        reader.next_line().unwrap();
+        reader.next_line().unwrap();
+
+        std::fs::write(&path, "new\n").unwrap();
+        match policy {
+            TruncationPolicy::StartOver => {
+                assert_eq!(reader.next_line().unwrap().unwrap(), "new");
+            }
+            TruncationPolicy::Eof => {
+                assert!(reader.next_line().unwrap().is_none());
+                assert_eq!(reader.prev_line().unwrap().unwrap(), "new");
+            }
+            TruncationPolicy::Error => {
+                let err = reader.next_line().err().unwrap();
+                assert_eq!(err.kind(), ErrorKind::InvalidData);
+                assert_eq!(
+                    ReaderError::of(&err),
+                    Some(&ReaderError::Truncated {
+                        previous_size: content.len() as u64,
+                        size: 4
+                    })
+                );
+                assert_eq!(
+                    reader.next_line().unwrap().unwrap(),
+                    "new",
+                    "The error should only be returned once"
+                );
+            }
+        }
+    }
+
+    // Truncated to 0 bytes, then growing again
+    for policy in [
+        TruncationPolicy::StartOver,
+        TruncationPolicy::Eof,
+        TruncationPolicy::Error,
+    ] {
+        for index in [None, Some(0), Some(2)] {
+            std::fs::write(&path, content).unwrap();
+            let mut reader = EasyReader::new(File::open(&path).unwrap()).unwrap();
+            reader.truncation_policy(policy);
+            match index {
+                #[cfg(feature = "index")]
+                Some(0) => {
+                    reader.build_index().unwrap();
+                }
+                #[cfg(feature = "index")]
+                Some(every) => {
+                    reader.build_index_every(every).unwrap();
+                }
+                _ => (),
+            }
+            reader.next_line().unwrap();
+
+            std::fs::write(&path, "").unwrap();
+            match policy {
+                TruncationPolicy::Error => {
+                    let err = reader.next_line().err().unwrap();
+                    assert_eq!(
+                        ReaderError::of(&err),
+                        Some(&ReaderError::Truncated {
+                            previous_size: content.len() as u64,
+                            size: 0
+                        })
+                    );
+                }
+                _ => assert_eq!(reader.next_line().unwrap(), None),
+            }
+            assert_eq!(reader.tail(2).unwrap(), Vec::<String>::new());
+            assert_eq!(reader.line_count().unwrap(), 0);
+            assert_eq!(reader.current_line().unwrap(), None);
+            assert_eq!(reader.get_line(0).unwrap(), None);
+            reader.eof();
+            assert_eq!(reader.skip_lines(-1).unwrap(), None);
+            assert_eq!(reader.prev_line().unwrap(), None);
+
+            std::fs::write(&path, "new\nlines\n").unwrap();
+            assert!(reader.refresh().unwrap());
+            assert_eq!(reader.line_count().unwrap(), 2);
+            assert_eq!(reader.tail(2).unwrap(), ["new", "lines"]);
+            #[cfg(feature = "index")]
+            assert_eq!(reader.index().is_some(), index == Some(0));
+            #[cfg(feature = "index")]
+            assert_eq!(reader.sparse_lines().is_some(), index == Some(2));
+        }
+    }
+
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[cfg(feature = "index")]
+#[test]
+fn test_index_file() {
+    let path = std::env::temp_dir().join("easy_reader_test_index_file");
+    std::fs::copy("resources/test-file-crlf", &path).unwrap();
+    let sidecar = EasyReader::sidecar_index_path(&path);
+    assert_eq!(
+        sidecar.file_name().unwrap(),
+        "easy_reader_test_index_file.eridx"
+    );
+
+    let mut reader = EasyReader::open(&path).unwrap();
+    assert!(reader.save_index(&sidecar).is_err(), "Nothing to save yet");
+    reader.build_index().unwrap();
+    reader.save_index(&sidecar).unwrap();
+    let line_starts = reader.line_starts.clone();
+
+    let mut reader = EasyReader::open(&path).unwrap();
+    assert!(reader.indexed, "The sidecar index should be loaded");
+    assert_eq!(reader.line_starts, line_starts);
+    reader.eof();
+    assert_eq!(
+        reader.prev_line().unwrap().unwrap(),
+        "EEEE  EEEEE  EEEE  EEEEE"
+    );
+
+    let file = File::open("resources/test-file-lf").unwrap();
+    let mut other = EasyReader::new(file).unwrap();
+    let err = other.load_index(&sidecar).err().unwrap();
+    assert_eq!(err.kind(), ErrorKind::InvalidData);
+    assert_eq!(
+        ReaderError::of(&err),
+        Some(&ReaderError::IndexStale),
+        "The index of another file shouldn't be loaded"
+    );
+    assert!(!other.indexed);
+
+    let bytes = std::fs::read(&sidecar).unwrap();
+    let mut corrupted = bytes.clone();
+    corrupted[40] ^= 1;
+    std::fs::write(&sidecar, corrupted).unwrap();
+    assert!(!EasyReader::open(&path).unwrap().indexed);
+    let mut other_version = bytes.clone();
+    other_version[6] += 1;
+    std::fs::write(&sidecar, other_version).unwrap();
+    assert!(!EasyReader::open(&path).unwrap().indexed);
+
+    std::fs::write(&sidecar, bytes).unwrap();
+    let mut other = EasyReader::new(File::open(&path).unwrap()).unwrap();
+    for &delimiter in b"\0\r" {
+        other.delimiter(delimiter);
+        assert_eq!(
+            other.load_index(&sidecar).err().unwrap().kind(),
+            ErrorKind::InvalidData,
+            "The index of other line terminators shouldn't be loaded"
+        );
+        assert!(!other.indexed);
+    }
+    other.delimiter_bytes(b"\r\n").unwrap();
+    assert!(other.load_index(&sidecar).is_err());
+    other.delimiter(b'\n');
+    assert!(other.load_index(&sidecar).unwrap().indexed);
+
+    // A boundary can't be told apart from another one
+    struct Lf;
+    impl Boundary for Lf {
+        fn terminator_len(&self) -> usize {
+            1
+        }
+        fn is_boundary(&self, before: &[u8], _after: &[u8]) -> bool {
+            before == b"\n"
+        }
+    }
+    other.boundary(Lf).unwrap().build_index().unwrap();
+    assert_eq!(
+        other.save_index(&sidecar).err().unwrap().kind(),
+        ErrorKind::Unsupported
+    );
+
+    let mut content = std::fs::read(&path).unwrap();
+    content[0] = b'Z';
+    std::fs::write(&path, content).unwrap();
+    assert!(
+        !EasyReader::open(&path).unwrap().indexed,
+        "A stale index of a file of the same size shouldn't be loaded"
+    );
+
+    std::fs::remove_file(&path).unwrap();
+    std::fs::remove_file(&sidecar).unwrap();
+}
+
+#[cfg(feature = "index")]
+#[test]
+fn test_sparse_index() {
+    let mut content = String::new();
+    for i in 0..1000 {
+        content.push_str(&format!("line {}\n", i));
+    }
+    let mut reader = EasyReader::new(io::Cursor::new(content.into_bytes())).unwrap();
+    assert!(reader.build_index_every(0).is_err());
+    reader.build_index_every(64).unwrap();
+    assert_eq!(reader.index_strategy(), IndexStrategy::Sparse { every: 64 });
+
+    assert_eq!(
+        reader.goto_line_approx(700).unwrap().unwrap().line,
+        "line 700"
+    );
+    assert_eq!(reader.next_line().unwrap().unwrap(), "line 701");
+    assert_eq!(
+        reader.goto_line_approx(999).unwrap().unwrap().line,
+        "line 999"
+    );
+    assert!(reader.goto_line_approx(1000).unwrap().is_none());
+
+    let offset = reader.offset_of(130, 2).unwrap();
+    assert_eq!(reader.position_of(offset).unwrap(), (130, 2));
+    let estimate = reader.estimate_line_count().unwrap();
+    assert!(estimate.is_exact());
+    assert_eq!(estimate.lines, 1000);
+
+    #[cfg(feature = "rand")]
+    for _ in 0..100 {
+        let line = reader.random_line().unwrap().unwrap();
+        assert!(line.starts_with("line "));
+        let start = reader.current_start_line_offset;
+        assert_eq!(
+            start,
+            reader.offset_of(line[5..].parse().unwrap(), 0).unwrap(),
+            "Random lines should be whole lines"
+        );
+    }
+
+    reader.build_index().unwrap();
+    assert_eq!(
+        reader.index_strategy(),
+        IndexStrategy::Full,
+        "The full index should replace the sparse one"
+    );
+}
+
+#[cfg(feature = "index")]
+#[test]
+fn test_compact_index() {
+    let content = b"first\r\n\r\nthird\n\nfifth\r\nlast".to_vec();
+    let mut scanning = EasyReader::new(io::Cursor::new(content.clone())).unwrap();
+    let mut indexed = EasyReader::new(io::Cursor::new(content)).unwrap();
+    indexed.build_index().unwrap();
+    assert_eq!(indexed.line_starts.len(), 6);
+
+    while let Some(line) = scanning.next_line().unwrap() {
+        assert_eq!(indexed.next_line().unwrap(), Some(line));
+        assert_eq!(
+            indexed.current_start_line_offset,
+            scanning.current_start_line_offset
+        );
+        assert_eq!(
+            indexed.current_end_line_offset, scanning.current_end_line_offset,
+            "The line ends derived from the next line starts should match the scanned ones"
+        );
+    }
+    assert!(indexed.next_line().unwrap().is_none());
+    while let Some(line) = scanning.prev_line().unwrap() {
+        assert_eq!(indexed.prev_line().unwrap(), Some(line));
+    }
+    assert_eq!(indexed.position_of(9).unwrap(), (2, 0));
+    assert_eq!(indexed.offset_of(3, 0).unwrap(), 15);
+}
+
+#[cfg(feature = "index")]
+#[test]
+fn test_compressed_index() {
+    let mut content = String::new();
+    for i in 0..1000 {
+        content.push_str(&format!("{}\n", "x".repeat(i % 300)));
+    }
+    let mut plain = EasyReader::new(io::Cursor::new(content.clone().into_bytes())).unwrap();
+    plain.build_index().unwrap();
+    let mut compressed = EasyReader::new(io::Cursor::new(content.into_bytes())).unwrap();
+    compressed.compress_index(true).build_index().unwrap();
+    assert!(compressed.line_starts.is_compressed());
+    assert_eq!(compressed.line_starts.len(), 1000);
+    assert!(compressed.line_starts.iter().eq(plain.line_starts.iter()));
+
+    for &line in &[0, 63, 64, 65, 500, 999] {
+        assert_eq!(
+            compressed.goto_line_approx(line).unwrap(),
+            plain.goto_line_approx(line).unwrap()
+        );
+        let offset = plain.offset_of(line, 0).unwrap();
+        assert_eq!(compressed.offset_of(line, 0).unwrap(), offset);
+        assert_eq!(
+            compressed.position_of(offset + 1).unwrap(),
+            plain.position_of(offset + 1).unwrap()
+        );
+    }
+    while let Some(line) = plain.prev_line().unwrap() {
+        assert_eq!(compressed.prev_line().unwrap(), Some(line));
+    }
+
+    let mut line_starts = compressed.line_starts.with_compression(true);
+    for line in (0..1000).rev() {
+        assert_eq!(line_starts.pop(), plain.line_starts.get(line));
+        assert_eq!(
+            line_starts.last(),
+            line.checked_sub(1)
+                .and_then(|line| plain.line_starts.get(line))
+        );
+    }
+    assert!(line_starts.pop().is_none());
+
+    compressed.compress_index(false);
+    assert!(!compressed.line_starts.is_compressed());
+    assert_eq!(compressed.line_starts, plain.line_starts);
+}
+
+#[cfg(feature = "index")]
+#[test]
+fn test_index_past_4_gib() {
+    // A file of a few hundred GiB, read as it's needed: lines of 'x' (and some more bytes
+    // between the starts of the lines than u32::MAX)
+    struct Huge {
+        size: u64,
+        pos: u64,
+        newlines: Vec<u64>,
+    }
+    impl Read for Huge {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            let len = (self.size.saturating_sub(self.pos)).min(buf.len() as u64) as usize;
+            buf[..len].fill(b'x');
+            let from = self.newlines.partition_point(|&newline| newline < self.pos);
+            for &newline in &self.newlines[from..] {
+                if newline >= self.pos + len as u64 {
+                    break;
+                }
+                buf[(newline - self.pos) as usize] = b'\n';
+            }
+            self.pos += len as u64;
+            Ok(len)
+        }
+    }
+    impl Seek for Huge {
+        fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+            self.pos = match pos {
+                SeekFrom::Start(offset) => offset,
+                SeekFrom::End(offset) => (self.size as i64 + offset) as u64,
+                SeekFrom::Current(offset) => (self.pos as i64 + offset) as u64,
+            };
+            Ok(self.pos)
+        }
+    }
+
+    let stride = u64::from(u32::MAX) + 12345;
+    let starts: Vec<u64> = (0..200).map(|line| line * stride).collect();
+    let mut plain = LineIndex::new(false);
+    let mut compressed = LineIndex::new(true);
+    for &start in &starts {
+        plain.push(start);
+        compressed.push(start);
+    }
+    for line_starts in [&plain, &compressed] {
+        assert_eq!(line_starts.len(), starts.len());
+        assert_eq!(line_starts.last(), starts.last().copied());
+        for (line, &start) in starts.iter().enumerate() {
+            assert_eq!(line_starts.get(line), Some(start));
+            assert_eq!(line_starts.partition_point(start), line + 1);
+            assert_eq!(line_starts.position(start), Some(line));
+        }
+        assert!(line_starts.iter().eq(starts.iter().copied()));
+    }
+    assert_eq!(compressed.with_compression(false), plain);
+    let mut popped = compressed.clone();
+    for &start in starts.iter().rev() {
+        assert_eq!(popped.pop(), Some(start));
+    }
+
+    for line_starts in [plain, compressed] {
+        let huge = Huge {
+            size: starts[199] + 10,
+            pos: 0,
+            newlines: starts[1..].iter().map(|start| start - 1).collect(),
+        };
+        let mut reader = EasyReader::new(huge).unwrap();
+        reader.set_index(line_starts).unwrap();
+        assert_eq!(reader.line_count().unwrap(), 200);
+        assert_eq!(reader.offset_of(150, 3).unwrap(), starts[150] + 3);
+        assert_eq!(reader.position_of(starts[150] + 3).unwrap(), (150, 3));
+        assert_eq!(
+            reader.index_entry(1).unwrap(),
+            Some((stride, 2 * stride - 1))
+        );
+    }
+}
+
+#[cfg(feature = "index")]
+#[test]
+fn test_parallel_index() {
+    assert!(
+        EasyReader::new(File::open("resources/test-file-lf").unwrap())
+            .unwrap()
+            .build_index_parallel(0)
+            .is_err()
+    );
+
+    for path in &[
+        "resources/fatty_lipsum_lf",
+        "resources/test-file-crlf",
+        "resources/file-with-blank-line-at-the-beginning",
+        "resources/one-line-file",
+    ] {
+        let mut sequential = EasyReader::new(File::open(path).unwrap()).unwrap();
+        sequential.build_index().unwrap();
+        for &threads in &[1, 3, 8] {
+            let mut parallel = EasyReader::new(File::open(path).unwrap()).unwrap();
+            parallel.build_index_parallel(threads).unwrap();
+            assert!(parallel.indexed);
+            assert_eq!(
+                parallel.line_starts, sequential.line_starts,
+                "The stitched index of {} should match the sequential one",
+                path
+            );
+        }
+    }
+}
+
+#[cfg(feature = "index")]
+#[test]
+fn test_lazy_index() {
+    let content = b"first\r\n\r\nthird\n\nfifth\r\nlast\n".to_vec();
+    let mut scanning = EasyReader::new(io::Cursor::new(content.clone())).unwrap();
+    let mut lazy = EasyReader::new(io::Cursor::new(content.clone())).unwrap();
+    lazy.lazy_index(true);
+
+    let mut offsets = Vec::new();
+    for _ in 0..2 {
+        while let Some(line) = scanning.next_line().unwrap() {
+            assert_eq!(lazy.next_line().unwrap(), Some(line));
+            assert_eq!(lazy.save_cursor(), scanning.save_cursor());
+            offsets.push(lazy.save_cursor());
+        }
+        assert!(lazy.next_line().unwrap().is_none());
+        while let Some(line) = scanning.prev_line().unwrap() {
+            assert_eq!(lazy.prev_line().unwrap(), Some(line));
+            assert_eq!(lazy.save_cursor(), scanning.save_cursor());
+        }
+        assert!(lazy.prev_line().unwrap().is_none());
+    }
+    let lines = lazy.lazy_index.as_ref().unwrap();
+    assert_eq!(lines.len(), 6);
+    assert_eq!(lines.values().filter(|(_, next)| next.is_some()).count(), 5);
+
+    // The lines met are located without scanning the file anymore
+    lazy.file = io::Cursor::new(vec![b'x'; content.len()]);
+    lazy.bof();
+    for &offsets in offsets.iter().take(6) {
+        lazy.next_line().unwrap();
+        assert_eq!(lazy.save_cursor(), offsets);
+    }
+    for &offsets in offsets.iter().take(5).rev() {
+        lazy.prev_line().unwrap();
+        assert_eq!(lazy.save_cursor(), offsets);
+    }
+
+    lazy.file = io::Cursor::new(content);
+    lazy.build_index().unwrap();
+    assert!(
+        lazy.lazy_index.as_ref().unwrap().is_empty(),
+        "The full index should replace the lazy one"
+    );
+    lazy.lazy_index(false);
+    assert!(lazy.lazy_index.is_none());
+
+    let path = std::env::temp_dir().join("easy_reader_test_lazy_index");
+    std::fs::write(&path, "one\ntw").unwrap();
+    let mut reader = EasyReader::new(File::open(&path).unwrap()).unwrap();
+    reader.lazy_index(true);
+    reader.next_line().unwrap();
+    reader.next_line().unwrap();
+    reader.prev_line().unwrap();
+    std::fs::OpenOptions::new()
+        .append(true)
+        .open(&path)
+        .unwrap()
+        .write_all(b"o\nthree")
+        .unwrap();
+    reader.refresh().unwrap();
+    assert_eq!(
+        reader.next_line().unwrap().unwrap(),
+        "two",
+        "The last line met should be extended by the appended data"
+    );
+    assert_eq!(reader.next_line().unwrap().unwrap(), "three");
+    assert_eq!(reader.prev_line().unwrap().unwrap(), "two");
+
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[cfg(feature = "index")]
+#[test]
+fn test_build_index_with_progress() {
+    use std::ops::ControlFlow;
+
+    let mut indexed = EasyReader::new(File::open("resources/fatty_lipsum_lf").unwrap()).unwrap();
+    indexed.build_index().unwrap();
+    let mut reader = EasyReader::new(File::open("resources/fatty_lipsum_lf").unwrap()).unwrap();
+    let total = reader.file_size;
+    reader.next_line().unwrap();
+    let cursor = reader.save_cursor();
+
+    let abort_after = |partial| {
+        move |done, _| {
+            if done > 100_000 {
+                ControlFlow::Break(partial)
+            } else {
+                ControlFlow::Continue(())
+            }
+        }
+    };
+    let err = reader
+        .build_index_with_progress(abort_after(PartialIndex::Discard))
+        .err()
+        .unwrap();
+    assert_eq!(ReaderError::of(&err), Some(&ReaderError::Cancelled));
+    assert!(!reader.indexed);
+    assert!(reader.partial_index.is_none());
+    assert_eq!(reader.save_cursor(), cursor);
+
+    assert!(reader
+        .build_index_with_progress(abort_after(PartialIndex::Keep))
+        .is_err());
+    let mut reports = Vec::new();
+    reader
+        .build_index_with_progress(|done, total| {
+            reports.push((done, total));
+            ControlFlow::Continue(())
+        })
+        .unwrap();
+    assert!(
+        reports[0].0 > 100_000,
+        "The build should resume from where it was aborted"
+    );
+    assert!(reports.windows(2).all(|pair| pair[0].0 < pair[1].0));
+    assert_eq!(reports.last(), Some(&(total, total)));
+    assert!(reader.indexed);
+    assert_eq!(reader.line_starts, indexed.line_starts);
+    assert_eq!(reader.save_cursor(), cursor);
+}
+
+#[cfg(feature = "index")]
+#[test]
+fn test_stale_index() {
+    let path = std::env::temp_dir().join("easy_reader_test_stale_index");
+    std::fs::write(&path, "one\ntwo\nthree\n").unwrap();
+
+    let mut reader = EasyReader::new(File::open(&path).unwrap()).unwrap();
+    assert!(!reader.is_index_stale().unwrap(), "No index, nothing stale");
+    reader.build_index().unwrap();
+    assert!(!reader.is_index_stale().unwrap());
+    std::fs::OpenOptions::new()
+        .append(true)
+        .open(&path)
+        .unwrap()
+        .write_all(b"four\n")
+        .unwrap();
+    assert!(
+        !reader.is_index_stale().unwrap(),
+        "Appended data shouldn't make the index stale"
+    );
+
+    std::fs::write(&path, "uno\ndue\ntre\nquattro\n").unwrap();
+    assert!(reader.is_index_stale().unwrap());
+    reader.stale_index_policy(StaleIndexPolicy::Rebuild);
+    assert_eq!(reader.next_line().unwrap().unwrap(), "uno");
+    assert_eq!(reader.index_strategy(), IndexStrategy::Full);
+    assert_eq!(reader.line_starts.len(), 4);
+    assert!(!reader.is_index_stale().unwrap());
+
+    reader.build_index_every(2).unwrap();
+    std::fs::write(&path, "eins\nzwei\n").unwrap();
+    assert!(reader.is_index_stale().unwrap(), "A shorter file is stale");
+    reader.stale_index_policy(StaleIndexPolicy::Error);
+    assert_eq!(
+        reader.next_line().err().unwrap().kind(),
+        ErrorKind::InvalidData
+    );
+    assert_eq!(reader.index_strategy(), IndexStrategy::None);
+    assert_eq!(reader.next_line().unwrap().unwrap(), "eins");
+
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn test_skip_lines() {
+    for path in &[
+        "resources/test-file-lf",
+        "resources/test-file-crlf",
+        "resources/file-with-blank-line-at-the-beginning",
+        "resources/fatty_lipsum_lf",
+    ] {
+        let mut reader = EasyReader::new(File::open(path).unwrap()).unwrap();
+        let lines: Vec<String> = reader.iter().collect::<io::Result<_>>().unwrap();
+        let count = lines.len() as i64;
+
+        #[cfg(feature = "index")]
+        let indexes = [false, true];
+        #[cfg(not(feature = "index"))]
+        let indexes = [false];
+        for &indexed in &indexes {
+            #[cfg(feature = "index")]
+            if indexed {
+                reader.build_index().unwrap();
+            }
+
+            reader.bof();
+            assert!(reader.skip_lines(-1).unwrap().is_none());
+            assert!(reader.skip_lines(count + 1).unwrap().is_none());
+            assert_eq!(reader.skip_lines(1).unwrap(), Some(lines[0].clone()));
+            let mut line = 0;
+            for &delta in &[count - 1, 1 - count, count / 2, -(count / 2), count - 1] {
+                line += delta;
+                assert_eq!(
+                    reader.skip_lines(delta).unwrap(),
+                    Some(lines[line as usize].clone()),
+                    "{} (indexed: {}) line {}",
+                    path,
+                    indexed,
+                    line
+                );
+            }
+            let cursor = reader.save_cursor();
+            assert!(reader.skip_lines(1).unwrap().is_none());
+            assert!(reader.skip_lines(-count).unwrap().is_none());
+            assert_eq!(reader.save_cursor(), cursor, "A failed skip shouldn't move");
+            assert_eq!(reader.skip_lines(0).unwrap(), lines.last().cloned());
+
+            reader.eof();
+            assert_eq!(reader.skip_lines(-1).unwrap(), lines.last().cloned());
+            reader.eof();
+            assert_eq!(reader.skip_lines(-count).unwrap(), Some(lines[0].clone()));
+            assert_eq!(reader.prev_line().unwrap(), None);
+            assert_eq!(reader.next_line().unwrap(), lines.get(1).cloned());
+        }
+    }
+
+    // Only the lines which aren't ignored count
+    let mut reader = EasyReader::new(io::Cursor::new(b"a\n#c\nb\n#d\ne\n".to_vec())).unwrap();
+    reader.comment_prefix("#");
+    assert_eq!(reader.next_line().unwrap().unwrap(), "a");
+    assert_eq!(reader.skip_lines(1).unwrap().unwrap(), "b");
+    assert_eq!(reader.skip_lines(-1).unwrap().unwrap(), "a");
+    assert_eq!(reader.skip_lines(2).unwrap().unwrap(), "e");
+    assert_eq!(reader.skip_lines(1).unwrap(), None);
+    assert_eq!(reader.current_line().unwrap().unwrap(), "e");
+    reader.eof();
+    assert_eq!(reader.skip_lines(-3).unwrap().unwrap(), "a");
+}
+
+#[cfg(any(unix, windows))]
+#[test]
+fn test_get_line() {
+    for path in &[
+        "resources/test-file-crlf",
+        "resources/file-with-blank-line-at-the-beginning",
+        "resources/fatty_lipsum_lf",
+    ] {
+        let mut reader = EasyReader::new(File::open(path).unwrap()).unwrap();
+        let lines: Vec<String> = reader.iter().collect::<io::Result<_>>().unwrap();
+        reader.bof();
+
+        #[cfg(feature = "index")]
+        let strategies = [0, 1, 2];
+        #[cfg(not(feature = "index"))]
+        let strategies = [0];
+        for &strategy in &strategies {
+            #[cfg(feature = "index")]
+            match strategy {
+                1 => drop(reader.build_index_every(3).unwrap()),
+                2 => drop(reader.build_index().unwrap()),
+                _ => (),
+            }
+            let shared = std::sync::Arc::new(reader);
+            std::thread::scope(|scope| {
+                for thread in 0..3 {
+                    let (shared, lines) = (std::sync::Arc::clone(&shared), &lines);
+                    scope.spawn(move || {
+                        for (i, line) in lines.iter().enumerate().skip(thread) {
+                            assert_eq!(
+                                shared.get_line(i as u64).unwrap().as_ref(),
+                                Some(line),
+                                "{} line {} (strategy {})",
+                                path,
+                                i,
+                                strategy
+                            );
+                        }
+                        assert!(shared.get_line(lines.len() as u64).unwrap().is_none());
+                    });
+                }
+            });
+            reader = std::sync::Arc::try_unwrap(shared).ok().unwrap();
+            assert_eq!(reader.position().offset(), 0, "The cursor shouldn't move");
+        }
+    }
+
+    let reader = EasyReader::new(io::Cursor::new(b"one\r\ntwo".to_vec())).unwrap();
+    assert_eq!(reader.get_line(1).unwrap().unwrap(), "two");
+    assert_eq!(reader.get_line(0).unwrap().unwrap(), "one");
+}
+
+#[cfg(all(feature = "rayon", feature = "index"))]
+#[test]
+fn test_par_lines() {
+    use rayon::prelude::*;
+
+    let mut reader = EasyReader::new(File::open("resources/fatty_lipsum_lf").unwrap()).unwrap();
+    assert_eq!(
+        reader.par_lines().err().unwrap().kind(),
+        io::ErrorKind::InvalidInput,
+        "Parallel reads without an index should fail"
+    );
+
+    let lines: Vec<String> = reader.iter().collect::<io::Result<_>>().unwrap();
+    reader.bof().build_index().unwrap();
+    let par_lines: Vec<String> = reader
+        .par_lines()
+        .unwrap()
+        .collect::<io::Result<_>>()
+        .unwrap();
+    assert_eq!(par_lines, lines);
+
+    let longest = reader
+        .par_lines()
+        .unwrap()
+        .map(|line| line.unwrap().len())
+        .max();
+    assert_eq!(longest, lines.iter().map(String::len).max());
+    assert_eq!(reader.position().offset(), 0, "The cursor shouldn't move");
+}
+
+#[cfg(any(unix, windows))]
+#[test]
+fn test_split_into() {
+    for path in &[
+        "resources/test-file-crlf",
+        "resources/file-with-blank-line-at-the-beginning",
+        "resources/fatty_lipsum_lf",
+    ] {
+        let mut reader = EasyReader::new(File::open(path).unwrap()).unwrap();
+        let lines: Vec<String> = reader.iter().collect::<io::Result<_>>().unwrap();
+        reader.bof();
+
+        for &n in &[1, 2, 3, 7, 10_000] {
+            let partitions = reader.split_into(n).unwrap();
+            assert!(partitions.len() <= n);
+            assert!(partitions.len() <= lines.len());
+
+            let mut end = 0;
+            let mut partition_lines = Vec::new();
+            for mut partition in partitions {
+                let range = partition.file.range();
+                assert_eq!(range.start, end, "{} split into {}", path, n);
+                end = range.end;
+                while let Some(line) = partition.next_line().unwrap() {
+                    partition_lines.push(line);
+                }
+            }
+            assert_eq!(end, reader.file_size);
+            assert_eq!(partition_lines, lines, "{} split into {}", path, n);
+        }
+    }
+
+    let reader = EasyReader::new(File::open("resources/one-line-file").unwrap()).unwrap();
+    assert_eq!(reader.split_into(4).unwrap().len(), 1);
+    assert_eq!(
+        reader.split_into(0).err().unwrap().kind(),
+        io::ErrorKind::InvalidInput
+    );
+}
+
+#[test]
+fn test_next_lines_prev_lines() {
+    for path in &[
+        "resources/test-file-lf",
+        "resources/test-file-crlf",
+        "resources/file-with-blank-line-at-the-beginning",
+        "resources/fatty_lipsum_lf",
+        "resources/one-line-file",
+    ] {
+        let mut reader = EasyReader::new(File::open(path).unwrap()).unwrap();
+        let lines: Vec<String> = reader.iter().collect::<io::Result<_>>().unwrap();
+        let count = lines.len();
+
+        #[cfg(feature = "index")]
+        let indexes = [false, true];
+        #[cfg(not(feature = "index"))]
+        let indexes = [false];
+        for &indexed in &indexes {
+            #[cfg(feature = "index")]
+            if indexed {
+                reader.build_index().unwrap();
+            }
+
+            reader.bof();
+            assert!(reader.prev_lines(3).unwrap().is_empty());
+            assert_eq!(reader.next_lines(3).unwrap(), &lines[..count.min(3)]);
+            let cursor = reader.save_cursor();
+            assert!(reader.next_lines(0).unwrap().is_empty());
+            assert_eq!(reader.save_cursor(), cursor);
+
+            // The cursor ends where as many next_line() calls leave it
+            let mut expected = EasyReader::new(File::open(path).unwrap()).unwrap();
+            for _ in 0..count.min(3) {
+                expected.next_line().unwrap();
+            }
+            assert_eq!(reader.save_cursor(), expected.save_cursor());
+            assert_eq!(
+                reader.next_lines(count).unwrap(),
+                &lines[count.min(3)..],
+                "{} (indexed: {})",
+                path,
+                indexed
+            );
+            assert_eq!(reader.current_line().unwrap(), lines.last().cloned());
+            assert!(reader.next_lines(1).unwrap().is_empty());
+
+            let mut reversed = lines.clone();
+            reversed.reverse();
+            reader.eof();
+            assert_eq!(
+                reader.prev_lines(count + 1).unwrap(),
+                reversed,
+                "{} (indexed: {})",
+                path,
+                indexed
+            );
+            assert_eq!(reader.current_line().unwrap(), lines.first().cloned());
+            assert!(reader.prev_lines(1).unwrap().is_empty());
+
+            let middle_line = count / 2;
+            reader.skip_lines(middle_line as i64).unwrap();
+            let middle = reader.save_cursor();
+            let prev = reader.prev_lines(2).unwrap();
+            reader.restore_cursor(middle);
+            let mut one_by_one = Vec::new();
+            while one_by_one.len() < 2 {
+                match reader.prev_line().unwrap() {
+                    Some(line) => one_by_one.push(line),
+                    None => break,
+                }
+            }
+            assert_eq!(prev, one_by_one, "{} (indexed: {})", path, indexed);
+            let reached = middle_line - one_by_one.len();
+            assert_eq!(reader.next_lines(count).unwrap(), &lines[reached + 1..]);
+        }
+    }
+}
+
+#[test]
+fn test_head_tail() {
+    for path in &[
+        "resources/test-file-lf",
+        "resources/test-file-crlf",
+        "resources/file-with-blank-line-at-the-beginning",
+        "resources/fatty_lipsum_lf",
+        "resources/one-line-file",
+    ] {
+        let mut reader = EasyReader::new(File::open(path).unwrap()).unwrap();
+        let lines: Vec<String> = reader.iter().collect::<io::Result<_>>().unwrap();
+        let count = lines.len();
+
+        #[cfg(feature = "index")]
+        let indexes = [false, true];
+        #[cfg(not(feature = "index"))]
+        let indexes = [false];
+        for &indexed in &indexes {
+            #[cfg(feature = "index")]
+            if indexed {
+                reader.build_index().unwrap();
+            }
+
+            reader.bof();
+            reader.next_line().unwrap();
+            let cursor = reader.save_cursor();
+            for &n in &[0, 1, 3, count, count + 1] {
+                assert_eq!(
+                    reader.head(n).unwrap(),
+                    &lines[..n.min(count)],
+                    "{} (indexed: {})",
+                    path,
+                    indexed
+                );
+                assert_eq!(
+                    reader.tail(n).unwrap(),
+                    &lines[count - n.min(count)..],
+                    "{} (indexed: {})",
+                    path,
+                    indexed
+                );
+            }
+            assert_eq!(reader.save_cursor(), cursor, "The cursor shouldn't move");
+        }
+    }
+}
+
+#[test]
+fn test_tail_across_blocks() {
+    let lines: Vec<String> = (0..20_000).map(|i| format!("line {}", i)).collect();
+    for terminator in &["", "\n", "\r\n"] {
+        let text = lines.join("\r\n") + terminator;
+        let mut reader = EasyReader::new(io::Cursor::new(text.into_bytes())).unwrap();
+        reader.set_offset(100);
+        for &n in &[1, 9_000, 19_999, 20_000, 30_000] {
+            assert_eq!(
+                reader.tail(n).unwrap(),
+                &lines[lines.len() - n.min(lines.len())..],
+                "Last {} lines (terminator: {:?})",
+                n,
+                terminator
+            );
+        }
+        assert_eq!(reader.next_line().unwrap().unwrap(), "line 12");
+    }
+}
+
+#[test]
+fn test_delimiter() {
+    let records = ["./a b", "./multi\nline", "", "./with\r", "./last"];
+    for trailing in &["", "\0"] {
+        let bytes = records.join("\0") + trailing;
+        let mut reader = EasyReader::new(io::Cursor::new(bytes.into_bytes())).unwrap();
+        reader.next_line().unwrap();
+        reader.delimiter(b'\0');
+        assert_eq!(reader.current_offsets(), (0, 0), "Back to the BOF");
+
+        #[cfg(feature = "index")]
+        let indexes = [false, true];
+        #[cfg(not(feature = "index"))]
+        let indexes = [false];
+        for &indexed in &indexes {
+            #[cfg(feature = "index")]
+            if indexed {
+                reader.build_index().unwrap();
+            }
+
+            reader.bof();
+            let lines: Vec<String> = reader.iter().collect::<io::Result<_>>().unwrap();
+            assert_eq!(
+                lines, records,
+                "Trailing: {:?}, indexed: {}",
+                trailing, indexed
+            );
+            let mut lines: Vec<String> = reader.rev_lines().collect::<io::Result<_>>().unwrap();
+            lines.reverse();
+            assert_eq!(
+                lines, records,
+                "Trailing: {:?}, indexed: {}",
+                trailing, indexed
+            );
+
+            assert_eq!(reader.line_count().unwrap(), records.len() as u64);
+            assert_eq!(reader.head(2).unwrap(), &records[..2]);
+            assert_eq!(reader.tail(2).unwrap(), &records[3..]);
+            reader.bof();
+            assert_eq!(reader.skip_lines(4).unwrap().unwrap(), "./with\r");
+            assert_eq!(
+                reader.prev_lines(3).unwrap(),
+                ["", "./multi\nline", "./a b"]
+            );
+            assert_eq!(reader.get_line(1).unwrap().unwrap(), "./multi\nline");
+            #[cfg(feature = "rand")]
+            for _ in 0..10 {
+                let line = reader.random_line().unwrap().unwrap();
+                assert!(records.contains(&line.as_str()), "{:?}", line);
+            }
+        }
+    }
+}
+
+#[test]
+fn test_delimiter_bytes() {
+    let mut reader = EasyReader::new(io::Cursor::new(b"a\nb".to_vec())).unwrap();
+    assert_eq!(
+        reader.delimiter_bytes(b"").err().unwrap().kind(),
+        io::ErrorKind::InvalidInput
+    );
+
+    // "\n\n" overlaps itself: the runs of LFs are split from the left, like str::split() does
+    let cases = [
+        ("---\n", "one\n--\n---\ntwo\n---\n---\n-three"),
+        ("\n\n", "a\nb\n\n\nc\n\n\n\nd\r\n\ne"),
+        ("\r\n\r\n", "x\r\n\r\n\r\ny\r\n\r\n\r\n\r\nz\r\n"),
+        // Across scan blocks
+        ("\n\n", &("q\n\n\n".repeat(20000) + "end")),
+    ];
+    for &(delimiter, text) in &cases {
+        for trailing in &["", delimiter] {
+            let text = text.to_string() + trailing;
+            // A trailing terminator doesn't open a new line
+            let mut records: Vec<&str> = text.split(delimiter).collect();
+            if records.last() == Some(&"") {
+                records.pop();
+            }
+            let mut reader = EasyReader::new(io::Cursor::new(text.clone().into_bytes())).unwrap();
+            reader.chunk_size(3);
+            reader.delimiter_bytes(delimiter.as_bytes()).unwrap();
+
+            #[cfg(feature = "index")]
+            let indexes = [false, true];
+            #[cfg(not(feature = "index"))]
+            let indexes = [false];
+            for &indexed in &indexes {
+                #[cfg(feature = "index")]
+                if indexed {
+                    reader.build_index().unwrap();
+                }
+                let context = format!("Text: {:?}, indexed: {}", text, indexed);
+                reader.bof();
+                let lines: Vec<String> = reader.iter().collect::<io::Result<_>>().unwrap();
+                assert_eq!(lines, records, "{}", context);
+                let mut lines: Vec<String> = reader.rev_lines().collect::<io::Result<_>>().unwrap();
+                lines.reverse();
+                assert_eq!(lines, records, "{}", context);
+                assert_eq!(
+                    reader.line_count().unwrap(),
+                    records.len() as u64,
+                    "{}",
+                    context
+                );
+                assert_eq!(reader.head(2).unwrap(), &records[..2], "{}", context);
+                assert_eq!(
+                    reader.tail(2).unwrap(),
+                    &records[records.len() - 2..],
+                    "{}",
+                    context
+                );
+                reader.bof();
+                assert_eq!(
+                    reader.skip_lines(3).unwrap().unwrap(),
+                    records[2],
+                    "{}",
+                    context
+                );
+                assert_eq!(
+                    reader.prev_lines(2).unwrap(),
+                    [records[1], records[0]],
+                    "{}",
+                    context
+                );
+                reader.eof();
+                assert_eq!(
+                    reader.skip_lines(-2).unwrap().unwrap(),
+                    records[records.len() - 2],
+                    "{}",
+                    context
+                );
+                for i in (0..3).chain(records.len() - 1..records.len()) {
+                    assert_eq!(
+                        reader.get_line(i as u64).unwrap().unwrap(),
+                        records[i],
+                        "{}",
+                        context
+                    );
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn test_continuation() {
+    let file = "set a \\\n  b \\\n  c\nplain\r\nx \\\r\ny\n\\\nlast \\";
+    let logical = ["set a   b   c", "plain", "x y", "last "];
+    let mut reader = EasyReader::new(io::Cursor::new(file.as_bytes().to_vec())).unwrap();
+    reader.continuation("\\");
+
+    #[cfg(feature = "index")]
+    let indexes = [false, true];
+    #[cfg(not(feature = "index"))]
+    let indexes = [false];
+    for &indexed in &indexes {
+        #[cfg(feature = "index")]
+        if indexed {
+            reader.build_index().unwrap();
+        }
+
+        reader.bof();
+        let lines: Vec<String> = reader.iter().collect::<io::Result<_>>().unwrap();
+        assert_eq!(lines, logical, "Indexed: {}", indexed);
+        let mut lines: Vec<String> = reader.rev_lines().collect::<io::Result<_>>().unwrap();
+        lines.reverse();
+        assert_eq!(lines, logical, "Indexed: {}", indexed);
+
+        // Changing direction on a logical line
+        reader.bof();
+        assert_eq!(reader.next_line().unwrap().unwrap(), logical[0]);
+        assert_eq!(reader.current_line().unwrap().unwrap(), logical[0]);
+        assert_eq!(reader.next_line().unwrap().unwrap(), logical[1]);
+        assert_eq!(reader.next_line().unwrap().unwrap(), logical[2]);
+        assert_eq!(reader.prev_line().unwrap().unwrap(), logical[1]);
+        assert_eq!(reader.prev_line().unwrap().unwrap(), logical[0]);
+        assert_eq!(reader.prev_line().unwrap(), None);
+        assert_eq!(reader.next_line().unwrap().unwrap(), logical[1]);
+
+        // The batch reads join them too
+        reader.bof();
+        assert_eq!(reader.next_lines(10).unwrap(), logical);
+        assert_eq!(
+            reader.prev_lines(10).unwrap(),
+            [logical[2], logical[1], logical[0]]
+        );
+        assert_eq!(reader.head(2).unwrap(), logical[..2]);
+        assert_eq!(reader.tail(2).unwrap(), logical[2..]);
+
+        // From a line in the middle of a logical line
+        reader.set_offset(10);
+        assert_eq!(reader.current_line().unwrap().unwrap(), logical[0]);
+        #[cfg(feature = "rand")]
+        for _ in 0..10 {
+            let line = reader.random_line().unwrap().unwrap();
+            assert!(logical.contains(&line.as_str()), "{:?}", line);
+        }
+    }
+
+    // The joined lines can't be borrowed
+    reader.bof();
+    let err = reader.next_line_ref().unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::Unsupported);
+
+    reader.continuation("");
+    reader.bof();
+    assert_eq!(reader.next_line().unwrap().unwrap(), "set a \\");
+    assert_eq!(reader.next_line_ref().unwrap().unwrap(), "  b \\");
+
+    // Each line continues if it ends with the marker itself, in both directions
+    let mut reader = EasyReader::new(io::Cursor::new(b"a\\\\\n\nb\n".to_vec())).unwrap();
+    reader.continuation("\\");
+    let lines: Vec<String> = reader.iter().collect::<io::Result<_>>().unwrap();
+    assert_eq!(lines, ["a\\", "b"]);
+    let lines: Vec<String> = reader.rev_lines().collect::<io::Result<_>>().unwrap();
+    assert_eq!(lines, ["b", "a\\"]);
+
+    // The logical lines are ignored, not their lines
+    let file = "#c \\\nd\n\\\n\nkept \\\n#not a comment\nlast\n";
+    let mut reader = EasyReader::new(io::Cursor::new(file.as_bytes().to_vec())).unwrap();
+    reader
+        .continuation("\\")
+        .comment_prefix("#")
+        .skip_blank_lines(true);
+    let lines: Vec<String> = reader.iter().collect::<io::Result<_>>().unwrap();
+    assert_eq!(lines, ["kept #not a comment", "last"]);
+    let lines: Vec<String> = reader.rev_lines().collect::<io::Result<_>>().unwrap();
+    assert_eq!(lines, ["last", "kept #not a comment"]);
+    reader.set_offset(0);
+    assert_eq!(
+        reader.current_line().unwrap().unwrap(),
+        "kept #not a comment"
+    );
+    assert_eq!(reader.prev_line().unwrap(), None);
+}
+
+#[test]
+fn test_framed_reader() {
+    let payloads: [&[u8]; 4] = [b"first", b"", &[0xff; 200], b"\n\0last"];
+    for &prefix in &[
+        LengthPrefix::U32Le,
+        LengthPrefix::U32Be,
+        LengthPrefix::Varint,
+    ] {
+        let mut bytes = Vec::new();
+        for payload in &payloads {
+            let len = payload.len() as u32;
+            match prefix {
+                LengthPrefix::U32Le => bytes.extend_from_slice(&len.to_le_bytes()),
+                LengthPrefix::U32Be => bytes.extend_from_slice(&len.to_be_bytes()),
+                // 200 takes 2 bytes
+                LengthPrefix::Varint if len >= 0x80 => {
+                    bytes.extend_from_slice(&[len as u8 | 0x80, (len >> 7) as u8])
+                }
+                LengthPrefix::Varint => bytes.push(len as u8),
+            }
+            bytes.extend_from_slice(payload);
+        }
+
+        let mut reader = FramedReader::new(io::Cursor::new(bytes.clone()), prefix).unwrap();
+        assert_eq!(reader.prev_record().unwrap(), None);
+        for payload in &payloads {
+            assert_eq!(
+                reader.next_record().unwrap().unwrap(),
+                *payload,
+                "{:?}",
+                prefix
+            );
+        }
+        assert_eq!(reader.next_record().unwrap(), None);
+        assert_eq!(reader.current_record().unwrap().unwrap(), payloads[3]);
+        for payload in payloads[..3].iter().rev() {
+            assert_eq!(
+                reader.prev_record().unwrap().unwrap(),
+                *payload,
+                "{:?}",
+                prefix
+            );
+        }
+        assert_eq!(reader.prev_record().unwrap(), None);
+
+        let mut reader = FramedReader::new(io::Cursor::new(bytes.clone()), prefix).unwrap();
+        assert_eq!(reader.record(2).unwrap().unwrap(), payloads[2]);
+        assert_eq!(reader.record(4).unwrap(), None);
+        assert_eq!(reader.current_record().unwrap().unwrap(), payloads[2]);
+        reader.eof().unwrap();
+        assert_eq!(
+            reader.current_offsets(),
+            (bytes.len() as u64, bytes.len() as u64)
+        );
+        assert_eq!(reader.prev_record().unwrap().unwrap(), payloads[3]);
+        assert_eq!(reader.records_count().unwrap(), 4);
+
+        // A payload shorter than its prefix says
+        let mut reader =
+            FramedReader::new(io::Cursor::new(bytes[..bytes.len() - 1].to_vec()), prefix).unwrap();
+        assert_eq!(
+            reader.build_index().err().unwrap().kind(),
+            io::ErrorKind::UnexpectedEof
+        );
+        assert_eq!(reader.next_record().unwrap().unwrap(), payloads[0]);
+    }
+}
+
+#[test]
+fn test_boundary() {
+    // Pretty-printed JSON documents, one after the other
+    struct Documents;
+    impl Boundary for Documents {
+        fn terminator_len(&self) -> usize {
+            1
+        }
+        fn window(&self) -> (usize, usize) {
+            (2, 1)
+        }
+        fn is_boundary(&self, before: &[u8], after: &[u8]) -> bool {
+            before.ends_with(b"}\n") && after.first().is_none_or(|&byte| byte == b'{')
+        }
+    }
+
+    let documents = ["{\n  \"a\": 1\n}", "{\"b\": [\n  {}\n]}", "{}", "{\n}"];
+    for trailing in &["", "\n"] {
+        let file = documents.join("\n") + trailing;
+        let mut reader = EasyReader::new(io::Cursor::new(file.into_bytes())).unwrap();
+        reader.chunk_size(4);
+        reader.boundary(Documents).unwrap();
+
+        #[cfg(feature = "index")]
+        let indexes = [false, true];
+        #[cfg(not(feature = "index"))]
+        let indexes = [false];
+        for &indexed in &indexes {
+            #[cfg(feature = "index")]
+            if indexed {
+                reader.build_index().unwrap();
+            }
+
+            reader.bof();
+            let lines: Vec<String> = reader.iter().collect::<io::Result<_>>().unwrap();
+            assert_eq!(lines, documents, "Indexed: {}", indexed);
+            let mut lines: Vec<String> = reader.rev_lines().collect::<io::Result<_>>().unwrap();
+            lines.reverse();
+            assert_eq!(lines, documents, "Indexed: {}", indexed);
+
+            assert_eq!(reader.line_count().unwrap(), documents.len() as u64);
+            assert_eq!(reader.tail(2).unwrap(), &documents[2..]);
+            reader.bof();
+            assert_eq!(reader.skip_lines(2).unwrap().unwrap(), documents[1]);
+            assert_eq!(reader.get_line(3).unwrap().unwrap(), documents[3]);
+        }
+    }
+
+    // The bytes around the offsets checked are taken from the chunks read
+    struct Lf;
+    impl Boundary for Lf {
+        fn terminator_len(&self) -> usize {
+            1
+        }
+        fn is_boundary(&self, before: &[u8], _after: &[u8]) -> bool {
+            before == b"\n"
+        }
+    }
+    let file = std::fs::read("resources/fatty_lipsum_lf").unwrap();
+    let mut seeks = Vec::new();
+    for boundary in [false, true] {
+        let mut reader = EasyReader::new(io::Cursor::new(file.clone())).unwrap();
+        if boundary {
+            reader.boundary(Lf).unwrap();
+        }
+        reader.reset_stats();
+        let lines = reader.iter().count();
+        reader.eof();
+        while reader.prev_line().unwrap().is_some() {}
+        assert_eq!(lines, 1107);
+        seeks.push(reader.stats().seeks);
+    }
+    assert!(seeks[1] <= seeks[0] * 2, "{:?}", seeks);
+
+    struct Empty;
+    impl Boundary for Empty {
+        fn terminator_len(&self) -> usize {
+            0
+        }
+        fn is_boundary(&self, _before: &[u8], _after: &[u8]) -> bool {
+            true
+        }
+    }
+    let mut reader = EasyReader::new(io::Cursor::new(b"a".to_vec())).unwrap();
+    assert_eq!(
+        reader.boundary(Empty).err().unwrap().kind(),
+        io::ErrorKind::InvalidInput
+    );
+}
+
+#[test]
+fn test_keep_terminators() {
+    for file in &["a\r\nb\n\nc\r\r\n", "x\ny\r\nlast"] {
+        let mut reader = EasyReader::new(io::Cursor::new(file.as_bytes().to_vec())).unwrap();
+        reader.keep_terminators(true);
+        let lines: Vec<String> = reader.iter().collect::<io::Result<_>>().unwrap();
+        assert_eq!(lines.concat(), *file);
+        let mut lines: Vec<String> = reader.rev_lines().collect::<io::Result<_>>().unwrap();
+        lines.reverse();
+        assert_eq!(lines.concat(), *file);
+        reader.bof();
+        assert_eq!(reader.next_lines(10).unwrap().concat(), *file);
+        assert_eq!(reader.head(10).unwrap(), lines);
+        assert_eq!(reader.tail(10).unwrap(), lines);
+        #[cfg(feature = "rand")]
+        {
+            let mut sample = reader.sample(10).unwrap();
+            sample.sort();
+            let mut sorted = lines.clone();
+            sorted.sort();
+            assert_eq!(sample, sorted);
+        }
+        reader.bof();
+        let mut borrowed = String::new();
+        while let Some(line) = reader.next_line_ref().unwrap() {
+            borrowed.push_str(line);
+        }
+        assert_eq!(borrowed, *file);
+        reader.bof();
+        reader.next_line().unwrap();
+        assert_eq!(
+            reader.current_offsets().1,
+            1,
+            "The offsets exclude the terminator"
+        );
+    }
+
+    let mut reader = EasyReader::new(io::Cursor::new(b"one\0two\0".to_vec())).unwrap();
+    reader.delimiter(b'\0').keep_terminators(true);
+    assert_eq!(reader.next_line().unwrap().unwrap(), "one\0");
+    assert_eq!(reader.next_line().unwrap().unwrap(), "two\0");
+    reader.keep_terminators(false);
+    assert_eq!(reader.current_line().unwrap().unwrap(), "two");
+}
+
+#[test]
+fn test_line_ending() {
+    let mut reader = EasyReader::new(io::Cursor::new(b"a\r\n\r\nb\nc".to_vec())).unwrap();
+    assert_eq!(reader.line_ending().unwrap(), None);
+    let mut endings = Vec::new();
+    while reader.next_line().unwrap().is_some() {
+        endings.push(reader.line_ending().unwrap().unwrap());
+    }
+    assert_eq!(
+        endings,
+        [
+            LineEnding::CrLf,
+            LineEnding::CrLf,
+            LineEnding::Lf,
+            LineEnding::Unterminated
+        ]
+    );
+    reader.eof();
+    assert_eq!(reader.line_ending().unwrap(), None);
+    reader.prev_line().unwrap();
+    assert_eq!(
+        reader.line_ending().unwrap(),
+        Some(LineEnding::Unterminated)
+    );
+
+    let mut reader = EasyReader::new(io::Cursor::new(b"a\0b".to_vec())).unwrap();
+    reader.delimiter(b'\0').next_line().unwrap();
+    assert_eq!(reader.line_ending().unwrap(), Some(LineEnding::Other));
+}
+
+#[test]
+fn test_cr_line_endings() {
+    let lines = ["first", "", "third", "last"];
+    for trailing in &["", "\r"] {
+        let file = lines.join("\r") + trailing;
+        let mut reader = EasyReader::new(io::Cursor::new(file.into_bytes())).unwrap();
+        assert_eq!(reader.detect_line_endings().unwrap(), LineEnding::Cr);
+
+        let read: Vec<String> = reader.iter().collect::<io::Result<_>>().unwrap();
+        assert_eq!(read, lines);
+        let mut read: Vec<String> = reader.rev_lines().collect::<io::Result<_>>().unwrap();
+        read.reverse();
+        assert_eq!(read, lines);
+        assert_eq!(reader.line_count().unwrap(), 4);
+        reader.bof();
+        assert_eq!(reader.skip_lines(3).unwrap().unwrap(), "third");
+        assert_eq!(reader.line_ending().unwrap(), Some(LineEnding::Cr));
+        assert_eq!(reader.prev_line().unwrap().unwrap(), "");
+    }
+
+    for &(file, ending) in &[
+        ("a\r\nb", LineEnding::CrLf),
+        ("a\nb\rc", LineEnding::Lf),
+        ("a", LineEnding::Unterminated),
+    ] {
+        let mut reader = EasyReader::new(io::Cursor::new(file.as_bytes().to_vec())).unwrap();
+        assert_eq!(reader.detect_line_endings().unwrap(), ending);
+        assert_eq!(reader.next_line().unwrap().unwrap(), "a");
+    }
+}
+
+#[cfg(feature = "encoding")]
+#[test]
+fn test_utf16() {
+    use encoding_rs::{UTF_16BE, UTF_16LE};
+
+    // In UTF-16LE "ਊĀ" is 0A 0A 00 01, with an LF (0A 00) across its code units
+    let text = "ਊĀ line\n\ncrlf\r\nlast";
+    let lines = ["ਊĀ line", "", "crlf", "last"];
+    for &encoding in &[UTF_16LE, UTF_16BE] {
+        for trailing in &["", "\n"] {
+            let mut bytes = Vec::new();
+            for unit in Some('\u{feff}')
+                .into_iter()
+                .chain(text.chars())
+                .chain(trailing.chars())
+            {
+                let mut units = [0; 2];
+                for unit in unit.encode_utf16(&mut units) {
+                    if encoding == UTF_16LE {
+                        bytes.extend_from_slice(&unit.to_le_bytes());
+                    } else {
+                        bytes.extend_from_slice(&unit.to_be_bytes());
+                    }
+                }
+            }
+            let mut reader = EasyReader::new(io::Cursor::new(bytes)).unwrap();
+            reader.chunk_size(3);
+            reader.encoding(encoding).unwrap();
+
+            #[cfg(feature = "index")]
+            let indexes = [false, true];
+            #[cfg(not(feature = "index"))]
+            let indexes = [false];
+            for &indexed in &indexes {
+                #[cfg(feature = "index")]
+                if indexed {
+                    reader.build_index().unwrap();
+                }
+                let context = format!("{}, trailing: {:?}", encoding.name(), trailing);
+
+                reader.bof();
+                let read: Vec<String> = reader.iter().collect::<io::Result<_>>().unwrap();
+                assert_eq!(read, lines, "{}", context);
+                let mut read: Vec<String> = reader.rev_lines().collect::<io::Result<_>>().unwrap();
+                read.reverse();
+                assert_eq!(read, lines, "{}", context);
+
+                assert_eq!(reader.line_count().unwrap(), 4, "{}", context);
+                assert_eq!(reader.tail(2).unwrap(), &lines[2..], "{}", context);
+                assert_eq!(reader.head(1).unwrap(), &lines[..1], "{}", context);
+                assert_eq!(reader.get_line(2).unwrap().unwrap(), "crlf", "{}", context);
+                reader.bof();
+                assert_eq!(
+                    reader.skip_lines(3).unwrap().unwrap(),
+                    "crlf",
+                    "{}",
+                    context
+                );
+                assert_eq!(reader.line_ending().unwrap(), Some(LineEnding::CrLf));
+            }
+        }
+    }
+
+    let mut reader = EasyReader::new(io::Cursor::new(vec![0x00, 0xd8, b'\n', 0])).unwrap();
+    reader.encoding(UTF_16LE).unwrap();
+    assert!(reader.next_line().is_err(), "A lone surrogate");
+}
+
+#[cfg(feature = "encoding")]
+#[test]
+fn test_encoding() {
+    use encoding_rs::{ISO_2022_JP, SHIFT_JIS, UTF_8, WINDOWS_1252};
+
+    let mut reader = EasyReader::new(io::Cursor::new(b"caf\xe9\r\n\xa3 5\n".to_vec())).unwrap();
+    assert!(reader.next_line().is_err(), "Not UTF-8");
+    reader.encoding(WINDOWS_1252).unwrap();
+    assert_eq!(reader.next_line().unwrap().unwrap(), "café");
+    assert_eq!(reader.next_line().unwrap().unwrap(), "£ 5");
+    assert_eq!(reader.prev_line().unwrap().unwrap(), "café");
+    assert_eq!(reader.tail(1).unwrap(), ["£ 5"]);
+    assert_eq!(
+        reader.next_line_as::<Vec<u8>>().unwrap().unwrap(),
+        "£ 5".as_bytes()
+    );
+    // The transcoded lines aren't in the buffer
+    reader.bof();
+    let err = reader.next_line_ref().unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::Unsupported);
+    reader.encoding(UTF_8).unwrap();
+    assert!(reader.next_line_ref().is_err(), "Not UTF-8");
+
+    // "表" ends with 0x5c (a backslash in ASCII)
+    let (bytes, _, _) = SHIFT_JIS.encode("表\nテスト\n");
+    let mut reader = EasyReader::new(io::Cursor::new(bytes.into_owned())).unwrap();
+    reader.encoding(SHIFT_JIS).unwrap();
+    let lines: Vec<String> = reader.rev_lines().collect::<io::Result<_>>().unwrap();
+    assert_eq!(lines, ["テスト", "表"]);
+
+    reader.encoding(UTF_8).unwrap();
+    assert!(reader.next_line().is_err());
+    assert_eq!(
+        reader.encoding(ISO_2022_JP).err().unwrap().kind(),
+        io::ErrorKind::InvalidInput
+    );
+}
+
+#[test]
+fn test_line_into() {
+    let mut reader =
+        EasyReader::new(io::Cursor::new(b"first\r\n\nthird \xff\nlast".to_vec())).unwrap();
+    let mut line = String::from("> ");
+    assert_eq!(reader.next_line_into(&mut line).unwrap(), Some(5));
+    assert_eq!(line, "> first");
+    assert_eq!(reader.next_line_into(&mut line).unwrap(), Some(0));
+    assert_eq!(line, "> first");
+    assert!(reader.next_line_into(&mut line).is_err(), "Not UTF-8");
+    assert_eq!(line, "> first");
+
+    let mut bytes = Vec::new();
+    assert_eq!(reader.current_line_into(&mut bytes).unwrap(), Some(7));
+    assert_eq!(reader.next_line_into(&mut bytes).unwrap(), Some(4));
+    assert_eq!(bytes, b"third \xfflast");
+    assert_eq!(reader.next_line_into(&mut bytes).unwrap(), None);
+
+    line.clear();
+    reader.bof();
+    assert_eq!(reader.prev_line_into(&mut line).unwrap(), None);
+    reader.eof();
+    assert_eq!(reader.prev_line_into(&mut line).unwrap(), Some(4));
+    assert_eq!(line, "last");
+}
+
+#[test]
+fn test_line_str() {
+    let mut reader =
+        EasyReader::new(io::Cursor::new(b"a somewhat long line\nb\n\xff".to_vec())).unwrap();
+    assert_eq!(
+        reader.current_line_str().unwrap(),
+        Some("a somewhat long line")
+    );
+    assert_eq!(reader.next_line_str().unwrap(), Some("b"));
+    assert!(reader.next_line_str().is_err(), "Not UTF-8");
+    assert_eq!(reader.prev_line_str().unwrap(), Some("b"));
+    assert_eq!(
+        reader.prev_line_str().unwrap(),
+        Some("a somewhat long line")
+    );
+    assert_eq!(reader.prev_line_str().unwrap(), None);
+
+    let mut longest = 0;
+    reader.bof();
+    while let Ok(Some(line)) = reader.next_line_str() {
+        longest = longest.max(line.len());
+    }
+    assert_eq!(longest, 20);
+}
+
+#[test]
+fn test_line_info() {
+    let mut reader = EasyReader::new(io::Cursor::new(b"first\r\nsecond\nlast".to_vec())).unwrap();
+    let line = reader.next_line_info().unwrap().unwrap();
+    assert_eq!(line.text, "first");
+    assert_eq!((line.start, line.end), (0, 5));
+    assert_eq!(line.ending, LineEnding::CrLf);
+    assert_eq!(
+        reader.next_line_info().unwrap().unwrap().ending,
+        LineEnding::Lf
+    );
+    let line = reader.next_line_info().unwrap().unwrap();
+    assert_eq!((line.text.as_str(), line.start, line.end), ("last", 14, 18));
+    assert_eq!(line.ending, LineEnding::Unterminated);
+    assert_eq!(reader.next_line_info().unwrap(), None);
+
+    #[cfg(feature = "index")]
+    {
+        assert_eq!(line.number, None);
+        reader.build_index().unwrap();
+        reader.eof();
+        let line = reader.prev_line_info().unwrap().unwrap();
+        assert_eq!((line.text.as_str(), line.number), ("last", Some(2)));
+        assert_eq!(reader.current_line_info().unwrap(), Some(line));
+    }
+}
+
+#[test]
+fn test_max_line_length() {
+    let mut data = b"short\r\n".to_vec();
+    data.extend(vec![b'x'; 100_000]);
+    data.extend(b"\nend\n");
+    let mut reader = EasyReader::new(io::Cursor::new(data)).unwrap();
+    reader.max_line_length(1000);
+
+    assert_eq!(reader.next_line().unwrap().unwrap(), "short");
+    let err = reader.next_line().err().unwrap();
+    assert_eq!(err.kind(), ErrorKind::InvalidData);
+    assert!(err.to_string().contains("byte: 7"));
+    assert_eq!(reader.next_line().unwrap().unwrap(), "end");
+    assert!(reader.prev_line().is_err());
+    assert_eq!(reader.prev_line().unwrap().unwrap(), "short");
+
+    assert_eq!(reader.head(1).unwrap(), vec!["short"]);
+    assert!(reader.head(2).is_err());
+    assert_eq!(reader.tail(1).unwrap(), vec!["end"]);
+    reader.eof();
+    assert!(reader.prev_lines(2).is_err());
+
+    reader.max_line_length(5).bof();
+    assert_eq!(reader.next_lines(1).unwrap(), vec!["short"]);
+    reader.max_line_length(usize::MAX);
+    assert_eq!(reader.next_line().unwrap().unwrap().len(), 100_000);
+}
+
+#[test]
+fn test_lenient_iter() {
+    let data = b"one\n\xff\xfe\ntwo\nthree is long\n\xc3\n".to_vec();
+    let mut reader = EasyReader::new(io::Cursor::new(data)).unwrap();
+    reader.max_line_length(5);
+
+    let mut offsets = Vec::new();
+    let mut lines = reader
+        .lenient_iter()
+        .on_skip(|err| offsets.push(err.to_string()));
+    assert_eq!(
+        lines.by_ref().collect::<io::Result<Vec<_>>>().unwrap(),
+        vec!["one", "two"]
+    );
+    assert_eq!(lines.skipped(), 3);
+    drop(lines);
+    assert_eq!(offsets.len(), 3);
+    assert!(offsets[1].contains("byte: 11"));
+}
+
+#[test]
+fn test_reader_error() {
+    let err = EasyReader::new(io::Cursor::new(Vec::new())).err().unwrap();
+    assert_eq!(err.kind(), ErrorKind::UnexpectedEof);
+    assert_eq!(ReaderError::of(&err), Some(&ReaderError::EmptyFile));
+
+    let mut reader = EasyReader::new(io::Cursor::new(b"ok\nnot \xff ok\n".to_vec())).unwrap();
+    reader.next_line().unwrap();
+    let err = reader.next_line().err().unwrap();
+    match ReaderError::of(&err) {
+        Some(ReaderError::InvalidUtf8 {
+            offset,
+            len,
+            error,
+            bytes,
+        }) => {
+            assert_eq!((*offset, *len, error.valid_up_to()), (3, 8, 4));
+            assert_eq!(bytes, b"not \xff ok");
+        }
+        other => panic!("Unexpected error: {:?}", other),
+    }
+    assert_eq!(err.to_string(), ReaderError::of(&err).unwrap().to_string());
+
+    reader.max_line_length(4).bof();
+    reader.next_line().unwrap();
+    let err = reader.next_line().err().unwrap();
+    let reader_err = ReaderError::of(&err).unwrap();
+    assert_eq!(reader_err.offset(), Some(3));
+    assert_eq!(reader_err.kind(), err.kind());
+
+    let mut framed =
+        FramedReader::new(io::Cursor::new(vec![2, 0, 0, 0, 1]), LengthPrefix::U32Le).unwrap();
+    let err = framed.next_record().err().unwrap();
+    assert_eq!(
+        ReaderError::of(&err),
+        Some(&ReaderError::TruncatedRecord { offset: 0 })
+    );
+    assert!(ReaderError::of(&io::Error::other("unrelated")).is_none());
+
+    let mut reader = EasyReader::new(io::Cursor::new(b"\xfe\xff\r\n".to_vec())).unwrap();
+    let err = reader.next_line_str().err().unwrap();
+    let bytes = ReaderError::of(&err).and_then(ReaderError::bytes);
+    assert_eq!(bytes, Some(&b"\xfe\xff"[..]));
+
+    #[cfg(feature = "encoding")]
+    {
+        reader.encoding(encoding_rs::SHIFT_JIS).unwrap();
+        let err = reader.current_line().err().unwrap();
+        match ReaderError::of(&err) {
+            Some(ReaderError::InvalidEncoding {
+                encoding, bytes, ..
+            }) => assert_eq!((*encoding, &bytes[..]), ("Shift_JIS", &b"\xfe\xff"[..])),
+            other => panic!("Unexpected error: {:?}", other),
+        }
+    }
+}
+
+#[test]
+fn test_chunk_cache() {
+    let mut data = Vec::new();
+    for i in 0..5000 {
+        data.extend(format!("line {}\r\n", i).as_bytes());
+    }
+    let mut reader = EasyReader::new(io::Cursor::new(data)).unwrap();
+    reader.chunk_cache(4);
+    let lines: Vec<String> = reader.iter().collect::<io::Result<_>>().unwrap();
+    assert_eq!(lines.len(), 5000);
+    assert_eq!(lines[4321], "line 4321");
+    let rev: Vec<String> = reader.rev_lines().collect::<io::Result<_>>().unwrap();
+    assert!(rev.iter().rev().eq(lines.iter()));
+    assert_eq!(reader.tail(2).unwrap(), ["line 4998", "line 4999"]);
+
+    // Appended data isn't hidden by the cached end of the file
+    reader.file.get_mut().extend(b"line 5000\n");
+    assert!(reader.refresh().unwrap());
+    reader.eof();
+    assert_eq!(reader.prev_line().unwrap().unwrap(), "line 5000");
+
+    #[cfg(feature = "faulty")]
+    {
+        let file = File::open("resources/test-file-lf").unwrap();
+        let mut reader = EasyReader::new(FaultyReader::new(file).unwrap()).unwrap();
+        reader.chunk_cache(2);
+        reader.next_line().unwrap();
+        reader.next_line().unwrap();
+        let calls = reader.file.calls();
+        for _ in 0..10 {
+            reader.prev_line().unwrap();
+            reader.next_line().unwrap();
+        }
+        assert_eq!(reader.file.calls(), calls);
+    }
+}
+
+#[test]
+fn test_chunk_buffer_reuse() {
+    let mut reader = EasyReader::new(File::open("resources/test-file-lf").unwrap()).unwrap();
+    reader.chunk_size(16).chunk_cache(2);
+    let mut line = String::new();
+    while reader.next_line_into(&mut line).unwrap().is_some() {}
+    let (chunk, buffer) = (reader.chunk_buffer.as_ptr(), reader.line_buffer.as_ptr());
+    reader.bof();
+    let mut lines = Vec::new();
+    while reader.next_line_into(&mut line).unwrap().is_some() {
+        lines.push(line.clone());
+        line.clear();
+    }
+    assert_eq!(lines[4], "EEEE  EEEEE  EEEE  EEEEE");
+    assert_eq!(reader.chunk_buffer.as_ptr(), chunk);
+    assert_eq!(reader.line_buffer.as_ptr(), buffer);
+}
+
+#[cfg(feature = "index")]
+#[test]
+fn test_bulk_index() {
+    for data in [&b"a\r\n\nbb\r\n"[..], b"\n\n\nlast", b"\n", b"x\r"] {
+        let mut reader = EasyReader::new(io::Cursor::new(data.to_vec())).unwrap();
+        let mut scanned = Vec::new();
+        while reader.next_line().unwrap().is_some() {
+            scanned.push(reader.current_offsets());
+        }
+        reader.build_index().unwrap();
+        assert_eq!(reader.line_count().unwrap(), scanned.len() as u64);
+        reader.bof();
+        let mut indexed = Vec::new();
+        while reader.next_line().unwrap().is_some() {
+            indexed.push(reader.current_offsets());
+        }
+        assert_eq!(indexed, scanned, "{:?}", data);
+    }
+
+    // Across the blocks scanned
+    let line = "0123456789abcdef\n".repeat(1 << 16);
+    let mut reader = EasyReader::new(io::Cursor::new(format!("{}{}", line, line))).unwrap();
+    let lines = reader.line_count().unwrap();
+    assert_eq!(lines, 2 << 16);
+    reader.build_index().unwrap();
+    assert_eq!(reader.index().unwrap().len() as u64, lines);
+    assert_eq!(
+        reader.get_line(lines - 1).unwrap().unwrap(),
+        "0123456789abcdef"
+    );
+}
+
+#[test]
+fn test_iter_read_ahead() {
+    let mut data = String::new();
+    for i in 0..3000 {
+        data.push_str(&format!("{}{}\r\n", i, "-".repeat(i % 50)));
+    }
+    let mut reader = EasyReader::new(io::Cursor::new(data.clone().into_bytes())).unwrap();
+    let mut expected = Vec::new();
+    while let Some(line) = reader.next_line().unwrap() {
+        expected.push(line);
+    }
+
+    reader.bof();
+    assert_eq!(reader.iter().take(1500).count(), 1500);
+    assert_eq!(reader.current_line().unwrap().unwrap(), expected[1499]);
+    let rest: Vec<String> = reader.iter().collect::<io::Result<_>>().unwrap();
+    assert_eq!(rest[..], expected[1500..]);
+
+    reader.set_offset(data.find("\n2000").unwrap() as u64 + 1);
+    assert_eq!(reader.iter().next().unwrap().unwrap(), expected[2000]);
+    assert_eq!(reader.prev_line().unwrap().unwrap(), expected[1999]);
+
+    // The lines before an invalid one are returned first
+    let mut bytes = data.into_bytes();
+    bytes.extend(b"\xff\nafter\n");
+    let mut reader = EasyReader::new(io::Cursor::new(bytes)).unwrap();
+    let lines: Vec<_> = reader.iter().collect();
+    assert_eq!(lines.len(), 3001);
+    assert!(lines[3000].is_err());
+    assert_eq!(reader.current_line().unwrap().unwrap(), expected[2999]);
+}
+
+#[test]
+fn test_advise() {
+    let file = File::open("resources/test-file-lf").unwrap();
+    let mut reader = EasyReader::new(file).unwrap();
+    reader.advise(AccessPattern::Sequential).unwrap();
+    assert_eq!(reader.next_line().unwrap().unwrap(), "AAAA AAAA");
+    reader.advise(AccessPattern::Random).unwrap();
+    reader.eof();
+    assert!(reader.prev_line().unwrap().is_some());
+    reader.advise(AccessPattern::DontNeed).unwrap();
+
+    let mut reader = EasyReader::new(io::Cursor::new(vec![b'x'; 10_000])).unwrap();
+    for pattern in [AccessPattern::WillNeed, AccessPattern::Normal] {
+        reader.advise(pattern).unwrap();
+    }
+    assert_eq!(reader.next_line().unwrap().unwrap().len(), 10_000);
+}
+
+#[cfg(unix)]
+#[test]
+fn test_advise_error() {
+    use std::{
+        os::unix::io::OwnedFd,
+        process::{Command, Stdio},
+    };
+
+    let file = File::open("resources/test-file-lf").unwrap();
+    let mut reader = EasyReader::new(file).unwrap();
+    // A pipe can't be advised: the error of posix_fadvise() is returned, where there are hints
+    let mut child = Command::new("true").stdout(Stdio::piped()).spawn().unwrap();
+    reader.file = File::from(OwnedFd::from(child.stdout.take().unwrap()));
+    let advised = reader.advise(AccessPattern::Sequential).map(|_| ());
+    if cfg!(any(
+        target_os = "linux",
+        target_os = "android",
+        target_os = "freebsd"
+    )) {
+        assert_eq!(advised.unwrap_err().raw_os_error(), Some(libc::ESPIPE));
+    } else {
+        assert!(advised.is_ok(), "No hints, nothing to fail");
+    }
+    child.wait().unwrap();
+}
+
+#[test]
+fn test_stats() {
+    let file = File::open("resources/test-file-lf").unwrap();
+    let mut reader = EasyReader::new(file).unwrap();
+    assert_eq!(
+        reader.stats(),
+        ReaderStats {
+            seeks: 1,
+            ..ReaderStats::default()
+        }
+    );
+
+    reader.next_line().unwrap().unwrap();
+    reader.next_line().unwrap().unwrap();
+    let stats = reader.stats();
+    assert_eq!(stats.lines_decoded, 2);
+    assert!(stats.reads > 0 && stats.seeks > 1 && stats.bytes_read > 0);
+    assert_eq!((stats.cache_hits, stats.cache_misses), (0, 0));
+
+    reader.reset_stats().chunk_cache(4).bof();
+    while reader.next_line().unwrap().is_some() {}
+    reader.bof();
+    while reader.next_line().unwrap().is_some() {}
+    let stats = reader.stats();
+    assert_eq!(stats.lines_decoded, 10);
+    assert_eq!(stats.cache_misses, 1);
+    assert!(stats.cache_hits > 0);
+    // Only the block cached was read from the file
+    assert_eq!(stats.bytes_read, reader.file_size);
+
+    reader.reset_stats();
+    assert_eq!(reader.next_lines(3).unwrap().len(), 0);
+    reader.bof();
+    assert_eq!(reader.next_lines(3).unwrap().len(), 3);
+    assert_eq!(reader.stats().lines_decoded, 3);
+}
+
+#[cfg(feature = "rand")]
+#[test]
+fn test_seeded_rng() {
+    use rand::SeedableRng;
+
+    fn draws(reader: &mut EasyReader<File>) -> Vec<String> {
+        (0..20)
+            .map(|_| reader.random_line().unwrap().unwrap())
+            .collect()
+    }
+
+    let mut reader = EasyReader::new(File::open("resources/test-file-lf").unwrap()).unwrap();
+    let first = draws(reader.seed(42));
+    assert_eq!(draws(reader.seed(42)), first);
+    assert_ne!(draws(reader.seed(43)), first);
+    let mut other = EasyReader::new(File::open("resources/test-file-lf").unwrap()).unwrap();
+    assert_eq!(
+        draws(other.rng(rand::rngs::StdRng::seed_from_u64(42))),
+        first
+    );
+
+    #[cfg(feature = "index")]
+    {
+        reader.build_index().unwrap();
+        let first = draws(reader.seed(7));
+        assert_eq!(draws(reader.seed(7)), first);
+        // All the lines come up
+        let mut seen = draws(reader.seed(7));
+        seen.extend(draws(&mut reader));
+        seen.sort();
+        seen.dedup();
+        assert_eq!(seen.len(), 5);
+    }
+    reader.thread_rng();
+    assert!(reader.random_line().unwrap().is_some());
+}
+
+#[cfg(feature = "rand")]
+#[test]
+fn test_random_line_with_rng() {
+    use rand::{rngs::StdRng, SeedableRng};
+
+    let mut reader = EasyReader::new(File::open("resources/test-file-lf").unwrap()).unwrap();
+    let draws = |reader: &mut EasyReader<File>, rng: &mut StdRng| -> Vec<String> {
+        (0..20)
+            .map(|_| reader.random_line_with_rng(rng).unwrap().unwrap())
+            .collect()
+    };
+    reader.seed(1);
+    let first = draws(&mut reader, &mut StdRng::seed_from_u64(5));
+    // The RNG of the reader isn't used
+    let own = reader.random_line().unwrap().unwrap();
+    reader.seed(1);
+    assert_eq!(reader.random_line().unwrap().unwrap(), own);
+    assert_eq!(draws(&mut reader, &mut StdRng::seed_from_u64(5)), first);
+    let mut rng: Box<dyn rand::RngCore> = Box::new(StdRng::seed_from_u64(5));
+    assert_eq!(
+        reader.random_line_with_rng(&mut *rng).unwrap().unwrap(),
+        first[0]
+    );
+}
+
+#[cfg(feature = "index")]
+#[test]
+fn test_shuffled_lines() {
+    let data: String = (0..1000).map(|i| format!("{}\n", i)).collect();
+    let mut reader = EasyReader::new(io::Cursor::new(data.into_bytes())).unwrap();
+    let epoch = |reader: &mut EasyReader<io::Cursor<Vec<u8>>>, seed| -> Vec<u32> {
+        let lines = reader.shuffled_lines(seed).unwrap();
+        assert_eq!(lines.size_hint(), (1000, Some(1000)));
+        lines.map(|line| line.unwrap().parse().unwrap()).collect()
+    };
+    let first = epoch(&mut reader, 1);
+    assert_eq!(
+        reader.current_line().unwrap().unwrap(),
+        first[999].to_string()
+    );
+    assert_ne!(first, (0..1000).collect::<Vec<_>>());
+    let mut sorted = first.clone();
+    sorted.sort_unstable();
+    assert_eq!(sorted, (0..1000).collect::<Vec<_>>());
+    assert_eq!(epoch(&mut reader, 1), first);
+    assert_ne!(epoch(&mut reader, 2), first);
+
+    for file in ["resources/one-line-file", "resources/test-file-lf"] {
+        let mut reader = EasyReader::new(File::open(file).unwrap()).unwrap();
+        let mut lines: Vec<_> = reader
+            .shuffled_lines(3)
+            .unwrap()
+            .map(Result::unwrap)
+            .collect();
+        reader.bof();
+        let mut expected: Vec<_> = reader.iter().map(Result::unwrap).collect();
+        lines.sort();
+        expected.sort();
+        assert_eq!(lines, expected);
+    }
+}
+
+#[cfg(feature = "rand")]
+#[test]
+fn test_sample() {
+    let data: String = (0..500).map(|i| format!("{}\n", i)).collect();
+    let mut reader = EasyReader::new(io::Cursor::new(data.into_bytes())).unwrap();
+    reader.seed(3);
+    let check = |sample: Vec<String>, k: usize| {
+        let mut numbers: Vec<u32> = sample.iter().map(|line| line.parse().unwrap()).collect();
+        numbers.sort_unstable();
+        numbers.dedup();
+        assert_eq!(numbers.len(), k);
+        assert!(numbers.iter().all(|&n| n < 500));
+    };
+
+    reader.next_line().unwrap();
+    check(reader.sample(50).unwrap(), 50);
+    check(reader.sample(600).unwrap(), 500);
+    assert!(reader.sample(0).unwrap().is_empty());
+    assert_eq!(reader.next_line().unwrap().unwrap(), "1");
+
+    #[cfg(feature = "index")]
+    {
+        reader.build_index().unwrap();
+        check(reader.sample(50).unwrap(), 50);
+        check(reader.sample(500).unwrap(), 500);
+        check(reader.sample(1000).unwrap(), 500);
+        assert_eq!(reader.next_line().unwrap().unwrap(), "2");
+    }
+
+    let mut reader = EasyReader::new(io::Cursor::new(b"\n\r\nb\n".to_vec())).unwrap();
+    let mut sample = reader.sample(5).unwrap();
+    sample.sort();
+    assert_eq!(sample, ["", "", "b"]);
+}
+
+#[cfg(feature = "rand")]
+#[test]
+fn test_unbiased_random() {
+    // A line 99 times longer than the others
+    let mut data = "x".repeat(99);
+    data.push_str("\na\nb\n");
+    let mut reader = EasyReader::new(io::Cursor::new(data.into_bytes())).unwrap();
+    reader.seed(9);
+    let long_lines = |reader: &mut EasyReader<io::Cursor<Vec<u8>>>| {
+        (0..300)
+            .filter(|_| reader.random_line().unwrap().unwrap().len() == 99)
+            .count()
+    };
+    assert!(long_lines(&mut reader) > 250);
+    reader.unbiased_random(true);
+    let count = long_lines(&mut reader);
+    assert!((60..140).contains(&count), "{}", count);
+
+    // With a multi-byte delimiter too
+    let mut reader = EasyReader::new(io::Cursor::new(b"aaaa||b||c".to_vec())).unwrap();
+    reader
+        .delimiter_bytes(b"||")
+        .unwrap()
+        .unbiased_random(true)
+        .seed(4);
+    let mut seen: Vec<_> = (0..60)
+        .map(|_| reader.random_line().unwrap().unwrap())
+        .collect();
+    seen.sort();
+    seen.dedup();
+    assert_eq!(seen, ["aaaa", "b", "c"]);
+}
+
+#[cfg(feature = "rand")]
+#[test]
+fn test_random_line_in() {
+    let data: String = (0..100).map(|i| format!("{:02}\n", i)).collect();
+    let mut reader = EasyReader::new(io::Cursor::new(data.into_bytes())).unwrap();
+    reader.seed(8);
+    let draws = |reader: &mut EasyReader<io::Cursor<Vec<u8>>>, lines: std::ops::Range<u64>| {
+        let mut seen: Vec<u64> = (0..200)
+            .map(|_| reader.random_line_in(lines.clone()).unwrap().unwrap())
+            .map(|line| line.parse().unwrap())
+            .collect();
+        seen.sort_unstable();
+        seen.dedup();
+        seen
+    };
+
+    assert_eq!(draws(&mut reader, 10..20), (10..20).collect::<Vec<_>>());
+    #[cfg(feature = "index")]
+    assert_eq!(
+        reader.index_strategy(),
+        IndexStrategy::Sparse { every: 1024 },
+        "The lines shouldn't be counted from the BOF for each draw"
+    );
+    assert_eq!(draws(&mut reader, 98..1000), [98, 99]);
+    assert_eq!(reader.random_line_in(100..200).unwrap(), None);
+    assert_eq!(reader.current_line().unwrap().unwrap().len(), 2);
+    #[cfg(feature = "index")]
+    {
+        reader.build_index().unwrap();
+        assert_eq!(draws(&mut reader, 0..3), [0, 1, 2]);
+    }
+
+    // Lines 5 to 7, the line containing the byte 15 included
+    for _ in 0..50 {
+        let line: u64 = reader
+            .random_line_in_bytes(16..24)
+            .unwrap()
+            .unwrap()
+            .parse()
+            .unwrap();
+        assert!((5..8).contains(&line));
+    }
+    assert_eq!(reader.random_line_in_bytes(300..400).unwrap(), None);
+    reader.unbiased_random(true);
+    for _ in 0..50 {
+        let line: u64 = reader
+            .random_line_in_bytes(16..24)
+            .unwrap()
+            .unwrap()
+            .parse()
+            .unwrap();
+        assert!((6..8).contains(&line));
+    }
+    assert_eq!(reader.random_line_in_bytes(16..18).unwrap(), None);
+}
+
+#[cfg(feature = "rand")]
+#[test]
+fn test_random_lines() {
+    let data: String = (0..2000).map(|i| format!("{:04}\r\n", i)).collect();
+    let mut reader = EasyReader::new(io::Cursor::new(data.into_bytes())).unwrap();
+    reader.seed(6).next_line().unwrap();
+    let check = |lines: Vec<String>| {
+        assert_eq!(lines.len(), 500);
+        let numbers: Vec<u32> = lines.iter().map(|line| line.parse().unwrap()).collect();
+        assert!(numbers.windows(2).all(|pair| pair[0] <= pair[1]));
+        assert!(numbers.iter().all(|&n| n < 2000));
+    };
+
+    reader.reset_stats();
+    check(reader.random_lines(500).unwrap());
+    assert_eq!(reader.current_line().unwrap().unwrap(), "0000");
+    #[cfg(feature = "index")]
+    {
+        reader.build_index().unwrap().reset_stats();
+        check(reader.random_lines(500).unwrap());
+        // The whole file (16 KB) is read at once
+        assert!(reader.stats().reads < 5, "{:?}", reader.stats());
+        reader.reset_stats();
+        for _ in 0..100 {
+            reader.random_line().unwrap();
+        }
+        assert!(reader.stats().reads >= 100);
+    }
+    assert!(reader.random_lines(0).unwrap().is_empty());
+
+    let mut reader = EasyReader::new(File::open("resources/test-file-lf").unwrap()).unwrap();
+    let expected: Vec<_> = reader.iter().map(Result::unwrap).collect();
+    let lines = reader.random_lines(100).unwrap();
+    assert!(lines.iter().all(|line| expected.contains(line)));
+}
+
+#[cfg(feature = "index")]
+#[test]
+fn test_batches() {
+    let data: String = (0..1000).map(|i| format!("{}\r\n", i)).collect();
+    let mut reader = EasyReader::new(io::Cursor::new(data.into_bytes())).unwrap();
+    let shuffled: Vec<String> = reader
+        .shuffled_lines(5)
+        .unwrap()
+        .map(Result::unwrap)
+        .collect();
+    reader.bof();
+
+    let batches: Vec<Vec<String>> = reader.batches(64, 5).unwrap().map(Result::unwrap).collect();
+    assert_eq!(batches.len(), 16);
+    assert!(batches[..15].iter().all(|batch| batch.len() == 64));
+    assert_eq!(batches[15].len(), 1000 - 15 * 64);
+    assert_eq!(batches.concat(), shuffled);
+    assert_eq!(reader.current_offsets(), (0, 0));
+    assert!(reader.batches(0, 5).is_err());
+
+    let mut batches = reader.into_batches(100, 5).unwrap();
+    assert_eq!(batches.next().unwrap().unwrap()[..], shuffled[..100]);
+    assert_eq!(batches.next().unwrap().unwrap()[..], shuffled[100..200]);
+    let reader = batches.into_inner();
+    let batches = reader.into_batches(300, 5).unwrap();
+    let lines: Vec<Vec<String>> = batches.map(Result::unwrap).collect();
+    assert_eq!(lines.len(), 4);
+    assert_eq!(lines.concat(), shuffled);
+}
+
+#[cfg(feature = "regex")]
+#[test]
+fn test_find() {
+    use regex::Regex;
+
+    let data: String = (0..3000)
+        .map(|i| match i % 1000 {
+            500 => format!("ERROR {}\n", i),
+            _ => format!("INFO {}\n", i),
+        })
+        .collect();
+    let mut reader = EasyReader::new(io::Cursor::new(data.into_bytes())).unwrap();
+    let error = Regex::new("^ERROR").unwrap();
+
+    assert_eq!(reader.find_next(&error).unwrap().unwrap(), "ERROR 500");
+    assert_eq!(reader.find_next(&error).unwrap().unwrap(), "ERROR 1500");
+    assert_eq!(reader.next_line().unwrap().unwrap(), "INFO 1501");
+    assert_eq!(reader.find_prev(&error).unwrap().unwrap(), "ERROR 1500");
+    assert_eq!(reader.find_prev(&error).unwrap().unwrap(), "ERROR 500");
+    assert_eq!(reader.find_prev(&error).unwrap(), None);
+    assert_eq!(reader.current_line().unwrap().unwrap(), "ERROR 500");
+    assert_eq!(reader.prev_line().unwrap().unwrap(), "INFO 499");
+
+    reader.eof();
+    assert_eq!(reader.find_prev(&error).unwrap().unwrap(), "ERROR 2500");
+    assert_eq!(reader.find_next(&error).unwrap(), None);
+    assert_eq!(reader.next_line().unwrap().unwrap(), "INFO 2501");
+    // The line containing the offset comes first, as with next_line()
+    reader.set_offset(9);
+    let info_1 = Regex::new("^INFO 1$").unwrap();
+    assert_eq!(reader.find_next(&info_1).unwrap().unwrap(), "INFO 1");
+    reader.set_offset(9);
+    assert_eq!(
+        reader.find_next(&Regex::new("^NONE").unwrap()).unwrap(),
+        None
+    );
+    assert_eq!(reader.next_line().unwrap().unwrap(), "INFO 1");
+
+    // Line by line
+    reader.keep_terminators(true).bof();
+    assert_eq!(reader.find_next(&error).unwrap().unwrap(), "ERROR 500\n");
+    assert_eq!(reader.find_prev(&error).unwrap(), None);
+    assert_eq!(reader.current_line().unwrap().unwrap(), "ERROR 500\n");
+}
+
+#[test]
+fn test_find_next_literal() {
+    let mut reader = EasyReader::new(File::open("resources/test-file-lf").unwrap()).unwrap();
+    let lines: Vec<String> = reader.iter().map(Result::unwrap).collect();
+    reader.bof();
+    assert_eq!(reader.find_next_literal("CC").unwrap().unwrap(), lines[2]);
+    assert_eq!(reader.find_next_literal("CC").unwrap(), None);
+    assert_eq!(reader.current_line().unwrap().unwrap(), lines[2]);
+    assert_eq!(reader.find_next_literal(b"E").unwrap().unwrap(), lines[4]);
+    reader.set_offset(1);
+    assert_eq!(reader.find_next_literal("AAAA").unwrap().unwrap(), lines[0]);
+
+    // Across two blocks
+    let mut data = vec![b'a'; 4 * 1024 * 1024 - 3];
+    data.extend(b"needle\nafter\nneedle");
+    let mut reader = EasyReader::new(io::Cursor::new(data)).unwrap();
+    assert_eq!(
+        reader.find_next_literal("needle").unwrap().unwrap().len(),
+        4 * 1024 * 1024 + 3
+    );
+    assert_eq!(
+        reader.find_next_literal("needle").unwrap().unwrap(),
+        "needle"
+    );
+    assert_eq!(reader.find_next_literal("needle").unwrap(), None);
+    assert_eq!(reader.prev_line().unwrap().unwrap(), "after");
+
+    // Not in the ignored lines
+    let mut reader = EasyReader::new(io::Cursor::new(b"a\n#c\nb\nc\n".to_vec())).unwrap();
+    reader.comment_prefix("#");
+    assert_eq!(reader.find_next_literal("c").unwrap().unwrap(), "c");
+    assert_eq!(reader.find_next_literal("c").unwrap(), None);
+    reader.bof();
+    assert_eq!(reader.find_next_literal("#").unwrap(), None);
+    assert_eq!(reader.next_line().unwrap().unwrap(), "a");
+}
+
+#[test]
+fn test_line_context() {
+    let data: String = (0..100).map(|i| format!("{}\r\n", i)).collect();
+    let mut reader = EasyReader::new(io::Cursor::new(data.into_bytes())).unwrap();
+    // The first line at the BOF, like current_line()
+    let context = reader.line_context(2, 2).unwrap().unwrap();
+    assert_eq!(
+        (context.line.as_str(), context.after),
+        ("0", vec!["1".into(), "2".into()])
+    );
+
+    reader.bof();
+    reader.next_lines(51).unwrap();
+    let context = reader.line_context(3, 2).unwrap().unwrap();
+    assert_eq!(context.before, ["47", "48", "49"]);
+    assert_eq!(context.line, "50");
+    assert_eq!(context.after, ["51", "52"]);
+    assert_eq!(reader.next_line().unwrap().unwrap(), "51");
+
+    reader.bof();
+    reader.next_line().unwrap();
+    let context = reader.line_context(5, 0).unwrap().unwrap();
+    assert!(context.before.is_empty() && context.after.is_empty());
+    reader.eof();
+    reader.prev_line().unwrap();
+    assert_eq!(reader.line_context(1, 5).unwrap().unwrap().before, ["98"]);
+
+    #[cfg(feature = "regex")]
+    {
+        let pattern = regex::Regex::new("^[1-3]0$").unwrap();
+        reader.bof();
+        let context = reader
+            .find_next_with_context(&pattern, 1, 1)
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            (context.before, context.after),
+            (vec!["9".into()], vec!["11".into()])
+        );
+        reader.eof();
+        let context = reader
+            .find_prev_with_context(&pattern, 0, 2)
+            .unwrap()
+            .unwrap();
+        assert_eq!(context.line, "30");
+        assert_eq!(context.after, ["31", "32"]);
+        assert_eq!(reader.current_line().unwrap().unwrap(), "30");
+        reader.bof();
+        assert_eq!(reader.find_prev_with_context(&pattern, 1, 1).unwrap(), None);
+    }
+}
+
+#[test]
+fn test_count_matching() {
+    let data: String = (0..1000).map(|i| format!("{}\r\n", i)).collect();
+    let mut reader = EasyReader::new(io::Cursor::new(data.clone().into_bytes())).unwrap();
+    reader.next_line().unwrap();
+    assert_eq!(reader.count_matching(|_| true).unwrap(), 1000);
+    assert_eq!(
+        reader.count_matching(|line| line.ends_with('7')).unwrap(),
+        100
+    );
+    assert_eq!(reader.count_matching(|line| line.is_empty()).unwrap(), 0);
+    assert_eq!(reader.current_line().unwrap().unwrap(), "0");
+
+    // The lines 10 to 19 start every 4 bytes from the byte 30
+    assert_eq!(reader.count_matching_in(30..70, |_| true).unwrap(), 10);
+    assert_eq!(reader.count_matching_in(29..67, |_| true).unwrap(), 10);
+    assert_eq!(reader.count_matching_in(31..70, |_| true).unwrap(), 9);
+    assert_eq!(reader.count_matching_in(31..34, |_| true).unwrap(), 0);
+    assert_eq!(reader.count_matching_in(5000..6000, |_| true).unwrap(), 0);
+    #[cfg(feature = "regex")]
+    assert_eq!(
+        reader
+            .count_matching_regex(&regex::Regex::new("^9+$").unwrap())
+            .unwrap(),
+        3
+    );
+
+    // Without a terminator at the end, with a delimiter of several bytes
+    let mut reader = EasyReader::new(io::Cursor::new(b"a||bb||a".to_vec())).unwrap();
+    reader.delimiter_bytes(b"||").unwrap();
+    assert_eq!(reader.count_matching(|line| line == "a").unwrap(), 2);
+    assert_eq!(reader.count_matching_in(1..8, |_| true).unwrap(), 2);
+    let mut reader = EasyReader::new(io::Cursor::new(b"\xff\nok\n".to_vec())).unwrap();
+    assert!(reader.count_matching(|_| true).is_err());
+    assert_eq!(reader.count_matching_in(1..5, |_| true).unwrap(), 1);
+
+    // The lines ignored by the reads aren't counted either, nor decoded
+    let data = b"# \xff\na\n\n  \n# b\nb\n".to_vec();
+    let mut reader = EasyReader::new(io::Cursor::new(data)).unwrap();
+    reader.comment_prefix("#").skip_blank_lines(true);
+    assert_eq!(reader.count_matching(|_| true).unwrap(), 2);
+    assert_eq!(reader.count_matching(|line| line.contains('b')).unwrap(), 1);
+    assert_eq!(reader.count_matching_in(2..14, |_| true).unwrap(), 1);
+    reader.skip_blank_lines(false);
+    assert_eq!(reader.count_matching(|_| true).unwrap(), 4);
+}
+
+#[test]
+fn test_line_matching() {
+    let data: String = (0..3000).map(|i| format!("{}\n", i)).collect();
+    let mut reader = EasyReader::new(io::Cursor::new(data.into_bytes())).unwrap();
+    let mut calls = 0;
+    let found = reader
+        .next_line_matching(|line| {
+            calls += 1;
+            line.len() == 4
+        })
+        .unwrap();
+    assert_eq!((found.unwrap().as_str(), calls), ("1000", 1001));
+    assert_eq!(
+        reader
+            .next_line_matching(|line| line.ends_with("99"))
+            .unwrap()
+            .unwrap(),
+        "1099"
+    );
+    assert_eq!(
+        reader
+            .prev_line_matching(|line| line.len() < 4)
+            .unwrap()
+            .unwrap(),
+        "999"
+    );
+    assert_eq!(
+        reader.prev_line_matching(|line| line == "1099").unwrap(),
+        None
+    );
+    assert_eq!(reader.next_line().unwrap().unwrap(), "1000");
+
+    // With the lines read one by one
+    reader.continuation("\\").bof();
+    assert_eq!(
+        reader
+            .next_line_matching(|line| line == "2999")
+            .unwrap()
+            .unwrap(),
+        "2999"
+    );
+    assert_eq!(reader.next_line_matching(|_| true).unwrap(), None);
+    assert_eq!(
+        reader
+            .prev_line_matching(|line| line == "5")
+            .unwrap()
+            .unwrap(),
+        "5"
+    );
+}
+
+#[test]
+fn test_binary_search_by() {
+    let words: Vec<String> = (0..5000).map(|i| format!("{:05}", i * 2)).collect();
+    let data = words.join("\r\n") + "\r\n";
+    let mut reader = EasyReader::new(io::Cursor::new(data.into_bytes())).unwrap();
+    reader.chunk_size(16);
+    for word in ["00000", "04242", "09998"] {
+        let found = reader.binary_search_by(|line| line.cmp(word)).unwrap();
+        assert_eq!(found.unwrap(), word);
+        assert_eq!(reader.current_line().unwrap().unwrap(), word);
+    }
+
+    // Not found: the cursor doesn't move
+    assert_eq!(
+        reader.binary_search_by(|line| line.cmp("04243")).unwrap(),
+        Err(2122 * 7)
+    );
+    assert_eq!(
+        reader.binary_search_by(|line| line.cmp("")).unwrap(),
+        Err(0)
+    );
+    assert_eq!(
+        reader.binary_search_by(|line| line.cmp("1")).unwrap(),
+        Err(35000)
+    );
+    assert_eq!(reader.current_line().unwrap().unwrap(), "09998");
+
+    // Few lines are read
+    reader.reset_stats();
+    reader
+        .binary_search_by(|line| line.cmp("07777"))
+        .unwrap()
+        .unwrap_err();
+    assert!(reader.stats().lines_decoded < 40);
+
+    let mut reader = EasyReader::new(io::Cursor::new(b"b".to_vec())).unwrap();
+    assert_eq!(
+        reader.binary_search_by(|line| line.cmp("a")).unwrap(),
+        Err(0)
+    );
+    assert_eq!(
+        reader.binary_search_by(|line| line.cmp("c")).unwrap(),
+        Err(1)
+    );
+}
+
+#[test]
+fn test_partition_point() {
+    let data: String = (0..3000).map(|i| format!("{}\n", i * 3)).collect();
+    let mut reader = EasyReader::new(io::Cursor::new(data.into_bytes())).unwrap();
+    let below = |limit: u32| move |line: &str| line.parse::<u32>().unwrap() < limit;
+    assert_eq!(
+        reader.partition_point(below(1000)).unwrap().unwrap(),
+        "1002"
+    );
+    assert_eq!(reader.next_line().unwrap().unwrap(), "1005");
+    assert_eq!(reader.partition_point(below(0)).unwrap().unwrap(), "0");
+    assert_eq!(
+        reader.partition_point(below(8997)).unwrap().unwrap(),
+        "8997"
+    );
+
+    // The predicate is true for all the lines: the cursor doesn't move
+    assert_eq!(reader.partition_point(below(9000)).unwrap(), None);
+    assert_eq!(reader.current_line().unwrap().unwrap(), "8997");
+}
+
+#[test]
+fn test_seek_to_time() {
+    let mut data = String::from("log start\n");
+    for minute in 0..2000u32 {
+        data += &format!("{:04} event {}\n", minute * 2, minute);
+        if minute % 7 == 0 {
+            data += "  trace line\n  trace line\n";
+        }
+    }
+    let mut reader = EasyReader::new(io::Cursor::new(data.into_bytes())).unwrap();
+    let parser = |line: &str| line.get(..4).and_then(|time| time.parse::<u32>().ok());
+    for (target, expected) in [
+        (0, "0000 event 0"),
+        (15, "0016 event 8"),
+        (1234, "1234 event 617"),
+    ] {
+        let line = reader.seek_to_time(target, parser).unwrap().unwrap();
+        assert_eq!(line, expected);
+        assert_eq!(reader.current_line().unwrap().unwrap(), expected);
+    }
+    assert_eq!(
+        reader.seek_to_time(3997, parser).unwrap().unwrap(),
+        "3998 event 1999"
+    );
+    assert_eq!(reader.prev_line().unwrap().unwrap(), "3996 event 1998");
+
+    // All the lines are earlier: the cursor doesn't move
+    assert_eq!(reader.seek_to_time(4000, parser).unwrap(), None);
+    assert_eq!(reader.current_line().unwrap().unwrap(), "3996 event 1998");
+
+    // The lines without a time belong to the record before them
+    let line = reader.seek_to_time(3991, parser).unwrap().unwrap();
+    assert_eq!(line, "3992 event 1996");
+    assert_eq!(reader.prev_line().unwrap().unwrap(), "  trace line");
+}
+
+#[test]
+fn test_skip_header_lines() {
+    let data = "name,value\nunit,kg\na,1\nb,2\nc,3";
+    let mut reader = EasyReader::new(io::Cursor::new(data.as_bytes().to_vec())).unwrap();
+    reader.skip_header_lines(2).unwrap();
+    assert_eq!(reader.header_size(), 19);
+    let lines: Vec<String> = reader.iter().collect::<io::Result<_>>().unwrap();
+    assert_eq!(lines, ["a,1", "b,2", "c,3"]);
+    assert_eq!(reader.prev_line().unwrap().unwrap(), "b,2");
+    assert_eq!(reader.prev_line().unwrap().unwrap(), "a,1");
+    assert_eq!(reader.prev_line().unwrap(), None);
+    assert_eq!(reader.position_of(5).unwrap(), (1, 1));
+    assert_eq!(reader.line_count().unwrap(), 3);
+    assert_eq!(reader.line_at_offset(0).unwrap().unwrap(), "a,1");
+    assert_eq!(reader.next_line_ref().unwrap().unwrap(), "b,2");
+    #[cfg(feature = "rand")]
+    for _ in 0..50 {
+        assert!(!reader.random_line().unwrap().unwrap().contains(['m', 'k']));
+    }
+    #[cfg(feature = "index")]
+    {
+        reader.build_index().unwrap();
+        assert_eq!(reader.tail(5).unwrap(), ["a,1", "b,2", "c,3"]);
+        reader.skip_header_lines(1).unwrap();
+        assert_eq!(reader.head(2).unwrap(), ["unit,kg", "a,1"]);
+        assert_eq!(reader.line_count().unwrap(), 4);
+    }
+
+    // More header lines than lines
+    reader.skip_header_lines(10).unwrap();
+    assert_eq!(reader.next_line().unwrap(), None);
+    reader.skip_header_lines(0).unwrap();
+    assert_eq!(reader.next_line().unwrap().unwrap(), "name,value");
+
+    // The header is the whole file
+    let mut reader = EasyReader::new(io::Cursor::new(b"a\n".to_vec())).unwrap();
+    reader.skip_header_lines(1).unwrap();
+    assert_eq!(reader.header_size(), 2);
+    assert_eq!(reader.tail(2).unwrap(), Vec::<String>::new());
+    assert_eq!(reader.line_count().unwrap(), 0);
+    assert_eq!(reader.current_line().unwrap(), None);
+    reader.eof();
+    assert_eq!(reader.skip_lines(-1).unwrap(), None);
+    assert_eq!(reader.prev_line().unwrap(), None);
+    reader.skip_header_lines(0).unwrap();
+    assert_eq!(reader.tail(2).unwrap(), ["a"]);
+
+    // Positional reads
+    let path = std::env::temp_dir().join("easy_reader_test_skip_header_lines");
+    std::fs::write(&path, data).unwrap();
+    let mut reader = EasyReader::new(File::open(&path).unwrap()).unwrap();
+    reader.skip_header_lines(1).unwrap();
+    assert_eq!(reader.get_line(1).unwrap().unwrap(), "a,1");
+    let partitions = reader.split_into(2).unwrap();
+    assert_eq!(partitions[0].file.range().start, 11);
+    let mut partition = partitions.into_iter().next().unwrap();
+    assert_eq!(partition.next_line().unwrap().unwrap(), "unit,kg");
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn test_comment_prefix() {
+    let data = "# header\n# columns: x y\n1 2\n# mid\n3 4\n5 6\n# end\n";
+    let mut reader = EasyReader::new(io::Cursor::new(data.as_bytes().to_vec())).unwrap();
+    reader.comment_prefix("#");
+    let lines: Vec<String> = reader.iter().collect::<io::Result<_>>().unwrap();
+    assert_eq!(lines, ["1 2", "3 4", "5 6"]);
+    let lines: Vec<String> = reader.rev_lines().collect::<io::Result<_>>().unwrap();
+    assert_eq!(lines, ["5 6", "3 4", "1 2"]);
+
+    // At the ends, the cursor stays on the last line read
+    reader.bof();
+    assert_eq!(reader.current_line().unwrap().unwrap(), "1 2");
+    assert_eq!(reader.prev_line().unwrap(), None);
+    assert_eq!(reader.next_line().unwrap().unwrap(), "3 4");
+    assert_eq!(reader.next_line().unwrap().unwrap(), "5 6");
+    assert_eq!(reader.next_line().unwrap(), None);
+    assert_eq!(reader.current_line().unwrap().unwrap(), "5 6");
+    assert_eq!(reader.line_at_offset(40).unwrap().unwrap(), "5 6");
+    assert_eq!(reader.line_at_offset(30).unwrap().unwrap(), "3 4");
+    assert_eq!(reader.next_line_ref().unwrap().unwrap(), "5 6");
+    #[cfg(feature = "rand")]
+    {
+        for _ in 0..50 {
+            assert!(!reader.random_line().unwrap().unwrap().starts_with('#'));
+        }
+        let lines = reader.random_lines(20).unwrap();
+        assert_eq!(lines.len(), 20);
+        assert!(lines.iter().all(|line| !line.starts_with('#')));
+        let mut sample = reader.sample(5).unwrap();
+        sample.sort();
+        assert_eq!(sample, ["1 2", "3 4", "5 6"]);
+    }
+
+    // The batch reads, the pages and the neighbors skip them too
+    let mut reader = EasyReader::new(io::Cursor::new(b"a\n#c\nb\n#d\ne\n".to_vec())).unwrap();
+    reader.comment_prefix("#");
+    assert_eq!(reader.next_lines(5).unwrap(), ["a", "b", "e"]);
+    assert_eq!(reader.current_line().unwrap().unwrap(), "e");
+    assert_eq!(reader.prev_lines(5).unwrap(), ["b", "a"]);
+    assert_eq!(reader.head(2).unwrap(), ["a", "b"]);
+    assert_eq!(reader.tail(2).unwrap(), ["b", "e"]);
+    assert_eq!(reader.current_line().unwrap().unwrap(), "a");
+    assert_eq!(
+        reader.neighbors().unwrap(),
+        (None, "a".to_string(), Some("b".to_string()))
+    );
+    reader.next_line().unwrap();
+    assert_eq!(
+        reader.neighbors().unwrap(),
+        (
+            Some("a".to_string()),
+            "b".to_string(),
+            Some("e".to_string())
+        )
+    );
+    let mut pager = Pager::new(reader, 2).unwrap();
+    assert_eq!(pager.page_down().unwrap().unwrap().lines, ["a", "b"]);
+    assert_eq!(pager.page_down().unwrap().unwrap().lines, ["e"]);
+    let mut reader = pager.into_inner();
+    #[cfg(feature = "index")]
+    {
+        for seed in 0..5 {
+            let mut lines: Vec<String> = reader
+                .shuffled_lines(seed)
+                .unwrap()
+                .collect::<io::Result<_>>()
+                .unwrap();
+            lines.sort();
+            assert_eq!(lines, ["a", "b", "e"]);
+        }
+        let batches: Vec<Vec<String>> = reader
+            .batches(2, 0)
+            .unwrap()
+            .collect::<io::Result<_>>()
+            .unwrap();
+        assert_eq!(batches.iter().map(Vec::len).collect::<Vec<_>>(), [2, 1]);
+    }
+
+    reader.comment_prefix("").bof();
+    assert_eq!(reader.next_line().unwrap().unwrap(), "a");
+    let mut reader = EasyReader::new(io::Cursor::new(data.as_bytes().to_vec())).unwrap();
+    assert_eq!(reader.next_line().unwrap().unwrap(), "# header");
+    reader.comment_prefix("# ");
+    assert_eq!(reader.next_line().unwrap().unwrap(), "1 2");
+
+    // Nothing but comments
+    let mut reader = EasyReader::new(io::Cursor::new(b"# a\n# b".to_vec())).unwrap();
+    reader.comment_prefix("#");
+    assert_eq!(reader.next_line().unwrap(), None);
+    assert_eq!(reader.current_line().unwrap(), None);
+    #[cfg(feature = "rand")]
+    assert_eq!(reader.random_line().unwrap(), None);
+}
+
+#[test]
+fn test_skip_blank_lines() {
+    let data = "\n  \na\n\n\t\nb\r\n \r\nc\n\n";
+    let mut reader = EasyReader::new(io::Cursor::new(data.as_bytes().to_vec())).unwrap();
+    reader.skip_blank_lines(true);
+    let lines: Vec<String> = reader.iter().collect::<io::Result<_>>().unwrap();
+    assert_eq!(lines, ["a", "b", "c"]);
+    let lines: Vec<String> = reader.rev_lines().collect::<io::Result<_>>().unwrap();
+    assert_eq!(lines, ["c", "b", "a"]);
+    assert_eq!(reader.line_at_offset(1).unwrap().unwrap(), "a");
+    assert_eq!(reader.line_at_offset(6).unwrap().unwrap(), "b");
+    reader.bof();
+    assert_eq!(reader.next_lines(5).unwrap(), ["a", "b", "c"]);
+    assert_eq!(reader.prev_lines(5).unwrap(), ["b", "a"]);
+    assert_eq!(reader.head(2).unwrap(), ["a", "b"]);
+    assert_eq!(reader.tail(2).unwrap(), ["b", "c"]);
+    #[cfg(feature = "index")]
+    for seed in 0..5 {
+        let mut lines: Vec<String> = reader
+            .shuffled_lines(seed)
+            .unwrap()
+            .collect::<io::Result<_>>()
+            .unwrap();
+        lines.sort();
+        assert_eq!(lines, ["a", "b", "c"]);
+    }
+
+    // With the comments
+    reader.comment_prefix("b").bof();
+    let lines: Vec<String> = reader.iter().collect::<io::Result<_>>().unwrap();
+    assert_eq!(lines, ["a", "c"]);
+    reader.skip_blank_lines(false).comment_prefix("").bof();
+    assert_eq!(reader.next_line().unwrap().unwrap(), "");
+
+    // Drawn uniformly among the other lines
+    #[cfg(all(feature = "rand", feature = "index"))]
+    {
+        let data: String = (0..100).map(|i| format!("{}\n\n\n", i % 2)).collect();
+        let mut reader = EasyReader::new(io::Cursor::new(data.into_bytes())).unwrap();
+        reader.build_index().unwrap().skip_blank_lines(true).seed(7);
+        let lines = reader.random_lines(2000).unwrap();
+        let zeros = lines.iter().filter(|line| *line == "0").count();
+        assert_eq!(lines.iter().filter(|line| line.is_empty()).count(), 0);
+        assert!((900..1100).contains(&zeros), "{}", zeros);
+        assert_eq!(reader.sample(500).unwrap().len(), 100);
+
+        // Even when the blank lines are drawn too often to draw again until they aren't
+        let data = format!("x\n{}y\n", "\n".repeat(300));
+        let mut reader = EasyReader::new(io::Cursor::new(data.into_bytes())).unwrap();
+        reader.build_index().unwrap().skip_blank_lines(true).seed(7);
+        let lines = reader.random_lines(2000).unwrap();
+        let xs = lines.iter().filter(|line| *line == "x").count();
+        assert_eq!(lines.iter().filter(|line| line.is_empty()).count(), 0);
+        assert!((900..1100).contains(&xs), "{}", xs);
+    }
+}
+
+#[test]
+fn test_trim() {
+    let data = "  a b \r;\tc\t\r;\r; d\r";
+    let mut reader = EasyReader::new(io::Cursor::new(data.as_bytes().to_vec())).unwrap();
+    reader.delimiter(b';');
+    for (trim, expected) in [
+        (Trim::None, ["  a b \r", "\tc\t\r", "\r", " d\r"]),
+        (Trim::CarriageReturn, ["  a b ", "\tc\t", "", " d"]),
+        (Trim::End, ["  a b", "\tc", "", " d"]),
+        (Trim::Both, ["a b", "c", "", "d"]),
+    ] {
+        reader.trim(trim).bof();
+        let lines: Vec<String> = reader.iter().collect::<io::Result<_>>().unwrap();
+        assert_eq!(lines, expected, "{:?}", trim);
+        assert_eq!(reader.head(4).unwrap(), expected, "{:?}", trim);
+        assert_eq!(reader.prev_line_ref().unwrap().unwrap(), expected[2]);
+        assert_eq!(
+            reader.count_matching(|line| line == expected[1]).unwrap(),
+            1
+        );
+    }
+
+    // The offsets are those of the lines of the file, the terminators kept as they are
+    reader.bof();
+    assert_eq!(reader.next_line().unwrap().unwrap(), "a b");
+    assert_eq!(reader.current_offsets(), (0, 7));
+    reader.keep_terminators(true).bof();
+    assert_eq!(reader.next_line().unwrap().unwrap(), "  a b \r;");
+}
+
+#[test]
+fn test_view_lines() {
+    let data: String = (0..1000).map(|i| format!("{}\n", i)).collect();
+    let expected: Vec<String> = (100..200).map(|i| i.to_string()).collect();
+    for indexed in [false, true] {
+        let mut reader = EasyReader::new(io::Cursor::new(data.as_bytes().to_vec())).unwrap();
+        if indexed {
+            #[cfg(feature = "index")]
+            reader.build_index().unwrap();
+        }
+        reader.view_lines(100..200).unwrap();
+        let lines: Vec<String> = reader.iter().collect::<io::Result<_>>().unwrap();
+        assert_eq!(lines, expected);
+        assert_eq!(reader.next_line().unwrap(), None);
+        assert_eq!(reader.tail(1).unwrap(), ["199"]);
+        reader.bof();
+        assert_eq!(reader.prev_line().unwrap(), None);
+        assert_eq!(reader.line_count().unwrap(), 100);
+        assert_eq!(reader.position_of(4).unwrap(), (1, 0));
+        #[cfg(feature = "rand")]
+        for _ in 0..50 {
+            let line: u32 = reader.random_line().unwrap().unwrap().parse().unwrap();
+            assert!((100..200).contains(&line));
+        }
+
+        // Narrowed again, within the view
+        let mut fork = reader
+            .fork_with(io::Cursor::new(data.as_bytes().to_vec()))
+            .unwrap();
+        fork.view_lines(10..12).unwrap();
+        assert_eq!(fork.head(5).unwrap(), ["110", "111"]);
+        fork.view_lines(5..5).unwrap();
+        assert_eq!(fork.next_line().unwrap(), None);
+        assert_eq!(reader.head(1).unwrap(), ["100"]);
+        reader.view_lines(95..1000).unwrap();
+        assert_eq!(
+            reader.next_lines(10).unwrap(),
+            ["195", "196", "197", "198", "199"]
+        );
+    }
+
+    // Past the EOF, and growing
+    let mut reader = EasyReader::new(io::Cursor::new(b"a\nb\nc".to_vec())).unwrap();
+    reader.view_lines(1..10).unwrap();
+    assert_eq!(reader.head(5).unwrap(), ["b", "c"]);
+    reader.view_lines(1..2).unwrap();
+    reader.file.get_mut().extend_from_slice(b"\nd\n");
+    assert!(!reader.refresh().unwrap());
+    assert_eq!(reader.head(5).unwrap(), ["c"]);
+}
+
+#[test]
+fn test_empty_view() {
+    for indexed in [false, true] {
+        let mut reader = EasyReader::new(io::Cursor::new(b"a\nb\n".to_vec())).unwrap();
+        if indexed {
+            #[cfg(feature = "index")]
+            reader.build_index().unwrap();
+        }
+        reader.view_lines(5..6).unwrap();
+        assert_eq!(reader.tail(3).unwrap(), Vec::<String>::new());
+        assert_eq!(reader.head(3).unwrap(), Vec::<String>::new());
+        assert_eq!(reader.line_count().unwrap(), 0);
+        assert_eq!(reader.estimate_line_count().unwrap().lines, 0);
+        assert_eq!(reader.current_line().unwrap(), None);
+        assert_eq!(reader.skip_lines(1).unwrap(), None);
+        assert_eq!(reader.next_line().unwrap(), None);
+        assert_eq!(
+            reader.neighbors().unwrap_err().kind(),
+            io::ErrorKind::NotFound
+        );
+        reader.eof();
+        assert_eq!(reader.skip_lines(-1).unwrap(), None);
+        assert_eq!(reader.prev_line().unwrap(), None);
+        assert_eq!(reader.current_line().unwrap(), None);
+        #[cfg(feature = "rand")]
+        assert_eq!(reader.random_line().unwrap(), None);
+        #[cfg(feature = "index")]
+        {
+            reader.build_index().unwrap();
+            assert_eq!(reader.line_count().unwrap(), 0);
+            assert_eq!(reader.current_line().unwrap(), None);
+        }
+    }
+}
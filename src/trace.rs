@@ -0,0 +1,179 @@
+use crate::{EasyReader, ReadMode};
+use std::{
+    fmt,
+    io::{self, prelude::*, Error, ErrorKind},
+    str::FromStr,
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum TraceOp {
+    Prev,
+    Current,
+    Next,
+    // Holds the random draw: a line of the index or an offset of the file
+    Random(u64),
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct TraceStep {
+    op: TraceOp,
+    from: (u64, u64, bool),
+    to: Option<(u64, u64)>,
+}
+
+/// A recorded sequence of navigation operations, see `EasyReader::record_trace()`.
+///
+/// Traces can be stored and loaded through their `Display` and `FromStr` implementations
+/// (one step per line), to replay a navigation session somewhere else.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Trace {
+    steps: Vec<TraceStep>,
+}
+
+impl Trace {
+    pub fn len(&self) -> usize {
+        self.steps.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.steps.is_empty()
+    }
+}
+
+impl fmt::Display for Trace {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for step in &self.steps {
+            match step.op {
+                TraceOp::Prev => write!(f, "prev")?,
+                TraceOp::Current => write!(f, "current")?,
+                TraceOp::Next => write!(f, "next")?,
+                TraceOp::Random(draw) => write!(f, "random:{}", draw)?,
+            }
+            let (start, end, at_bof) = step.from;
+            write!(f, " {} {} {}", start, end, at_bof as u8)?;
+            match step.to {
+                Some((start, end)) => writeln!(f, " {} {}", start, end)?,
+                None => writeln!(f, " -")?,
+            }
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Trace {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = |n: usize| {
+            Error::new(
+                ErrorKind::InvalidData,
+                format!("Malformed trace step at line {}", n + 1),
+            )
+        };
+
+        let mut steps = Vec::new();
+        for (n, line) in s.lines().enumerate() {
+            let fields: Vec<&str> = line.split(' ').collect();
+            let number = |i: usize| -> io::Result<u64> {
+                fields
+                    .get(i)
+                    .and_then(|f| f.parse().ok())
+                    .ok_or_else(|| invalid(n))
+            };
+
+            let op = match fields[0] {
+                "prev" => TraceOp::Prev,
+                "current" => TraceOp::Current,
+                "next" => TraceOp::Next,
+                op => match op.strip_prefix("random:").map(str::parse) {
+                    Some(Ok(draw)) => TraceOp::Random(draw),
+                    _ => return Err(invalid(n)),
+                },
+            };
+            let from = (number(1)?, number(2)?, number(3)? == 1);
+            let to = match fields.get(4) {
+                Some(&"-") if fields.len() == 5 => None,
+                Some(_) if fields.len() == 6 => Some((number(4)?, number(5)?)),
+                _ => return Err(invalid(n)),
+            };
+            steps.push(TraceStep { op, from, to });
+        }
+        Ok(Trace { steps })
+    }
+}
+
+impl<R: Read + Seek> EasyReader<R> {
+    /// Starts recording the navigation operations (prev, current, next and random lines),
+    /// discarding any trace recorded so far.
+    pub fn record_trace(&mut self) -> &mut Self {
+        self.trace = Some(Trace::default());
+        self
+    }
+
+    /// Stops recording and returns the trace.
+    pub fn take_trace(&mut self) -> Trace {
+        self.trace.take().unwrap_or_default()
+    }
+
+    /// Replays a trace, returning the lines read by each step.
+    ///
+    /// Jumps between the recorded steps (e.g. to a bookmark) are reproduced, and random lines
+    /// are drawn as they were when recorded. Fails with `ErrorKind::InvalidData` as soon as a
+    /// step doesn't land where it did when recorded.
+    pub fn replay(&mut self, trace: &Trace) -> io::Result<Vec<Option<String>>> {
+        let mut lines = Vec::with_capacity(trace.len());
+        for (i, step) in trace.steps.iter().enumerate() {
+            self.restore_cursor(step.from);
+
+            let moved = match step.op {
+                TraceOp::Prev => self.move_cursor(ReadMode::Prev)?,
+                TraceOp::Current => self.move_cursor(ReadMode::Current)?,
+                TraceOp::Next => self.move_cursor(ReadMode::Next)?,
+                TraceOp::Random(draw) if self.index().is_some() => match self.index_entry(draw)? {
+                    Some((start, end)) => {
+                        self.set_current_line(start, end);
+                        true
+                    }
+                    None => false,
+                },
+                TraceOp::Random(draw) if draw < self.file_size => {
+                    self.align_to_line(draw)?;
+                    true
+                }
+                TraceOp::Random(_) => false,
+            };
+
+            let to = Some((self.current_start_line_offset, self.current_end_line_offset))
+                .filter(|_| moved);
+            if to != step.to {
+                return Err(Error::new(
+                    ErrorKind::InvalidData,
+                    format!("The replay diverged from the trace at step {}", i),
+                ));
+            }
+            lines.push(if moved {
+                Some(self.read_current_line()?)
+            } else {
+                None
+            });
+        }
+        Ok(lines)
+    }
+
+    pub(crate) fn record_step(&mut self, mode: &ReadMode, from: (u64, u64, bool), moved: bool) {
+        let op = match mode {
+            ReadMode::Prev => TraceOp::Prev,
+            ReadMode::Current => TraceOp::Current,
+            ReadMode::Next => TraceOp::Next,
+            #[cfg(feature = "rand")]
+            ReadMode::Random => TraceOp::Random(self.random_draw),
+            ReadMode::Containing => return,
+        };
+        let to =
+            Some((self.current_start_line_offset, self.current_end_line_offset)).filter(|_| moved);
+
+        if let Some(trace) = self.trace.as_mut() {
+            trace.steps.push(TraceStep { op, from, to });
+        }
+    }
+}
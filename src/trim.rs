@@ -0,0 +1,49 @@
+use crate::EasyReader;
+use std::{io::prelude::*, ops::Range};
+
+/// What to trim off the lines returned, see `EasyReader::trim()`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Trim {
+    /// Nothing, the lines are returned as they are in the file.
+    #[default]
+    None,
+    /// A trailing CR, e.g. left by CRLF terminators with another `delimiter()`.
+    CarriageReturn,
+    /// The trailing whitespace.
+    End,
+    /// The leading and trailing whitespace.
+    Both,
+}
+
+impl<R: Read + Seek> EasyReader<R> {
+    /// Trims the lines returned (by the line reads, the iterators, the searches...): their
+    /// trailing CR or whitespace, or their whitespace at both ends. Only ASCII whitespace is
+    /// trimmed. The lines returned with their terminators (see `keep_terminators()`) aren't,
+    /// nor are their offsets: they're still those of the lines of the file.
+    pub fn trim(&mut self, trim: Trim) -> &mut Self {
+        self.trim = trim;
+        self
+    }
+
+    // Trims the line in `buffer[from..]`
+    pub(crate) fn trim_line(&self, buffer: &mut Vec<u8>, from: usize) {
+        let kept = trimmed(&buffer[from..], self.trim);
+        buffer.truncate(from + kept.end);
+        buffer.drain(from..from + kept.start);
+    }
+}
+
+// The part of `line` left once trimmed
+pub(crate) fn trimmed(line: &[u8], trim: Trim) -> Range<usize> {
+    let is_space = |byte: &&u8| byte.is_ascii_whitespace();
+    let end = match trim {
+        Trim::None => line.len(),
+        Trim::CarriageReturn => line.len() - line.ends_with(b"\r") as usize,
+        Trim::End | Trim::Both => line.len() - line.iter().rev().take_while(is_space).count(),
+    };
+    let start = match trim {
+        Trim::Both => line[..end].iter().take_while(is_space).count(),
+        _ => 0,
+    };
+    start..end
+}
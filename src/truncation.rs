@@ -0,0 +1,55 @@
+use crate::{EasyReader, ReaderError};
+use std::io::{self, prelude::*};
+
+/// What to do when the file is found shorter than it was, see `EasyReader::truncation_policy()`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TruncationPolicy {
+    /// Starts over from the BOF, like after a rotation.
+    StartOver,
+    /// Jumps to the new EOF, to only read what gets appended from now on.
+    Eof,
+    /// Fails with a [`ReaderError::Truncated`] error (once, the cursor being moved to the BOF).
+    Error,
+}
+
+impl<R: Read + Seek> EasyReader<R> {
+    /// Checks the file size before every read, applying `policy` as soon as it shrinks.
+    ///
+    /// Without a policy, a truncation is only detected by `reopen()` (and `refresh()`),
+    /// which start over from the BOF.
+    pub fn truncation_policy(&mut self, policy: TruncationPolicy) -> &mut Self {
+        self.truncation_policy = Some(policy);
+        self
+    }
+
+    pub(crate) fn check_truncation(&mut self) -> io::Result<()> {
+        let file_size = self.seek_file_size()?;
+        if file_size < self.file_size {
+            self.truncated(file_size)?;
+        }
+        Ok(())
+    }
+
+    pub(crate) fn truncated(&mut self, file_size: u64) -> io::Result<()> {
+        let previous_size = self.file_size;
+        match self
+            .truncation_policy
+            .unwrap_or(TruncationPolicy::StartOver)
+        {
+            TruncationPolicy::StartOver => self.start_over(file_size),
+            TruncationPolicy::Eof => {
+                self.start_over(file_size)?;
+                self.eof();
+                Ok(())
+            }
+            TruncationPolicy::Error => {
+                self.start_over(file_size)?;
+                Err(ReaderError::Truncated {
+                    previous_size,
+                    size: file_size,
+                }
+                .into())
+            }
+        }
+    }
+}
@@ -0,0 +1,60 @@
+#[cfg(feature = "index")]
+use crate::line_index::LineIndex;
+use crate::EasyReader;
+use std::{
+    convert::TryFrom,
+    io::{self, prelude::*},
+    ops::Range,
+};
+
+impl<R: Read + Seek> EasyReader<R> {
+    /// Narrows the reader to the (zero-based) line numbers `lines`, e.g. to work on a shard
+    /// of a dataset: all the reads (in both directions, at random, by line number...) stay
+    /// within them, as if the file held nothing else, and `refresh()` doesn't grow past them.
+    /// Offsets are relative to the start of the view, see `header_size()`. The cursor moves to
+    /// the BOF.
+    ///
+    /// The lines are counted in the current view, if any: a view can only be narrowed. With an
+    /// index its lines are found right away and the index of the view is sliced out of it,
+    /// otherwise they're found by counting the lines up to them. Use a `fork()` per view to
+    /// keep the reader over the whole file.
+    pub fn view_lines(&mut self, lines: Range<u64>) -> io::Result<&mut Self> {
+        let start = self.line_start_or_eof(lines.start)?;
+        let end = self.line_start_or_eof(lines.end.max(lines.start))?;
+
+        // The index of the lines of the view, from its start
+        #[cfg(feature = "index")]
+        let line_starts = match self.index() {
+            Some(line_starts) if end > start => {
+                let mut view_starts = LineIndex::new(line_starts.is_compressed());
+                for line in lines.start as usize..line_starts.partition_point(end - 1) {
+                    view_starts.push(line_starts.get(line).unwrap_or(end) - start);
+                }
+                self.drop_index();
+                Some(view_starts)
+            }
+            _ => None,
+        };
+
+        self.header_size += start;
+        self.view_end = Some(self.header_size + (end - start));
+        self.pending_offset = None;
+        self.start_over(end - start)?;
+        #[cfg(feature = "index")]
+        if let Some(line_starts) = line_starts {
+            self.set_index(line_starts)?;
+        }
+        Ok(self)
+    }
+
+    // The start of the `line`-th line, the file size if there's none
+    fn line_start_or_eof(&mut self, line: u64) -> io::Result<u64> {
+        let start = match self.index() {
+            Some(line_starts) => usize::try_from(line)
+                .ok()
+                .and_then(|line| line_starts.get(line)),
+            None => self.find_line_start(line)?,
+        };
+        Ok(start.unwrap_or(self.file_size))
+    }
+}
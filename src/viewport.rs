@@ -0,0 +1,116 @@
+use crate::LineCursor;
+use std::io;
+
+/// A fixed-height window over the lines of a [`LineCursor`] (e.g. an `EasyReader`), meant for
+/// pagers and TUIs.
+///
+/// The visible lines are cached: scrolling only reads the lines entering the window.
+pub struct Viewport<C: LineCursor> {
+    reader: C,
+    rows: usize,
+    lines: Vec<String>,
+    positions: Vec<C::Position>,
+}
+
+impl<C: LineCursor> Viewport<C> {
+    pub fn new(mut reader: C, rows: usize) -> io::Result<Self> {
+        reader.bof();
+        let mut viewport = Viewport {
+            reader,
+            rows: 0,
+            lines: Vec::new(),
+            positions: Vec::new(),
+        };
+        viewport.resize(rows)?;
+        Ok(viewport)
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn lines(&self) -> &[String] {
+        &self.lines
+    }
+
+    pub fn resize(&mut self, rows: usize) -> io::Result<&mut Self> {
+        self.rows = rows;
+        if self.lines.len() > rows {
+            self.lines.truncate(rows);
+            self.positions.truncate(rows);
+        } else {
+            let missing = rows - self.lines.len();
+            let added = self.push_bottom(missing)?;
+            // Near the EOF the window is filled upwards
+            self.push_top(missing - added)?;
+        }
+        Ok(self)
+    }
+
+    pub fn scroll_down(&mut self, n: usize) -> io::Result<usize> {
+        let added = self.push_bottom(n)?;
+        let excess = self.lines.len().saturating_sub(self.rows);
+        self.lines.drain(..excess);
+        self.positions.drain(..excess);
+        Ok(added)
+    }
+
+    pub fn scroll_up(&mut self, n: usize) -> io::Result<usize> {
+        let added = self.push_top(n)?;
+        self.lines.truncate(self.rows);
+        self.positions.truncate(self.rows);
+        Ok(added)
+    }
+
+    pub fn get_ref(&self) -> &C {
+        &self.reader
+    }
+
+    pub fn into_inner(self) -> C {
+        self.reader
+    }
+
+    fn push_bottom(&mut self, n: usize) -> io::Result<usize> {
+        match self.positions.last() {
+            Some(position) => self.reader.goto(position)?,
+            None => self.reader.bof(),
+        }
+
+        let mut added = 0;
+        while added < n {
+            match self.reader.next_line()? {
+                Some(line) => {
+                    self.lines.push(line);
+                    self.positions.push(self.reader.position());
+                    added += 1;
+                }
+                None => break,
+            }
+        }
+        Ok(added)
+    }
+
+    fn push_top(&mut self, n: usize) -> io::Result<usize> {
+        match self.positions.first() {
+            Some(position) => self.reader.goto(position)?,
+            None => self.reader.eof(),
+        }
+
+        let mut lines = Vec::new();
+        let mut positions = Vec::new();
+        while lines.len() < n {
+            match self.reader.prev_line()? {
+                Some(line) => {
+                    lines.push(line);
+                    positions.push(self.reader.position());
+                }
+                None => break,
+            }
+        }
+
+        let added = lines.len();
+        self.lines.splice(0..0, lines.into_iter().rev());
+        self.positions.splice(0..0, positions.into_iter().rev());
+        Ok(added)
+    }
+}
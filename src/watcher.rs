@@ -0,0 +1,128 @@
+use crate::{EasyReader, LineIndex, ReaderError};
+use std::{
+    fs::File,
+    io,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+/// Keeps the index of a growing file (e.g. a log) up to date and hands out indexed readers over it.
+///
+/// Only complete lines (terminated by a line terminator) are indexed, a partially written
+/// last line is picked up by the `update()` following its completion.
+pub struct IndexedWatcher {
+    path: PathBuf,
+    file: File,
+    delimiter: Option<u8>,
+    // An indexed reader over the complete lines (its file ending after the last one), which
+    // refresh() extends like it does for follow(). None as long as the file is empty.
+    reader: Option<EasyReader<File>>,
+}
+
+impl IndexedWatcher {
+    pub fn open<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let mut watcher = IndexedWatcher {
+            path: path.as_ref().to_path_buf(),
+            file: File::open(path)?,
+            delimiter: None,
+            reader: None,
+        };
+        watcher.update()?;
+        Ok(watcher)
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Terminates the lines with `byte` instead of LF, like `EasyReader::delimiter()`, in the
+    /// index and in the snapshots. The file is indexed again.
+    pub fn delimiter(&mut self, byte: u8) -> io::Result<&mut Self> {
+        self.delimiter = Some(byte);
+        self.reader = None;
+        self.update()?;
+        Ok(self)
+    }
+
+    /// Number of complete lines indexed so far.
+    pub fn lines_count(&self) -> usize {
+        self.line_starts()
+            .map_or(0, |line_starts| line_starts.len())
+    }
+
+    /// Indexes the lines appended since the last update, returns how many were added.
+    pub fn update(&mut self) -> io::Result<usize> {
+        let file_size = self.file.metadata()?.len();
+        let indexed_size = self.reader.as_ref().map_or(0, |reader| reader.file_size);
+        if file_size < indexed_size {
+            return Err(ReaderError::Truncated {
+                previous_size: indexed_size,
+                size: file_size,
+            }
+            .into());
+        }
+        if file_size == indexed_size {
+            return Ok(0);
+        }
+
+        let indexed_lines = self.lines_count();
+        let reader = match self.reader.as_mut() {
+            Some(reader) => {
+                reader.refresh()?;
+                reader
+            }
+            None => {
+                let mut reader = EasyReader::new(self.file.try_clone()?)?;
+                if let Some(byte) = self.delimiter {
+                    reader.delimiter(byte);
+                }
+                reader.build_index()?;
+                self.reader.insert(reader)
+            }
+        };
+        reader.forget_partial_line()?;
+        Ok(self.lines_count() - indexed_lines)
+    }
+
+    /// Returns an indexed reader over the lines complete at the time of the call.
+    ///
+    /// The snapshot has its own file handle and keeps seeing the same lines, no matter how
+    /// much the file grows afterwards. It shares the index of the watcher instead of copying
+    /// it: only the next `update()` copies it, if the snapshot is still around.
+    pub fn snapshot(&self) -> io::Result<EasyReader<File>> {
+        let reader = match self.reader.as_ref() {
+            Some(reader) if self.lines_count() > 0 => reader,
+            _ => return Err(ReaderError::EmptyFile.into()),
+        };
+
+        let mut snapshot = EasyReader::new(File::open(&self.path)?)?;
+        if let Some(byte) = self.delimiter {
+            snapshot.delimiter(byte);
+        }
+        snapshot.file_size = reader.file_size;
+        snapshot.share_index(reader);
+        Ok(snapshot)
+    }
+
+    fn line_starts(&self) -> Option<&LineIndex> {
+        self.reader.as_ref().and_then(|reader| reader.index())
+    }
+}
+
+impl EasyReader<File> {
+    // Leaves the last line out of the index and of the file if it isn't terminated yet: the
+    // next refresh() indexes it again from the line before it
+    fn forget_partial_line(&mut self) -> io::Result<()> {
+        let last = match self.index().and_then(LineIndex::last) {
+            Some(last) => last,
+            None => return Ok(()),
+        };
+        let end = self.line_end_before(last, self.file_size)?;
+        if self.terminator_after(end)?.is_empty() {
+            Arc::make_mut(&mut self.line_starts).pop();
+            self.file_size = last;
+            self.fingerprint_index()?;
+        }
+        Ok(())
+    }
+}